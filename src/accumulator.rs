@@ -0,0 +1,155 @@
+//! This module implements **Accumulator** that sums many full double width
+//! products and reduces the total modulo `m` only once in the end. It is
+//! useful for inner products over Z_m (dot products, polynomial evaluation)
+//! where reducing after every term is expensive.
+//!
+//! ```rust
+//! use bigi::{Bigi, Accumulator};
+//!
+//! let m = Bigi::<4>::from(97);
+//! let mut acc = Accumulator::<4>::new();
+//! acc.add_mul(&Bigi::<4>::from(50), &Bigi::<4>::from(60));
+//! acc.add_mul(&Bigi::<4>::from(70), &Bigi::<4>::from(80));
+//! assert_eq!(acc.reduce(&m), Bigi::<4>::from(64));  // 8600 % 97
+//! ```
+
+use crate::base::Bigi;
+
+
+/// Sum of double width integers with an overflow counter.
+#[derive(Debug, Clone, Copy)]
+pub struct Accumulator<const N: usize> {
+    lo: Bigi<N>,
+    hi: Bigi<N>,
+    carry: u64,
+}
+
+
+impl<const N: usize> Accumulator<N> {
+    /// Creates an empty accumulator.
+    pub fn new() -> Self {
+        Self { lo: Bigi::<N>::new(), hi: Bigi::<N>::new(), carry: 0 }
+    }
+
+    /// Checks if the accumulated sum is zero.
+    pub fn is_zero(&self) -> bool {
+        self.carry == 0 && self.lo.is_zero() && self.hi.is_zero()
+    }
+
+    /// Resets the accumulated sum to zero.
+    pub fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Adds an integer to the sum.
+    pub fn add(&mut self, a: &Bigi<N>) {
+        let c = add_carry(&mut self.lo, a, false);
+        if c {
+            let c = add_carry(&mut self.hi, &Bigi::<N>::new(), true);
+            self.carry += c as u64;
+        }
+    }
+
+    /// Adds the full product `a * b` to the sum.
+    pub fn add_mul(&mut self, a: &Bigi<N>, b: &Bigi<N>) {
+        let (lo, hi) = a.multiply_overflowing(b);
+        let c = add_carry(&mut self.lo, &lo, false);
+        let c = add_carry(&mut self.hi, &hi, c);
+        self.carry += c as u64;
+    }
+
+    /// Gets the accumulated sum modulo `m`.
+    pub fn reduce(&self, m: &Bigi<N>) -> Bigi<N> {
+        // Reducing limb blocks from the top so every partial quotient fits
+        let mut top = Bigi::<N>::from(self.carry);
+        top.divide(m);
+        let mut mid = self.hi;
+        mid.divide_overflowing(m, &top);
+        let mut res = self.lo;
+        res.divide_overflowing(m, &mid);
+        res
+    }
+}
+
+
+impl<const N: usize> Default for Accumulator<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
+fn add_carry<const N: usize>(a: &mut Bigi<N>, b: &Bigi<N>, c: bool) -> bool {
+    let mut fw = c;
+    for i in 0..N {
+        let (s1, c1) = a.digits[i].overflowing_add(b.digits[i]);
+        let (s2, c2) = s1.overflowing_add(fw as u64);
+        a.digits[i] = s2;
+        fw = c1 || c2;
+    }
+    fw
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::bigi;
+    use super::*;
+    use crate::prime::{gen_prime, add_mod, mul_mod};
+    use test::Bencher;
+
+    #[test]
+    fn test_add_mul() {
+        let mut rng = rand::thread_rng();
+        let m = gen_prime::<_, 4>(&mut rng, 256);
+        let mut acc = Accumulator::<4>::new();
+        let mut expected = bigi![4; 0];
+        for _ in 0..50 {
+            let a = Bigi::<4>::gen_random(&mut rng, 256, false);
+            let b = Bigi::<4>::gen_random(&mut rng, 256, false);
+            acc.add_mul(&a, &b);
+            expected = add_mod(&expected, &mul_mod(&(a % &m), &(b % &m), &m), &m);
+        }
+        assert_eq!(acc.reduce(&m), expected);
+    }
+
+    #[test]
+    fn test_carry() {
+        let max = bigi![2; u64::MAX, u64::MAX];
+        let mut acc = Accumulator::<2>::new();
+        for _ in 0..3 {
+            acc.add_mul(&max, &max);
+        }
+        acc.add(&max);
+        assert_eq!(acc.carry, 2);
+        // 3 * (2^128 - 1)^2 + 2^128 - 1 modulo 1000003
+        assert_eq!(acc.reduce(&bigi![2; 1000003]), bigi![2; 454819]);
+    }
+
+    #[test]
+    fn test_add() {
+        let mut acc = Accumulator::<2>::new();
+        assert!(acc.is_zero());
+        acc.add(&bigi![2; u64::MAX, u64::MAX]);
+        acc.add(&bigi![2; 2]);
+        assert_eq!(acc.lo, bigi![2; 1]);
+        assert_eq!(acc.hi, bigi![2; 1]);
+        acc.clear();
+        assert!(acc.is_zero());
+    }
+
+    #[bench]
+    fn bench_dot_256_x16(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let m = gen_prime::<_, 4>(&mut rng, 256);
+        let xs: Vec<Bigi<4>> = (0..16)
+            .map(|_| Bigi::<4>::gen_random(&mut rng, 255, false)).collect();
+        bencher.iter(|| {
+            let mut acc = Accumulator::<4>::new();
+            for x in xs.iter() {
+                acc.add_mul(x, x);
+            }
+            acc.reduce(&m)
+        });
+    }
+}
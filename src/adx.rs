@@ -0,0 +1,155 @@
+//! This module implements the carry chains of addition, subtraction and
+//! multiplication with x86_64 intrinsics (enabled by the feature `adx`), so
+//! they are compiled into `adc`, `sbb` and `mulx` instructions instead of
+//! the comparisons that the compiler makes from `overflowing_add`. `mulx`
+//! and `adcx` are used if the target has BMI2 and ADX (for example, with
+//! `-C target-cpu=native`), otherwise the ordinary `mul` and `adc` are
+//! emitted.
+//!
+//! The rows of multiplication are used for the full products
+//! (`widening_mul` and the base case of Karatsuba algorithm) only: the
+//! truncated products of variable length are faster with the portable
+//! loops.
+
+use std::arch::x86_64;
+
+
+/// Adds `b` to `a` of the same length and returns the carry.
+#[inline]
+pub(crate) fn add_assign(a: &mut [u64], b: &[u64]) -> bool {
+    let mut carry = 0;
+    for (x, y) in a.iter_mut().zip(b.iter()) {
+        carry = adc(carry, *x, *y, x);
+    }
+    carry != 0
+}
+
+
+/// Writes `a + b` into `dst`, all of the same length, and returns the carry.
+#[inline]
+pub(crate) fn add_into(dst: &mut [u64], a: &[u64], b: &[u64]) -> bool {
+    let mut carry = 0;
+    for ((z, x), y) in dst.iter_mut().zip(a.iter()).zip(b.iter()) {
+        carry = adc(carry, *x, *y, z);
+    }
+    carry != 0
+}
+
+
+/// Subtracts `b` from `a` of the same length and returns the borrow.
+#[inline]
+pub(crate) fn sub_assign(a: &mut [u64], b: &[u64]) -> bool {
+    let mut borrow = 0;
+    for (x, y) in a.iter_mut().zip(b.iter()) {
+        borrow = x86_64::_subborrow_u64(borrow, *x, *y, x);
+    }
+    borrow != 0
+}
+
+
+/// Writes `a - b` into `dst`, all of the same length, and returns
+/// the borrow.
+#[inline]
+pub(crate) fn sub_into(dst: &mut [u64], a: &[u64], b: &[u64]) -> bool {
+    let mut borrow = 0;
+    for ((z, x), y) in dst.iter_mut().zip(a.iter()).zip(b.iter()) {
+        borrow = x86_64::_subborrow_u64(borrow, *x, *y, z);
+    }
+    borrow != 0
+}
+
+
+/// Adds `a * x` to `res` of the same length as `a` and returns the digit
+/// that is carried out. It is a row of the schoolbook multiplication.
+#[inline]
+pub(crate) fn mul_add_row(res: &mut [u64], a: &[u64], x: u64) -> u64 {
+    // The high digit of the previous product is added to the low digit of
+    // the current one in a separate carry chain
+    let mut fw: u64 = 0;
+    let mut carry1 = 0;
+    let mut carry2 = 0;
+    for (z, y) in res.iter_mut().zip(a.iter()) {
+        let (lo, hi) = mulx(*y, x);
+        let mut sum = 0;
+        carry1 = adc(carry1, lo, fw, &mut sum);
+        carry2 = adc(carry2, sum, *z, z);
+        fw = hi;
+    }
+    // The high digit is less than 2^64 - 1, so the carries fit into it
+    fw + carry1 as u64 + carry2 as u64
+}
+
+
+#[inline(always)]
+fn adc(carry: u8, a: u64, b: u64, out: &mut u64) -> u8 {
+    #[cfg(target_feature = "adx")]
+    unsafe {
+        x86_64::_addcarryx_u64(carry, a, b, out)
+    }
+    #[cfg(not(target_feature = "adx"))]
+    {
+        x86_64::_addcarry_u64(carry, a, b, out)
+    }
+}
+
+
+#[inline(always)]
+fn mulx(a: u64, b: u64) -> (u64, u64) {
+    #[cfg(target_feature = "bmi2")]
+    {
+        let mut hi = 0;
+        let lo = unsafe { x86_64::_mulx_u64(a, b, &mut hi) };
+        (lo, hi)
+    }
+    #[cfg(not(target_feature = "bmi2"))]
+    {
+        let product = (a as u128) * (b as u128);
+        (product as u64, (product >> 64) as u64)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::Bigi;
+    use super::*;
+
+    #[test]
+    fn test_add_sub() {
+        let mut x = [u64::MAX, u64::MAX, 5];
+        assert!(!add_assign(&mut x, &[1, 0, 0]));
+        assert_eq!(x, [0, 0, 6]);
+        assert!(!sub_assign(&mut x, &[1, 0, 0]));
+        assert_eq!(x, [u64::MAX, u64::MAX, 5]);
+        assert!(add_assign(&mut x, &[1, 0, u64::MAX - 5]));
+        assert_eq!(x, [0, 0, 0]);
+        assert!(sub_assign(&mut x, &[0, 1, 0]));
+        assert_eq!(x, [0, u64::MAX, u64::MAX]);
+
+        let mut z = [0; 2];
+        assert!(!add_into(&mut z, &[u64::MAX, 1], &[1, 2]));
+        assert_eq!(z, [0, 4]);
+        assert!(sub_into(&mut z, &[0, 4], &[1, 5]));
+        assert_eq!(z, [u64::MAX, u64::MAX - 1]);
+    }
+
+    #[test]
+    fn test_mul_add_row() {
+        let mut res = [u64::MAX; 4];
+        assert_eq!(mul_add_row(&mut res, &[u64::MAX; 4], u64::MAX), u64::MAX);
+        assert_eq!(res, [0, u64::MAX, u64::MAX, u64::MAX]);
+
+        let mut rng = rand::thread_rng();
+        for bits in [0, 1, 64, 100, 256] {
+            let a = Bigi::<4>::gen_random(&mut rng, bits, false);
+            let c = Bigi::<4>::gen_random(&mut rng, 256, false);
+            let x: u64 = rand::Rng::gen(&mut rng);
+            let mut res = c.digits;
+            let fw = mul_add_row(&mut res, &a.digits, x);
+            let mut expected = Bigi::<8>::from(&a) * x + &Bigi::<8>::from(&c);
+            assert_eq!(expected.digits[..4], res);
+            expected >>= 256;
+            assert_eq!(expected, Bigi::<8>::from(fw));
+        }
+    }
+}
@@ -0,0 +1,269 @@
+//! This module implements
+//! [Barrett reduction](https://en.wikipedia.org/wiki/Barrett_reduction).
+//! The quotient of the division by the modulus is estimated by
+//! the multiplication by the precomputed reciprocal, so no division is
+//! performed after the creation.
+//!
+//! ```rust
+//! use bigi::{Bigi, BarrettAlg};
+//!
+//! let alg = BarrettAlg::new(&Bigi::<4>::from(23));
+//!
+//! let a = Bigi::<4>::from(6);
+//! let b = Bigi::<4>::from(9);
+//!
+//! assert_eq!(alg.mul(&a, &b), Bigi::<4>::from(8));  // 8 = 6 * 9 % 23
+//! assert_eq!(alg.powmod(&a, &b), Bigi::<4>::from(16));  // 16 = 6**9 % 23
+//! ```
+
+use std::cmp;
+use crate::base::Bigi;
+use crate::operations::{mul_slices, divide_slices, cmp_slices, sub_slices};
+use crate::prime::reduce_wide;
+
+
+/// Barrett reduction modulo `m`. It keeps `mu = floor(2^(128n) / m)` where
+/// `n` is the number of digits of `m`, and reduces integers up to
+/// `2^(128n)` (in particular, the products of reduced integers) with two
+/// multiplications.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BarrettAlg<const N: usize> {
+    m: Bigi<N>,
+    mu: [Bigi<N>; 2],
+}
+
+
+impl<const N: usize> BarrettAlg<N> {
+    /// Creates a Barrett reduction instance. The modulus `m` must be nonzero.
+    pub fn new(m: &Bigi<N>) -> Self {
+        assert!(!m.is_zero(), "Modulus must be nonzero");
+        let n = m.get_order();
+
+        // b^(2n) / m has at most n + 2 digits, so it fits into 2N digits
+        // except for a single digit modulus that is reduced in u128
+        let mut mu = [Bigi::<N>::new(); 2];
+        if n > 1 {
+            let mut u = vec![0; (n << 1) + 1];
+            u[n << 1] = 1;
+            let mut q = vec![0; n + 2];
+            let mut scratch = vec![0; 11 * n + 6];
+            divide_slices(&mut u, &m.digits[..n], &mut q, &mut scratch);
+            for (i, digit) in q.into_iter().enumerate() {
+                mu[i / N].digits[i % N] = digit;
+            }
+        }
+
+        Self { m: *m, mu }
+    }
+
+    /// The modulus.
+    pub fn modulus(&self) -> &Bigi<N> {
+        &self.m
+    }
+
+    /// Calculates `x % m`.
+    /// ```rust
+    /// use bigi::{bigi, Bigi, BarrettAlg};
+    ///
+    /// let alg = BarrettAlg::new(&bigi![2; 1000]);
+    /// assert_eq!(alg.reduce(&bigi![2; 123456]), bigi![2; 456]);
+    /// ```
+    pub fn reduce(&self, x: &Bigi<N>) -> Bigi<N> {
+        self.reduce_wide(&Bigi::<N>::new(), x)
+    }
+
+    /// Calculates `(hi * 2^(64 * N) + lo) % m` for a double width integer
+    /// given by its high and low halves, for example, the result of
+    /// `widening_mul`. Integers not less than `2^(128n)` are
+    /// reduced by the ordinary division.
+    /// ```rust
+    /// use bigi::{bigi, Bigi, BarrettAlg};
+    ///
+    /// let alg = BarrettAlg::new(&bigi![2; 1000, 1]);
+    /// let (lo, hi) = bigi![2; 0, 1 << 63].widening_mul(&bigi![2; 0, 6]);
+    /// assert_eq!(alg.reduce_wide(&hi, &lo), bigi::reduce_wide(&hi, &lo, alg.modulus()));
+    /// ```
+    pub fn reduce_wide(&self, hi: &Bigi<N>, lo: &Bigi<N>) -> Bigi<N> {
+        let n = self.m.get_order();
+        let wide = [lo.digits, hi.digits];
+        let x = wide.as_flattened();
+        if x[(n << 1)..].iter().any(|digit| *digit != 0) {
+            return reduce_wide(hi, lo, &self.m);
+        }
+
+        if n == 1 {
+            let x = (x[0] as u128) | ((x[1] as u128) << 64);
+            return Bigi::<N>::from((x % self.m.digits[0] as u128) as u64);
+        }
+
+        // q3 = floor(floor(x / b^(n - 1)) * mu / b^(n + 1)) where b = 2^64,
+        // it is less than the quotient by at most 2
+        let mu = self.mu.map(|half| half.digits);
+        let mu = mu.as_flattened();
+        let mu = &mu[..(n + 2)];
+        let mut scratch = [[0; N]; 5];
+        let scratch = scratch.as_flattened_mut();
+        let mut q2 = [[0; N]; 4];
+        let q2 = &mut q2.as_flattened_mut()[..(2 * n + 3)];
+        mul_slices(&x[(n - 1)..(n << 1)], mu, q2, scratch);
+        let q3 = &q2[(n + 1)..(2 * n + 2)];
+
+        // r = x - q3 * m modulo b^(n + 1)
+        let mut r2 = [[0; N]; 4];
+        let r2 = &mut r2.as_flattened_mut()[..(2 * n + 1)];
+        mul_slices(q3, &self.m.digits[..n], r2, scratch);
+        let mut r = [[0; N]; 2];
+        let r = &mut r.as_flattened_mut()[..(n + 1)];
+        r.copy_from_slice(&x[..(n + 1)]);
+        sub_slices(r, &r2[..(n + 1)]);
+
+        while cmp_slices(r, &self.m.digits[..n]) != cmp::Ordering::Less {
+            sub_slices(r, &self.m.digits[..n]);
+        }
+
+        let mut res = Bigi::<N>::new();
+        res.digits[..n].copy_from_slice(&r[..n]);
+        res
+    }
+
+    /// Modular multiplication `(x * y) % m`.
+    pub fn mul(&self, x: &Bigi<N>, y: &Bigi<N>) -> Bigi<N> {
+        let (lo, hi) = x.widening_mul(y);
+        self.reduce_wide(&hi, &lo)
+    }
+
+    /// Modular square `(x * x) % m`.
+    pub fn square(&self, x: &Bigi<N>) -> Bigi<N> {
+        let (lo, hi) = x.square_overflowing();
+        self.reduce_wide(&hi, &lo)
+    }
+
+    /// Modular exponentiation `(x^e) % m`.
+    pub fn powmod(&self, x: &Bigi<N>, e: &Bigi<N>) -> Bigi<N> {
+        let mut res = self.reduce(&Bigi::<N>::from(1));
+        let mut x2 = self.reduce(x);
+        for bit in e.bits() {
+            if bit {
+                res = self.mul(&res, &x2);
+            }
+            x2 = self.square(&x2);
+        }
+        res
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::bigi;
+    use super::*;
+    use crate::prime::mul_mod;
+    use test::Bencher;
+
+    #[test]
+    fn test_new() {
+        let alg = BarrettAlg::new(&bigi![2; 23]);
+        assert_eq!(alg.mu, [bigi![2; 0]; 2]);
+
+        // 2^256 / 2^64
+        let alg = BarrettAlg::new(&bigi![2; 0, 1]);
+        assert_eq!(alg.mu, [bigi![2; 0, 0], bigi![2; 0, 1]]);
+
+        let m = bigi![4; 12345, 678];
+        let alg = BarrettAlg::new(&m);
+        assert_eq!(Bigi::<8>::from(&alg.mu[0]), (bigi![8; 1] << 256) / &Bigi::<8>::from(&m));
+        assert_eq!(alg.mu[1], bigi![4; 0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Modulus must be nonzero")]
+    fn test_new_zero() {
+        BarrettAlg::new(&bigi![4; 0]);
+    }
+
+    #[test]
+    fn test_reduce() {
+        let mut rng = rand::thread_rng();
+        for bits in [1, 2, 63, 64, 65, 127, 128, 129, 200, 255, 256] {
+            let m = Bigi::<4>::gen_random(&mut rng, bits, true);
+            let alg = BarrettAlg::new(&m);
+            for _ in 0..20 {
+                let x = Bigi::<4>::gen_random(&mut rng, 256, false);
+                let hi = Bigi::<4>::gen_random(&mut rng, 256, false);
+                assert_eq!(alg.reduce(&x), x % &m);
+                assert_eq!(alg.reduce_wide(&hi, &x), reduce_wide(&hi, &x, &m));
+                let y = x % &m;
+                let z = hi % &m;
+                assert_eq!(alg.mul(&y, &z), mul_mod(&y, &z, &m));
+                assert_eq!(alg.square(&y), mul_mod(&y, &y, &m));
+            }
+            // The largest product
+            let x = m - &bigi![4; 1];
+            assert_eq!(alg.mul(&x, &x), bigi![4; 1] % &m);
+        }
+
+        // Moduli with the digits of all ones and zeros
+        for m in [bigi![2; u64::MAX, u64::MAX], bigi![2; 0, 1], bigi![2; 1, 1],
+                  bigi![2; u64::MAX], bigi![2; 0, u64::MAX], bigi![2; 2]] {
+            let alg = BarrettAlg::new(&m);
+            for x in [bigi![2; 0], bigi![2; 1], m - &bigi![2; 1], bigi![2; u64::MAX, 5]] {
+                let x = x % &m;
+                for y in [bigi![2; 0], bigi![2; 1], m - &bigi![2; 1], bigi![2; 7, 3]] {
+                    let y = y % &m;
+                    assert_eq!(alg.mul(&x, &y), mul_mod(&x, &y, &m));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_powmod() {
+        let alg = BarrettAlg::new(&bigi![4; 23]);
+        assert_eq!(alg.powmod(&bigi![4; 3], &bigi![4; 4]), bigi![4; 12]);
+        assert_eq!(alg.powmod(&bigi![4; 3], &bigi![4; 0]), bigi![4; 1]);
+
+        let mut rng = rand::thread_rng();
+        let m = Bigi::<4>::gen_random(&mut rng, 256, true);
+        let alg = BarrettAlg::new(&m);
+        let x = Bigi::<4>::gen_random(&mut rng, 256, false);
+        let e = Bigi::<4>::gen_random(&mut rng, 256, false);
+        assert_eq!(alg.powmod(&x, &e), x.powmod(&e, &m));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serde() {
+        use serde_test::{Token, assert_tokens};
+
+        assert_tokens(&BarrettAlg::new(&bigi![2; 1, 1]), &[
+            Token::Struct { name: "BarrettAlg", len: 2 },
+            Token::Str("m"), Token::Seq { len: Some(2) }, Token::U64(1), Token::U64(1), Token::SeqEnd,
+            Token::Str("mu"), Token::Tuple { len: 2 },
+            // 2^256 / (2^64 + 1) = 2^192 - 2^128 + 2^64 - 1
+            Token::Seq { len: Some(1) }, Token::U64(u64::MAX), Token::SeqEnd,
+            Token::Seq { len: Some(1) }, Token::U64(u64::MAX), Token::SeqEnd,
+            Token::TupleEnd,
+            Token::StructEnd,
+        ]);
+    }
+
+    #[bench]
+    fn bench_mul_256(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let m = Bigi::<4>::gen_random(&mut rng, 256, true);
+        let alg = BarrettAlg::new(&m);
+        let x = Bigi::<4>::gen_random(&mut rng, 256, false) % &m;
+        let y = Bigi::<4>::gen_random(&mut rng, 256, false) % &m;
+        bencher.iter(|| alg.mul(&x, &y));
+    }
+
+    #[bench]
+    fn bench_mul_mod_256(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let m = Bigi::<4>::gen_random(&mut rng, 256, true);
+        let x = Bigi::<4>::gen_random(&mut rng, 256, false) % &m;
+        let y = Bigi::<4>::gen_random(&mut rng, 256, false) % &m;
+        bencher.iter(|| mul_mod(&x, &y, &m));
+    }
+}
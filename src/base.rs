@@ -1,6 +1,6 @@
 //! This module implements basics for Bigi.
 
-use std::cmp;
+use std::{cmp, hash};
 
 
 /// Type for multiprecision integers.
@@ -11,6 +11,14 @@ pub struct Bigi<const N: usize> {
 
 
 impl<const N: usize> Bigi<N> {
+    /// The largest integer `2^(64N) - 1`.
+    /// ```rust
+    /// use bigi::Bigi;
+    ///
+    /// assert_eq!(Bigi::<2>::MAX.to_vec(), [u64::MAX, u64::MAX]);
+    /// ```
+    pub const MAX: Self = Self { digits: [u64::MAX; N] };
+
     /// Creates a zero ingeter.
     /// ```rust
     /// use bigi::Bigi;
@@ -48,6 +56,43 @@ impl<const N: usize> Bigi<N> {
 }
 
 
+impl<const N: usize> Default for Bigi<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
+impl<const N: usize> hash::Hash for Bigi<N> {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.digits.hash(state);
+    }
+}
+
+
+/// The integer is serialized as a sequence of its significant *u64* digits
+/// from the least significant one, so the data does not depend on `N`.
+#[cfg(feature = "serde")]
+impl<const N: usize> serde::Serialize for Bigi<N> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let order = self.digits.iter().rposition(|d| *d != 0).map_or(0, |i| i + 1);
+        serializer.collect_seq(self.digits[..order].iter())
+    }
+}
+
+
+#[cfg(feature = "serde")]
+impl<'de, const N: usize> serde::Deserialize<'de> for Bigi<N> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let digits = Vec::<u64>::deserialize(deserializer)?;
+        if digits.iter().skip(N).any(|d| *d != 0) {
+            return Err(serde::de::Error::custom("Integer overflow"));
+        }
+        Ok(Self::from_vec(&digits))
+    }
+}
+
+
 /// A macros to create an integer by listing its *u64* digits.
 /// ```rust
 /// use bigi::{bigi, Bigi};
@@ -83,6 +128,45 @@ mod tests {
         assert_eq!(a.to_vec(), vec![2, 4, 0, 11, 5, 87, 1, 111]);
     }
 
+    #[test]
+    fn test_default_hash() {
+        use std::collections::{BTreeSet, HashSet};
+
+        assert_eq!(Bigi::<4>::default(), Bigi::<4>::new());
+
+        let xs = [bigi![4; 5, 1], bigi![4; 7], bigi![4; 0, 0, 1], bigi![4; 7]];
+        let set: HashSet<Bigi<4>> = xs.iter().cloned().collect();
+        assert_eq!(set.len(), 3);
+        assert!(set.contains(&bigi![4; 7]));
+        let sorted: Vec<Bigi<4>> = xs.iter().cloned().collect::<BTreeSet<_>>()
+                                     .into_iter().collect();
+        assert_eq!(sorted, vec![bigi![4; 7], bigi![4; 5, 1], bigi![4; 0, 0, 1]]);
+        assert_eq!(xs.iter().max(), Some(&bigi![4; 0, 0, 1]));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serde() {
+        use serde_test::{Token, assert_tokens, assert_de_tokens, assert_de_tokens_error};
+
+        assert_tokens(&bigi![4; 5, 0, 7], &[
+            Token::Seq { len: Some(3) },
+            Token::U64(5), Token::U64(0), Token::U64(7),
+            Token::SeqEnd,
+        ]);
+        assert_tokens(&bigi![4; 0], &[Token::Seq { len: Some(0) }, Token::SeqEnd]);
+        assert_de_tokens(&bigi![2; 1, 2], &[
+            Token::Seq { len: Some(4) },
+            Token::U64(1), Token::U64(2), Token::U64(0), Token::U64(0),
+            Token::SeqEnd,
+        ]);
+        assert_de_tokens_error::<Bigi<2>>(&[
+            Token::Seq { len: Some(3) },
+            Token::U64(1), Token::U64(2), Token::U64(3),
+            Token::SeqEnd,
+        ], "Integer overflow");
+    }
+
     #[bench]
     fn bench_macro_bigi(bencher: &mut Bencher) {
         bencher.iter(|| bigi![8; 2, 4, 0, 11, 5, 87, 1, 111]);
@@ -0,0 +1,191 @@
+//! This module implements batch algorithms over many integers:
+//! [product and remainder trees](https://cr.yp.to/arith/scaledmod-20040820.pdf)
+//! and [batch GCD](https://facthacks.cr.yp.to/batchgcd.html).
+//!
+//! The tree nodes are products of many leaves, so they are stored in a wider
+//! type `Bigi<M>` that must be large enough to keep the product of all the
+//! leaves.
+//!
+//! ```rust
+//! use bigi::{Bigi, remainder_tree};
+//!
+//! let x = Bigi::<8>::from(1000);
+//! let moduli = [Bigi::<2>::from(7), Bigi::<2>::from(11), Bigi::<2>::from(13)];
+//! assert_eq!(
+//!     remainder_tree(&x, &moduli),
+//!     vec![Bigi::<2>::from(6), Bigi::<2>::from(10), Bigi::<2>::from(12)]
+//! );
+//! ```
+
+use crate::base::Bigi;
+use crate::prime::euclidean;
+
+
+/// Binary tree of products: the leaves are given integers and every node
+/// is the product of its children.
+pub struct ProductTree<const M: usize> {
+    levels: Vec<Vec<Bigi<M>>>,
+}
+
+
+impl<const M: usize> ProductTree<M> {
+    /// Builds the product tree over the given leaves.
+    /// ```rust
+    /// use bigi::{Bigi, ProductTree};
+    ///
+    /// let leaves = [Bigi::<4>::from(3), Bigi::<4>::from(5), Bigi::<4>::from(7)];
+    /// let tree = ProductTree::new(&leaves);
+    /// assert_eq!(tree.root(), &Bigi::<4>::from(105));
+    /// ```
+    pub fn new(leaves: &[Bigi<M>]) -> Self {
+        assert!(!leaves.is_empty(), "Empty leaves");
+        let mut levels = vec![leaves.to_vec()];
+        while levels[levels.len() - 1].len() > 1 {
+            let level = levels[levels.len() - 1].chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => *a * b,
+                    _ => pair[0],
+                })
+                .collect();
+            levels.push(level);
+        }
+        Self { levels }
+    }
+
+    /// Gets the product of all the leaves.
+    pub fn root(&self) -> &Bigi<M> {
+        &self.levels[self.levels.len() - 1][0]
+    }
+
+    /// Gets the leaves of the tree.
+    pub fn leaves(&self) -> &[Bigi<M>] {
+        &self.levels[0]
+    }
+
+    /// Calculates `x % leaf` for every leaf going down the tree from the root,
+    /// so that each node reduces the remainder of its parent.
+    pub fn remainders(&self, x: &Bigi<M>) -> Vec<Bigi<M>> {
+        self.remainders_by(x, |node| *node)
+    }
+
+    /// Calculates `x % f(leaf)` for every leaf the same way as `remainders`,
+    /// where every node is transformed by `f` (for example, squared).
+    /// `f` must keep divisibility: `f(a)` divides `f(a * b)`.
+    pub fn remainders_by<F>(&self, x: &Bigi<M>, f: F) -> Vec<Bigi<M>>
+            where F: Fn(&Bigi<M>) -> Bigi<M> {
+        let mut rems = vec![*x % &f(self.root())];
+        for level in self.levels.iter().rev().skip(1) {
+            rems = level.iter().enumerate()
+                .map(|(i, node)| rems[i >> 1] % &f(node))
+                .collect();
+        }
+        rems
+    }
+}
+
+
+/// Calculates `x % m` for each `m` in `moduli` using a remainder tree.
+/// `Bigi<M>` must be large enough to keep the product of all the moduli.
+pub fn remainder_tree<const N: usize, const M: usize>(
+            x: &Bigi<M>, moduli: &[Bigi<N>]) -> Vec<Bigi<N>> {
+    let leaves: Vec<Bigi<M>> = moduli.iter().map(Bigi::<M>::from).collect();
+    ProductTree::new(&leaves).remainders(x).iter()
+        .map(Bigi::<N>::from).collect()
+}
+
+
+/// Calculates [batch GCD](https://facthacks.cr.yp.to/batchgcd.html):
+/// for each `n` in `moduli` it returns the GCD of `n` and the product of all
+/// the other moduli, so a result different from `1` means that `n` shares
+/// a factor with some other modulus. `Bigi<M>` must be large enough to keep
+/// the square of the product of all the moduli.
+/// ```rust
+/// use bigi::{Bigi, batch_gcd};
+///
+/// let moduli = [Bigi::<2>::from(15), Bigi::<2>::from(77), Bigi::<2>::from(26)];
+/// assert_eq!(
+///     batch_gcd::<2, 4>(&moduli),
+///     vec![Bigi::<2>::from(1), Bigi::<2>::from(1), Bigi::<2>::from(1)]
+/// );
+///
+/// let moduli = [Bigi::<2>::from(15), Bigi::<2>::from(77), Bigi::<2>::from(35)];
+/// assert_eq!(
+///     batch_gcd::<2, 4>(&moduli),
+///     vec![Bigi::<2>::from(5), Bigi::<2>::from(7), Bigi::<2>::from(35)]
+/// );
+/// ```
+pub fn batch_gcd<const N: usize, const M: usize>(
+            moduli: &[Bigi<N>]) -> Vec<Bigi<N>> {
+    let leaves: Vec<Bigi<M>> = moduli.iter().map(Bigi::<M>::from).collect();
+    let tree = ProductTree::new(&leaves);
+    tree.remainders_by(tree.root(), |node| *node * node).iter()
+        .zip(leaves.iter())
+        .map(|(z, n)| Bigi::<N>::from(&euclidean(&z.exact_div(n), n)))
+        .collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::bigi;
+    use super::*;
+    use crate::prime::gen_prime;
+    use test::Bencher;
+
+    #[test]
+    fn test_product_tree() {
+        let leaves = [bigi![4; 2], bigi![4; 3], bigi![4; 5], bigi![4; 7],
+                      bigi![4; 11]];
+        let tree = ProductTree::new(&leaves);
+        assert_eq!(tree.root(), &bigi![4; 2310]);
+        assert_eq!(tree.leaves(), &leaves);
+
+        let tree = ProductTree::new(&[bigi![4; 17]]);
+        assert_eq!(tree.root(), &bigi![4; 17]);
+    }
+
+    #[test]
+    fn test_remainder_tree() {
+        let mut rng = rand::thread_rng();
+        let moduli: Vec<Bigi<2>> = (0..13)
+            .map(|_| gen_prime::<_, 2>(&mut rng, 100)).collect();
+        let x = Bigi::<32>::gen_random(&mut rng, 2000, false);
+        let rems = remainder_tree(&x, &moduli);
+        for (m, r) in moduli.iter().zip(rems.iter()) {
+            assert_eq!(Bigi::<2>::from(&(x % &Bigi::<32>::from(m))), *r);
+        }
+    }
+
+    #[test]
+    fn test_batch_gcd() {
+        let mut rng = rand::thread_rng();
+        let primes: Vec<Bigi<2>> = (0..6)
+            .map(|_| gen_prime::<_, 2>(&mut rng, 64)).collect();
+        // The last modulus shares a prime with the first and the third ones
+        let moduli = [
+            primes[0] * &primes[1],
+            primes[2] * &primes[3],
+            primes[4] * &primes[5],
+            primes[0] * &primes[5],
+        ];
+        let gcds = batch_gcd::<2, 32>(&moduli);
+        assert_eq!(gcds, vec![primes[0], bigi![2; 1], primes[5], moduli[3]]);
+    }
+
+    #[bench]
+    fn bench_remainder_tree_128_x64(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let moduli: Vec<Bigi<2>> = (0..64)
+            .map(|_| Bigi::<2>::gen_random(&mut rng, 128, true)).collect();
+        let x = Bigi::<128>::gen_random(&mut rng, 8192, false);
+        bencher.iter(|| remainder_tree(&x, &moduli));
+    }
+
+    #[bench]
+    fn bench_batch_gcd_128_x64(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let moduli: Vec<Bigi<2>> = (0..64)
+            .map(|_| Bigi::<2>::gen_random(&mut rng, 128, true)).collect();
+        bencher.iter(|| batch_gcd::<2, 256>(&moduli));
+    }
+}
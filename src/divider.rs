@@ -0,0 +1,157 @@
+//! This module implements division by an invariant divisor. The divisor
+//! is normalized and the reciprocal of its highest digit is calculated
+//! once, so every digit of the quotient is found by multiplications
+//! instead of the hardware division.
+//!
+//! ```rust
+//! use bigi::{Bigi, Divider};
+//!
+//! let divider = Divider::new(&Bigi::<4>::from(1000));
+//! let (q, r) = divider.div_rem(&Bigi::<4>::from(123456));
+//! assert_eq!(q, Bigi::<4>::from(123));
+//! assert_eq!(r, Bigi::<4>::from(456));
+//! ```
+
+use crate::base::Bigi;
+use crate::operations::{divide_normalized, reciprocal, shift_slice_left,
+                        shift_slice_right};
+
+
+/// Precomputed data to divide many integers by the same divisor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Divider<const N: usize> {
+    divisor: Bigi<N>,
+    normalized: Bigi<N>,
+    order: usize,
+    shift: u32,
+    reciprocal: u64,
+}
+
+
+impl<const N: usize> Divider<N> {
+    /// Creates a divider for `divisor` that must be nonzero.
+    pub fn new(divisor: &Bigi<N>) -> Self {
+        assert!(!divisor.is_zero(), "Division by zero");
+        let order = divisor.get_order();
+        let shift = divisor.digits[order - 1].leading_zeros();
+        let mut normalized = Bigi::<N>::new();
+        shift_slice_left(&divisor.digits[..order], shift, &mut normalized.digits[..order]);
+        Self {
+            divisor: *divisor,
+            normalized,
+            order,
+            shift,
+            reciprocal: reciprocal(normalized.digits[order - 1]),
+        }
+    }
+
+    /// The divisor.
+    pub fn divisor(&self) -> &Bigi<N> {
+        &self.divisor
+    }
+
+    /// Calculates the quotient and the remainder of `x` divided by
+    /// the divisor, the same as `divide` does.
+    pub fn div_rem(&self, x: &Bigi<N>) -> (Bigi<N>, Bigi<N>) {
+        let order = x.get_order();
+        if order < self.order {
+            return (Bigi::<N>::new(), *x);
+        }
+
+        // The dividend is normalized with an extra highest digit
+        let mut un = [[0; N]; 2];
+        let un = &mut un.as_flattened_mut()[..(order + 1)];
+        if self.shift > 0 {
+            un[order] = x.digits[order - 1] >> (64 - self.shift);
+        }
+        shift_slice_left(&x.digits[..order], self.shift, &mut un[..order]);
+
+        let mut quotient = Bigi::<N>::new();
+        let mut scratch = [[0; N]; 7];
+        divide_normalized(un, &self.normalized.digits[..self.order],
+                          self.reciprocal, &mut quotient.digits, scratch.as_flattened_mut());
+
+        let mut remainder = Bigi::<N>::new();
+        shift_slice_right(&un[..(self.order + 1)], self.shift,
+                          &mut remainder.digits[..self.order]);
+        (quotient, remainder)
+    }
+
+    /// Calculates the quotient of `x` divided by the divisor.
+    pub fn div(&self, x: &Bigi<N>) -> Bigi<N> {
+        self.div_rem(x).0
+    }
+
+    /// Calculates the remainder of `x` divided by the divisor.
+    pub fn rem(&self, x: &Bigi<N>) -> Bigi<N> {
+        self.div_rem(x).1
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::bigi;
+    use super::*;
+    use test::Bencher;
+
+    #[test]
+    fn test_new() {
+        let divider = Divider::new(&bigi![4; 1, 1]);
+        assert_eq!(divider.normalized, bigi![4; 1 << 63, 1 << 63]);
+        assert_eq!(divider.order, 2);
+        assert_eq!(divider.shift, 63);
+        assert_eq!(divider.divisor(), &bigi![4; 1, 1]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Division by zero")]
+    fn test_new_zero() {
+        Divider::new(&bigi![4; 0]);
+    }
+
+    #[test]
+    fn test_div_rem() {
+        let divider = Divider::new(&bigi![4; 10]);
+        assert_eq!(divider.div_rem(&bigi![4; 0]), (bigi![4; 0], bigi![4; 0]));
+        assert_eq!(divider.div_rem(&bigi![4; 9]), (bigi![4; 0], bigi![4; 9]));
+        assert_eq!(divider.div(&bigi![4; 0, 1]), bigi![4; 1844674407370955161]);
+        assert_eq!(divider.rem(&bigi![4; 0, 1]), bigi![4; 6]);
+
+        let mut rng = rand::thread_rng();
+        for bits in [1, 2, 63, 64, 65, 128, 200, 256] {
+            let d = Bigi::<4>::gen_random(&mut rng, bits, true);
+            let divider = Divider::new(&d);
+            for x_bits in [0, 1, 64, 100, 200, 255, 256] {
+                let x = Bigi::<4>::gen_random(&mut rng, x_bits, false);
+                let mut r = x;
+                let q = r.divide(&d);
+                assert_eq!(divider.div_rem(&x), (q, r));
+            }
+            let x = Bigi::<4>::from(0) - &bigi![4; 1];
+            let mut r = x;
+            let q = r.divide(&d);
+            assert_eq!(divider.div_rem(&x), (q, r));
+        }
+    }
+
+    #[bench]
+    fn bench_div_rem_256_64(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let x = Bigi::<4>::gen_random(&mut rng, 256, false);
+        let divider = Divider::new(&Bigi::<4>::gen_random(&mut rng, 64, false));
+        bencher.iter(|| divider.div_rem(&x));
+    }
+
+    #[bench]
+    fn bench_divide_256_64(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let x = Bigi::<4>::gen_random(&mut rng, 256, false);
+        let d = Bigi::<4>::gen_random(&mut rng, 64, false);
+        bencher.iter(|| {
+            let mut r = x;
+            let q = r.divide(&d);
+            (q, r)
+        });
+    }
+}
@@ -0,0 +1,466 @@
+//! This module implements elliptic curves in short Weierstrass form
+//! `y^2 = x^3 + ax + b` over a prime field given by `Modulo` (**Curve**)
+//! and the arithmetics of their points in affine coordinates (**Point**):
+//! addition, doubling, scalar multiplication (Montgomery ladder and wNAF),
+//! validation and compressed encoding (SEC 1).
+//!
+//! ```rust
+//! use bigi::{Bigi, Curve, Point};
+//!
+//! // y^2 = x^3 + 2x + 3 (mod 97)
+//! let curve = Curve::new(&Bigi::<4>::from(97), &Bigi::<4>::from(2), &Bigi::<4>::from(3));
+//! let g = Point::Affine(Bigi::<4>::from(3), Bigi::<4>::from(6));
+//! assert!(curve.is_on_curve(&g));
+//! assert_eq!(curve.mul(&Bigi::<4>::from(5), &g), Point::Infinity);
+//! ```
+
+use crate::base::Bigi;
+use crate::modulo::Modulo;
+
+
+/// Point of an elliptic curve in affine coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Point<const N: usize> {
+    /// The point at infinity (the neutral element).
+    Infinity,
+    /// A point `(x, y)` with reduced coordinates.
+    Affine(Bigi<N>, Bigi<N>),
+}
+
+
+/// Elliptic curve `y^2 = x^3 + ax + b` over the prime field of `field`.
+pub struct Curve<const N: usize> {
+    pub a: Bigi<N>,
+    pub b: Bigi<N>,
+    pub field: Modulo<N>,
+}
+
+
+impl<const N: usize> Curve<N> {
+    /// Creates a curve over the prime field of `p`. The coefficients must be
+    /// reduced, the curve must be non-singular: `4a^3 + 27b^2 != 0 (mod p)`.
+    pub fn new(p: &Bigi<N>, a: &Bigi<N>, b: &Bigi<N>) -> Self {
+        let field = Modulo::new(p);
+        let a3 = field.mul(&field.mul(a, a), a);
+        let b2 = field.mul(b, b);
+        let disc = field.add(
+            &field.mul(&Bigi::<N>::from(4), &a3),
+            &field.mul(&Bigi::<N>::from(27), &b2)
+        );
+        assert!(!disc.is_zero(), "Singular curve");
+        Self { a: *a, b: *b, field }
+    }
+
+    /// Checks whether the point lies on the curve.
+    pub fn is_on_curve(&self, p: &Point<N>) -> bool {
+        match p {
+            Point::Infinity => true,
+            Point::Affine(x, y) => {
+                x < self.field.modulo() && y < self.field.modulo() &&
+                    self.field.mul(y, y) == self.rhs(x)
+            },
+        }
+    }
+
+    /// Negation of the point: `-(x, y) = (x, -y)`.
+    pub fn neg(&self, p: &Point<N>) -> Point<N> {
+        match p {
+            Point::Infinity => Point::Infinity,
+            Point::Affine(x, y) => Point::Affine(
+                *x, self.field.sub(&Bigi::<N>::new(), y)
+            ),
+        }
+    }
+
+    /// Addition of two points.
+    pub fn add(&self, p: &Point<N>, q: &Point<N>) -> Point<N> {
+        match (p, q) {
+            (Point::Infinity, _) => *q,
+            (_, Point::Infinity) => *p,
+            (Point::Affine(x1, y1), Point::Affine(x2, y2)) => {
+                if x1 == x2 {
+                    if y1 == y2 {
+                        self.double(p)
+                    } else {
+                        Point::Infinity
+                    }
+                } else {
+                    let l = self.field.div(
+                        &self.field.sub(y2, y1), &self.field.sub(x2, x1)
+                    );
+                    self.line_point(&l, x1, y1, x2)
+                }
+            },
+        }
+    }
+
+    /// Subtraction of two points.
+    pub fn sub(&self, p: &Point<N>, q: &Point<N>) -> Point<N> {
+        self.add(p, &self.neg(q))
+    }
+
+    /// Doubling of the point.
+    pub fn double(&self, p: &Point<N>) -> Point<N> {
+        match p {
+            Point::Infinity => Point::Infinity,
+            Point::Affine(x, y) => {
+                if y.is_zero() {
+                    return Point::Infinity;
+                }
+                let x2 = self.field.mul(x, x);
+                let num = self.field.add(
+                    &self.field.add(&x2, &self.field.add(&x2, &x2)), &self.a
+                );
+                let l = self.field.div(&num, &self.field.add(y, y));
+                self.line_point(&l, x, y, x)
+            },
+        }
+    }
+
+    /// Scalar multiplication `k * p` by Montgomery ladder. The ladder
+    /// performs the same sequence of operations for every bit of `k`.
+    pub fn mul(&self, k: &Bigi<N>, p: &Point<N>) -> Point<N> {
+        let mut r0 = Point::Infinity;
+        let mut r1 = *p;
+        for bit in k.bits_be() {
+            if bit {
+                r0 = self.add(&r0, &r1);
+                r1 = self.double(&r1);
+            } else {
+                r1 = self.add(&r0, &r1);
+                r0 = self.double(&r0);
+            }
+        }
+        r0
+    }
+
+    /// Scalar multiplication `k * p` using the width-`w` non-adjacent form
+    /// of `k` (`2 <= w <= 8`). It needs `2^(w-2)` precomputed points and
+    /// about `bits / (w + 1)` additions, so it is faster than `mul`, but
+    /// the running time depends on `k`.
+    /// ```rust
+    /// use bigi::{Bigi, Curve, Point};
+    ///
+    /// let curve = Curve::new(&Bigi::<4>::from(97), &Bigi::<4>::from(2), &Bigi::<4>::from(3));
+    /// let g = Point::Affine(Bigi::<4>::from(3), Bigi::<4>::from(6));
+    /// assert_eq!(curve.mul_wnaf(&Bigi::<4>::from(1234), &g, 4),
+    ///            curve.mul(&Bigi::<4>::from(1234), &g));
+    /// ```
+    pub fn mul_wnaf(&self, k: &Bigi<N>, p: &Point<N>, w: usize) -> Point<N> {
+        assert!((2..=8).contains(&w), "Wrong window width");
+
+        // Odd multiples p, 3p, 5p, ...
+        let p2 = self.double(p);
+        let mut table = vec![*p];
+        for i in 1..(1 << (w - 2)) {
+            table.push(self.add(&table[i - 1], &p2));
+        }
+
+        let mut res = Point::Infinity;
+        for d in wnaf(k, w).iter().rev() {
+            res = self.double(&res);
+            if *d > 0 {
+                res = self.add(&res, &table[(*d >> 1) as usize]);
+            } else if *d < 0 {
+                res = self.sub(&res, &table[(-*d >> 1) as usize]);
+            }
+        }
+        res
+    }
+
+    /// Encodes the point in SEC 1 compressed form: `0x02` or `0x03`
+    /// (the parity of `y`) followed by big-endian `x` of the byte length of
+    /// the field. The point at infinity is encoded as `[0x00]`.
+    /// ```rust
+    /// use bigi::{Bigi, Curve, Point};
+    ///
+    /// let curve = Curve::new(&Bigi::<4>::from(97), &Bigi::<4>::from(2), &Bigi::<4>::from(3));
+    /// let g = Point::Affine(Bigi::<4>::from(3), Bigi::<4>::from(6));
+    /// assert_eq!(curve.to_compressed(&g), vec![0x02, 0x03]);
+    /// assert_eq!(curve.from_compressed(&[0x02, 0x03]), Ok(g));
+    /// ```
+    pub fn to_compressed(&self, p: &Point<N>) -> Vec<u8> {
+        match p {
+            Point::Infinity => vec![0x00],
+            Point::Affine(x, y) => {
+                let mut res = vec![if y.is_odd() { 0x03 } else { 0x02 }];
+                res.extend(x.to_bytes_be_padded(self.byte_length()));
+                res
+            },
+        }
+    }
+
+    /// Decodes the point from SEC 1 compressed form recovering `y` by
+    /// the modular square root.
+    pub fn from_compressed(&self, data: &[u8]) -> Result<Point<N>, &'static str> {
+        match data.first() {
+            Some(0x00) if data.len() == 1 => Ok(Point::Infinity),
+            Some(tag @ (0x02 | 0x03)) if data.len() == 1 + self.byte_length() => {
+                let x = Bigi::<N>::from_bytes_be(&data[1..]);
+                if x >= *self.field.modulo() {
+                    return Err("Invalid point");
+                }
+                let (y, y_neg) = self.field.sqrt(&self.rhs(&x))
+                    .map_err(|_| "Point not on curve")?;
+                let odd = *tag == 0x03;
+                if y.is_odd() == odd {
+                    Ok(Point::Affine(x, y))
+                } else if y.is_zero() {
+                    Err("Invalid point")
+                } else {
+                    Ok(Point::Affine(x, y_neg))
+                }
+            },
+            _ => Err("Invalid point encoding"),
+        }
+    }
+
+    /// Calculates `x^3 + ax + b`.
+    fn rhs(&self, x: &Bigi<N>) -> Bigi<N> {
+        let x2 = self.field.mul(x, x);
+        self.field.add(&self.field.mul(&self.field.add(&x2, &self.a), x), &self.b)
+    }
+
+    /// Third intersection of the line of slope `l` through `(x1, y1)` and
+    /// a point with abscissa `x2`, reflected over the x-axis.
+    fn line_point(&self, l: &Bigi<N>, x1: &Bigi<N>, y1: &Bigi<N>,
+                  x2: &Bigi<N>) -> Point<N> {
+        let x3 = self.field.sub(&self.field.sub(&self.field.mul(l, l), x1), x2);
+        let y3 = self.field.sub(&self.field.mul(l, &self.field.sub(x1, &x3)), y1);
+        Point::Affine(x3, y3)
+    }
+
+    fn byte_length(&self) -> usize {
+        self.field.modulo().byte_length()
+    }
+}
+
+
+/// Width-`w` non-adjacent form of `k` from the least significant digit:
+/// every nonzero digit is odd, less than `2^(w-1)` by absolute value and
+/// followed by at least `w - 1` zeros.
+fn wnaf<const N: usize>(k: &Bigi<N>, w: usize) -> Vec<i64> {
+    let mut res = Vec::new();
+    let mut k = *k;
+    while !k.is_zero() {
+        let mut d = 0;
+        // The carry out of the highest digit is the bit 64N of k
+        let mut carry = false;
+        if k.is_odd() {
+            d = (k.digits[0] & ((1 << w) - 1)) as i64;
+            if d >= 1 << (w - 1) {
+                d -= 1 << w;
+                let x = k;
+                k += (-d) as u64;
+                carry = k < x;
+            } else {
+                k -= d as u64;
+            }
+        }
+        res.push(d);
+        k >>= 1;
+        if carry {
+            k.set_bit((N << 6) - 1);
+        }
+    }
+    res
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::bigi;
+    use super::*;
+    use test::Bencher;
+
+    const P256_P: &str = "0xFFFFFFFF00000001000000000000000000000000FFFFFFFFFFFFFFFFFFFFFFFF";
+    const P256_B: &str = "0x5AC635D8AA3A93E7B3EBBD55769886BC651D06B0CC53B0F63BCE3C3E27D2604B";
+    const P256_N: &str = "0xFFFFFFFF00000000FFFFFFFFFFFFFFFFBCE6FAADA7179E84F3B9CAC2FC632551";
+    const P256_GX: &str = "0x6B17D1F2E12C4247F8BCE6E563A440F277037D812DEB33A0F4A13945D898C296";
+    const P256_GY: &str = "0x4FE342E2FE1A7F9B8EE7EB4A7C0F9E162BCE33576B315ECECBB6406837BF51F5";
+
+    fn p256() -> (Curve<8>, Point<8>) {
+        let p = Bigi::<8>::from_hex(P256_P);
+        let curve = Curve::new(&p, &(p - &bigi![8; 3]), &Bigi::from_hex(P256_B));
+        let g = Point::Affine(Bigi::from_hex(P256_GX), Bigi::from_hex(P256_GY));
+        (curve, g)
+    }
+
+    fn small_curve() -> Curve<4> {
+        Curve::new(&bigi![4; 97], &bigi![4; 2], &bigi![4; 3])
+    }
+
+    #[test]
+    #[should_panic(expected = "Singular curve")]
+    fn test_new_singular() {
+        Curve::new(&bigi![4; 97], &bigi![4; 0], &bigi![4; 0]);
+    }
+
+    #[test]
+    fn test_is_on_curve() {
+        let curve = small_curve();
+        assert!(curve.is_on_curve(&Point::Infinity));
+        assert!(curve.is_on_curve(&Point::Affine(bigi![4; 3], bigi![4; 6])));
+        assert!(!curve.is_on_curve(&Point::Affine(bigi![4; 3], bigi![4; 7])));
+        assert!(!curve.is_on_curve(&Point::Affine(bigi![4; 100], bigi![4; 6])));
+
+        let (curve, g) = p256();
+        assert!(curve.is_on_curve(&g));
+    }
+
+    #[test]
+    fn test_add() {
+        let curve = small_curve();
+        let mut points = vec![Point::Infinity];
+        for x in 0..97 {
+            if let Ok((y, y_neg)) = curve.field.sqrt(&curve.rhs(&bigi![4; x])) {
+                points.push(Point::Affine(bigi![4; x], y));
+                points.push(Point::Affine(bigi![4; x], y_neg));
+            }
+        }
+        for p in points.iter() {
+            assert_eq!(curve.add(p, &Point::Infinity), *p);
+            assert_eq!(curve.add(p, &curve.neg(p)), Point::Infinity);
+            assert_eq!(curve.add(p, p), curve.double(p));
+            for q in points.iter().step_by(7) {
+                let r = curve.add(p, q);
+                assert!(curve.is_on_curve(&r));
+                assert_eq!(r, curve.add(q, p));
+                assert_eq!(curve.sub(&r, q), *p);
+            }
+        }
+    }
+
+    #[test]
+    fn test_double() {
+        let (curve, g) = p256();
+        let g2 = Point::Affine(
+            Bigi::from_hex("0x7CF27B188D034F7E8A52380304B51AC3C08969E277F21B35A60B48FC47669978"),
+            Bigi::from_hex("0x07775510DB8ED040293D9AC69F7430DBBA7DADE63CE982299E04B79D227873D1"),
+        );
+        assert_eq!(curve.double(&g), g2);
+        assert_eq!(curve.double(&Point::Infinity), Point::Infinity);
+    }
+
+    #[test]
+    fn test_mul() {
+        let (curve, g) = p256();
+        let n = Bigi::<8>::from_hex(P256_N);
+        assert_eq!(curve.mul(&n, &g), Point::Infinity);
+        assert_eq!(curve.mul(&(n - &bigi![8; 1]), &g), curve.neg(&g));
+        assert_eq!(curve.mul(&bigi![8; 0], &g), Point::Infinity);
+        assert_eq!(curve.mul(&bigi![8; 1], &g), g);
+
+        let mut rng = rand::thread_rng();
+        let k = Bigi::<8>::gen_random(&mut rng, 256, false);
+        let l = Bigi::<8>::gen_random(&mut rng, 256, false);
+        let kl = crate::prime::mul_mod(&k, &l, &n);
+        assert_eq!(curve.mul(&l, &curve.mul(&k, &g)), curve.mul(&kl, &g));
+    }
+
+    #[test]
+    fn test_mul_secp256k1() {
+        let p = Bigi::<8>::from_hex(
+            "0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F"
+        );
+        let n = Bigi::<8>::from_hex(
+            "0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141"
+        );
+        let curve = Curve::new(&p, &bigi![8; 0], &bigi![8; 7]);
+        let g = Point::Affine(
+            Bigi::from_hex("0x79BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798"),
+            Bigi::from_hex("0x483ADA7726A3C4655DA4FBFC0E1108A8FD17B448A68554199C47D08FFB10D4B8"),
+        );
+        assert!(curve.is_on_curve(&g));
+        assert_eq!(curve.mul(&n, &g), Point::Infinity);
+        assert_eq!(curve.mul(&(n - &bigi![8; 1]), &g), curve.neg(&g));
+    }
+
+    #[test]
+    fn test_mul_wnaf() {
+        let curve = small_curve();
+        let g = Point::Affine(bigi![4; 3], bigi![4; 6]);
+        for w in 2..=8 {
+            for k in 0..300 {
+                assert_eq!(curve.mul_wnaf(&bigi![4; k], &g, w), curve.mul(&bigi![4; k], &g));
+            }
+        }
+
+        // The highest integers carry out of the width
+        for w in 2..=8 {
+            for k in [Bigi::<4>::MAX, Bigi::<4>::MAX - &bigi![4; 1]] {
+                assert_eq!(curve.mul_wnaf(&k, &g, w), curve.mul(&k, &g));
+            }
+        }
+
+        let (curve, g) = p256();
+        let mut rng = rand::thread_rng();
+        let k = Bigi::<8>::gen_random(&mut rng, 256, false);
+        assert_eq!(curve.mul_wnaf(&k, &g, 5), curve.mul(&k, &g));
+    }
+
+    #[test]
+    fn test_wnaf() {
+        let mut rng = rand::thread_rng();
+        for w in 2..=8 {
+            let k = Bigi::<4>::gen_random(&mut rng, 200, false);
+            let digits = wnaf(&k, w);
+            let mut x = bigi![4; 0];
+            for d in digits.iter().rev() {
+                x <<= 1;
+                if *d >= 0 {
+                    x += &bigi![4; *d as u64];
+                } else {
+                    x -= &bigi![4; (-*d) as u64];
+                }
+            }
+            assert_eq!(x, k);
+            for (i, d) in digits.iter().enumerate() {
+                assert!(*d == 0 || (d.abs() & 1 == 1 && d.abs() < 1 << (w - 1)));
+                if *d != 0 {
+                    assert!(digits.iter().skip(i + 1).take(w - 1).all(|e| *e == 0));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_compressed() {
+        let curve = small_curve();
+        assert_eq!(curve.to_compressed(&Point::Infinity), vec![0x00]);
+        assert_eq!(curve.from_compressed(&[0x00]), Ok(Point::Infinity));
+        assert_eq!(curve.from_compressed(&[]), Err("Invalid point encoding"));
+        assert_eq!(curve.from_compressed(&[0x04, 0x03]), Err("Invalid point encoding"));
+        assert_eq!(curve.from_compressed(&[0x02, 0x03, 0x00]), Err("Invalid point encoding"));
+        assert_eq!(curve.from_compressed(&[0x02, 0x61]), Err("Invalid point"));
+
+        let (curve, g) = p256();
+        let mut rng = rand::thread_rng();
+        for _ in 0..10 {
+            let k = Bigi::<8>::gen_random(&mut rng, 256, false);
+            let p = curve.mul_wnaf(&k, &g, 4);
+            let data = curve.to_compressed(&p);
+            assert_eq!(data.len(), 33);
+            assert_eq!(curve.from_compressed(&data), Ok(p));
+        }
+        let data = curve.to_compressed(&g);
+        assert_eq!(data[0], 0x03);
+        assert_eq!(Bigi::<8>::from_bytes_be(&data[1..]), Bigi::from_hex(P256_GX));
+    }
+
+    #[bench]
+    fn bench_mul_256(bencher: &mut Bencher) {
+        let (curve, g) = p256();
+        let mut rng = rand::thread_rng();
+        let k = Bigi::<8>::gen_random(&mut rng, 256, false);
+        bencher.iter(|| curve.mul(&k, &g));
+    }
+
+    #[bench]
+    fn bench_mul_wnaf_256(bencher: &mut Bencher) {
+        let (curve, g) = p256();
+        let mut rng = rand::thread_rng();
+        let k = Bigi::<8>::gen_random(&mut rng, 256, false);
+        bencher.iter(|| curve.mul_wnaf(&k, &g, 5));
+    }
+}
@@ -0,0 +1,376 @@
+//! This module implements decimal fixed-point numbers over `Bigi` as the type
+//! **BigiFixed**. A number is stored as an integer `value` with a decimal
+//! `scale` so it equals `value * 10^-scale`. Multiplication and division
+//! round the result half up to the scale of the operands. The operations
+//! panic if the result is negative or does not fit into `N` digits.
+//!
+//! ```rust
+//! use bigi::BigiFixed;
+//!
+//! let a = BigiFixed::<4>::from_decimal("12.50", 2);
+//! let b = BigiFixed::<4>::from_decimal("0.07", 2);
+//! assert_eq!((a * &b).to_decimal(), "0.88");  // 0.875 rounded
+//! ```
+
+use std::{ops, cmp};
+use crate::base::Bigi;
+
+
+/// Type for non-negative decimal fixed-point numbers.
+#[derive(Debug, Clone, Copy)]
+pub struct BigiFixed<const N: usize> {
+    value: Bigi<N>,
+    scale: usize,
+}
+
+
+impl<const N: usize> BigiFixed<N> {
+    /// Creates a fixed-point number `value * 10^-scale` from its raw value.
+    /// ```rust
+    /// use bigi::{Bigi, BigiFixed};
+    ///
+    /// let a = BigiFixed::new(&Bigi::<4>::from(1250), 2);
+    /// assert_eq!(a.to_decimal(), "12.50");
+    /// ```
+    pub fn new(value: &Bigi<N>, scale: usize) -> Self {
+        Self { value: *value, scale }
+    }
+
+    /// Creates a fixed-point number equal to the integer `x`.
+    pub fn from_integer(x: &Bigi<N>, scale: usize) -> Self {
+        Self { value: Self::mul_checked(x, &Self::unit(scale)), scale }
+    }
+
+    /// Gets the raw value.
+    pub fn value(&self) -> &Bigi<N> {
+        &self.value
+    }
+
+    /// Gets the number of decimal digits after the point.
+    pub fn scale(&self) -> usize {
+        self.scale
+    }
+
+    /// Converts the number to another scale rounding half up if needed.
+    /// ```rust
+    /// use bigi::BigiFixed;
+    ///
+    /// let a = BigiFixed::<4>::from_decimal("2.345", 3);
+    /// assert_eq!(a.rescale(2).to_decimal(), "2.35");
+    /// assert_eq!(a.rescale(5).to_decimal(), "2.34500");
+    /// ```
+    pub fn rescale(&self, scale: usize) -> Self {
+        let value = if scale >= self.scale {
+            Self::mul_checked(&self.value, &Self::unit(scale - self.scale))
+        } else {
+            self.value.div_round(&Self::unit(self.scale - scale))
+        };
+        Self { value, scale }
+    }
+
+    /// Gets the integer part of the number.
+    pub fn trunc(&self) -> Bigi<N> {
+        self.value / &Self::unit(self.scale)
+    }
+
+    /// Converts the number into a decimal string with exactly `scale` digits
+    /// after the point.
+    pub fn to_decimal(&self) -> String {
+        let (int, frac) = self.value.div_rem(&Self::unit(self.scale));
+        if self.scale == 0 {
+            return int.to_decimal();
+        }
+        format!("{}.{:0>width$}", int.to_decimal(), frac.to_decimal(),
+                width = self.scale)
+    }
+
+    /// Converts decimal string into a fixed-point number with the given scale.
+    /// Extra fractional digits are rounded half up.
+    pub fn from_decimal(decimal: &str, scale: usize) -> Self {
+        let (int, frac) = match decimal.split_once('.') {
+            Some(pair) => pair,
+            None => (decimal, ""),
+        };
+        let digits = frac.len();
+        let mut raw = int.to_string() + frac;
+        if raw.is_empty() {
+            raw += "0";
+        }
+        Self::new(&Bigi::<N>::from_decimal(&raw), digits).rescale(scale)
+    }
+
+    fn unit(scale: usize) -> Bigi<N> {
+        let ten = Bigi::<N>::from(10);
+        let mut res = Bigi::<N>::from(1);
+        for _ in 0..scale {
+            res = Self::mul_checked(&res, &ten);
+        }
+        res
+    }
+
+    fn mul_checked(x: &Bigi<N>, y: &Bigi<N>) -> Bigi<N> {
+        x.checked_mul(y).expect("Integer overflow")
+    }
+
+    fn round(quot: Bigi<N>, rem: &Bigi<N>, divisor: &Bigi<N>) -> Bigi<N> {
+        // Half up: rem >= divisor - rem is the same as 2 * rem >= divisor
+        if *rem >= *divisor - rem {
+            quot.checked_add(&Bigi::<N>::from(1)).expect("Integer overflow")
+        } else {
+            quot
+        }
+    }
+}
+
+
+impl<const N: usize> ops::Add<&BigiFixed<N>> for BigiFixed<N> {
+    type Output = BigiFixed<N>;
+
+    fn add(self, other: &BigiFixed<N>) -> BigiFixed<N> {
+        let mut res = self;
+        res += other;
+        res
+    }
+}
+
+
+impl<const N: usize> ops::AddAssign<&BigiFixed<N>> for BigiFixed<N> {
+    fn add_assign(&mut self, other: &BigiFixed<N>) {
+        assert_eq!(self.scale, other.scale, "Different scales");
+        self.value = self.value.checked_add(&other.value).expect("Integer overflow");
+    }
+}
+
+
+impl<const N: usize> ops::Sub<&BigiFixed<N>> for BigiFixed<N> {
+    type Output = BigiFixed<N>;
+
+    fn sub(self, other: &BigiFixed<N>) -> BigiFixed<N> {
+        let mut res = self;
+        res -= other;
+        res
+    }
+}
+
+
+impl<const N: usize> ops::SubAssign<&BigiFixed<N>> for BigiFixed<N> {
+    fn sub_assign(&mut self, other: &BigiFixed<N>) {
+        assert_eq!(self.scale, other.scale, "Different scales");
+        assert!(self.value >= other.value, "Negative difference");
+        self.value -= &other.value;
+    }
+}
+
+
+impl<const N: usize> ops::Mul<&BigiFixed<N>> for BigiFixed<N> {
+    type Output = BigiFixed<N>;
+
+    fn mul(self, other: &BigiFixed<N>) -> BigiFixed<N> {
+        let mut res = self;
+        res *= other;
+        res
+    }
+}
+
+
+impl<const N: usize> ops::MulAssign<&BigiFixed<N>> for BigiFixed<N> {
+    fn mul_assign(&mut self, other: &BigiFixed<N>) {
+        assert_eq!(self.scale, other.scale, "Different scales");
+        let unit = Self::unit(self.scale);
+        let (mut rem, overflow) = self.value.multiply_overflowing(&other.value);
+        // The quotient fits into N digits only if the high half is less
+        // than the divisor
+        assert!(overflow < unit, "Integer overflow");
+        let quot = rem.divide_overflowing(&unit, &overflow);
+        self.value = Self::round(quot, &rem, &unit);
+    }
+}
+
+
+impl<const N: usize> ops::Div<&BigiFixed<N>> for BigiFixed<N> {
+    type Output = BigiFixed<N>;
+
+    fn div(self, other: &BigiFixed<N>) -> BigiFixed<N> {
+        let mut res = self;
+        res /= other;
+        res
+    }
+}
+
+
+impl<const N: usize> ops::DivAssign<&BigiFixed<N>> for BigiFixed<N> {
+    fn div_assign(&mut self, other: &BigiFixed<N>) {
+        assert_eq!(self.scale, other.scale, "Different scales");
+        assert!(!other.value.is_zero(), "Division by zero");
+        let unit = Self::unit(self.scale);
+        let (mut rem, overflow) = self.value.multiply_overflowing(&unit);
+        assert!(overflow < other.value, "Integer overflow");
+        let quot = rem.divide_overflowing(&other.value, &overflow);
+        self.value = Self::round(quot, &rem, &other.value);
+    }
+}
+
+
+impl<const N: usize> cmp::PartialEq for BigiFixed<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(cmp::Ordering::Equal)
+    }
+}
+
+
+impl<const N: usize> cmp::PartialOrd for BigiFixed<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        if self.scale == other.scale {
+            self.value.partial_cmp(&other.value)
+        } else {
+            let scale = cmp::max(self.scale, other.scale);
+            self.rescale(scale).value.partial_cmp(&other.rescale(scale).value)
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::bigi;
+    use super::*;
+    use test::Bencher;
+
+    #[test]
+    fn test_decimal() {
+        assert_eq!(BigiFixed::<4>::from_decimal("12.5", 2).to_decimal(), "12.50");
+        assert_eq!(BigiFixed::<4>::from_decimal("0.005", 2).to_decimal(), "0.01");
+        assert_eq!(BigiFixed::<4>::from_decimal("0.004", 2).to_decimal(), "0.00");
+        assert_eq!(BigiFixed::<4>::from_decimal("7", 0).to_decimal(), "7");
+        assert_eq!(BigiFixed::<4>::from_decimal(".25", 3).to_decimal(), "0.250");
+        assert_eq!(
+            BigiFixed::<4>::from_decimal("3.14", 2).value(), &bigi![4; 314]
+        );
+    }
+
+    #[test]
+    fn test_add_sub() {
+        let a = BigiFixed::<4>::from_decimal("1.25", 2);
+        let b = BigiFixed::<4>::from_decimal("0.80", 2);
+        assert_eq!((a + &b).to_decimal(), "2.05");
+        assert_eq!((a - &b).to_decimal(), "0.45");
+    }
+
+    #[test]
+    #[should_panic(expected = "Negative difference")]
+    fn test_sub_negative() {
+        let a = BigiFixed::<4>::from_decimal("0.80", 2);
+        let b = BigiFixed::<4>::from_decimal("1.25", 2);
+        let _ = a - &b;
+    }
+
+    #[test]
+    fn test_mul() {
+        let a = BigiFixed::<4>::from_decimal("1.25", 2);
+        let b = BigiFixed::<4>::from_decimal("0.33", 2);
+        assert_eq!((a * &b).to_decimal(), "0.41");  // 0.4125
+        let b = BigiFixed::<4>::from_decimal("0.34", 2);
+        assert_eq!((a * &b).to_decimal(), "0.43");  // 0.425
+    }
+
+    #[test]
+    fn test_div() {
+        let a = BigiFixed::<4>::from_decimal("2.00", 2);
+        let b = BigiFixed::<4>::from_decimal("3.00", 2);
+        assert_eq!((a / &b).to_decimal(), "0.67");
+        let a = BigiFixed::<4>::from_decimal("1.00", 2);
+        assert_eq!((a / &b).to_decimal(), "0.33");
+    }
+
+    #[test]
+    #[should_panic(expected = "Division by zero")]
+    fn test_div_zero() {
+        let a = BigiFixed::<4>::from_decimal("1.00", 2);
+        let _ = a / &BigiFixed::<4>::from_decimal("0", 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "Integer overflow")]
+    fn test_div_overflow() {
+        // 10^17 / 0.01 does not fit into 64 bits
+        let a = BigiFixed::<1>::from_decimal("100000000000000000.00", 2);
+        let _ = a / &BigiFixed::<1>::from_decimal("0.01", 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "Integer overflow")]
+    fn test_add_overflow() {
+        let a = BigiFixed::<1>::from_decimal("100000000000000000.00", 2);
+        let _ = a + &a;
+    }
+
+    #[test]
+    #[should_panic(expected = "Integer overflow")]
+    fn test_mul_overflow() {
+        let a = BigiFixed::<1>::from_decimal("10000000000.00", 2);
+        let _ = a * &a;
+    }
+
+    #[test]
+    #[should_panic(expected = "Integer overflow")]
+    fn test_round_overflow() {
+        // The product is (2^64 - 1) + 0.5 before rounding
+        let a = BigiFixed::new(&bigi![1; 155], 1);
+        let b = BigiFixed::new(&bigi![1; 1190112520884487201], 1);
+        let _ = a * &b;
+    }
+
+    #[test]
+    #[should_panic(expected = "Integer overflow")]
+    fn test_unit_overflow() {
+        // 10^20 does not fit into 64 bits
+        BigiFixed::<1>::from_integer(&bigi![1; 1], 20);
+    }
+
+    #[test]
+    #[should_panic(expected = "Integer overflow")]
+    fn test_from_integer_overflow() {
+        BigiFixed::<1>::from_integer(&bigi![1; 1000], 18);
+    }
+
+    #[test]
+    #[should_panic(expected = "Integer overflow")]
+    fn test_rescale_overflow() {
+        let a = BigiFixed::<1>::from_decimal("100000000000000000.00", 2);
+        a.rescale(3);
+    }
+
+    #[test]
+    fn test_mul_wide() {
+        // The full product exceeds the width before the scale is removed
+        let a = BigiFixed::<2>::from_decimal("1000000000.5", 18);
+        assert_eq!(
+            (a * &a).to_decimal(),
+            "1000000001000000000.250000000000000000"
+        );
+    }
+
+    #[test]
+    fn test_cmp() {
+        let a = BigiFixed::<4>::from_decimal("1.5", 1);
+        let b = BigiFixed::<4>::from_decimal("1.50", 2);
+        let c = BigiFixed::<4>::from_decimal("1.51", 2);
+        assert!(a == b);
+        assert!(a < c);
+        assert!(c > b);
+    }
+
+    #[bench]
+    fn bench_mul_128(bencher: &mut Bencher) {
+        let a = BigiFixed::<4>::from_decimal("123456789012345678.123456789012345678", 18);
+        let b = BigiFixed::<4>::from_decimal("987654321.987654321987654321", 18);
+        bencher.iter(|| a * &b);
+    }
+
+    #[bench]
+    fn bench_div_128(bencher: &mut Bencher) {
+        let a = BigiFixed::<4>::from_decimal("123456789012345678.123456789012345678", 18);
+        let b = BigiFixed::<4>::from_decimal("987654321.987654321987654321", 18);
+        bencher.iter(|| a / &b);
+    }
+}
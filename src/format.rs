@@ -1,5 +1,5 @@
 //! This module implements methods to format Bigi into decimal string,
-//! hex string, bytes and back.
+//! hex string, binary string, bytes and back.
 //!
 //! ```rust
 //! use bigi::Bigi;
@@ -8,52 +8,155 @@
 //! assert_eq!(a.to_hex(), "0x1C");
 //! ```
 
+use std::{fmt, str};
 use crate::base::Bigi;
 
 
+/// The largest power of 10 that fits into *u64*.
+const DECIMAL_CHUNK_BASE: u64 = 10_000_000_000_000_000_000;
+
+
+/// Wrapper to display the integer in decimal with the digits split into
+/// groups by a separator. It is created by `Bigi::grouped`.
+pub struct Grouped<'a, const N: usize> {
+    x: &'a Bigi<N>,
+    separator: char,
+    size: usize,
+}
+
+
+impl<'a, const N: usize> fmt::Display for Grouped<'a, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let decimal = self.x.to_decimal();
+        let first = match decimal.len() % self.size {
+            0 => self.size,
+            r => r,
+        };
+        f.write_str(&decimal[..first])?;
+        for group in decimal.as_bytes()[first..].chunks(self.size) {
+            // The decimal string is ASCII so any slice of it is valid
+            write!(f, "{}{}", self.separator, str::from_utf8(group).unwrap())?;
+        }
+        Ok(())
+    }
+}
+
+
 impl<const N: usize> Bigi<N> {
-    /// Converts the integer into a decimal string.
-    pub fn to_decimal(&self) -> String {
-        let mut decimal = String::new();
-        let mut value = self.clone();
-        let ten = Bigi::<N>::from(10);
-        let zero = Bigi::<N>::from(0);
+    /// Writes the integer as a decimal string into `w`. The integer is split
+    /// into chunks of 19 decimal digits that are written in reverse order
+    /// at once.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// let mut s = String::from("x = ");
+    /// bigi![4; 0, 1].write_decimal(&mut s).unwrap();
+    /// assert_eq!(s, "x = 18446744073709551616");
+    /// ```
+    pub fn write_decimal<W: fmt::Write>(&self, w: &mut W) -> fmt::Result {
+        let mut chunks = Vec::new();
+        let mut value = *self;
 
-        while value > zero {
-            let new_value = value.divide(&ten);
-            decimal = value.digits[0].to_string() + &decimal;
-            value = new_value;
+        while !value.is_zero() {
+            chunks.push(value.div_u64(DECIMAL_CHUNK_BASE));
         }
 
-        if decimal.is_empty() {
-            decimal += "0";
+        match chunks.pop() {
+            Some(chunk) => write!(w, "{}", chunk)?,
+            None => w.write_char('0')?,
+        }
+        for chunk in chunks.iter().rev() {
+            write!(w, "{:019}", chunk)?;
         }
 
+        Ok(())
+    }
+
+    /// Converts the integer into a decimal string.
+    pub fn to_decimal(&self) -> String {
+        let mut decimal = String::new();
+        self.write_decimal(&mut decimal).unwrap();
         decimal
     }
 
+    /// Gets a wrapper that displays the integer in decimal with the digits
+    /// split into groups of `size` from the right by `separator`.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// assert_eq!(bigi![4; 1234567].grouped('_', 3).to_string(), "1_234_567");
+    /// assert_eq!(bigi![4; 1234567].grouped(' ', 4).to_string(), "123 4567");
+    /// ```
+    pub fn grouped(&self, separator: char, size: usize) -> Grouped<'_, N> {
+        assert!(size > 0, "Group size must be positive");
+        Grouped { x: self, separator, size }
+    }
+
     /// Converts decimal string into an integer.
     pub fn from_decimal(decimal: &str) -> Bigi<N> {
         let mut res = Bigi::<N>::from(0);
-        let ten = Bigi::<N>::from(10);
         for ch in decimal.chars() {
             let digit = ch.to_string().parse::<u64>().unwrap();
-            res = res * &ten + &Bigi::<N>::from(digit);
+            res = res * 10 + digit;
         }
         res
     }
 
     /// Converts the integer into a hex string.
     pub fn to_hex(&self) -> String {
+        self.format_hex(false, false)
+    }
+
+    /// Converts the integer into a hex string with lowercase digits.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// assert_eq!(bigi![4; 0xabc].to_hex_lower(), "0xabc");
+    /// ```
+    pub fn to_hex_lower(&self) -> String {
+        self.format_hex(true, false)
+    }
+
+    /// Converts the integer into a hex string of exactly `16 * N` digits
+    /// padded with leading zeros.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// assert_eq!(bigi![2; 0xABC].to_hex_padded(), "0x00000000000000000000000000000ABC");
+    /// ```
+    pub fn to_hex_padded(&self) -> String {
+        self.format_hex(false, true)
+    }
+
+    /// Converts the integer into a hex string of exactly `16 * N` lowercase
+    /// digits padded with leading zeros.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// assert_eq!(bigi![2; 0xABC].to_hex_lower_padded(), "0x00000000000000000000000000000abc");
+    /// ```
+    pub fn to_hex_lower_padded(&self) -> String {
+        self.format_hex(true, true)
+    }
+
+    fn format_hex(&self, lower: bool, padded: bool) -> String {
         let mut hex = "0x".to_string();
-        let mut is_started = false;
+        let mut is_started = padded;
 
         for i in (0..N).rev() {
             if is_started || self.digits[i] > 0 {
                 if is_started {
-                    hex += &format!("{:016X}", self.digits[i]);
+                    if lower {
+                        hex += &format!("{:016x}", self.digits[i]);
+                    } else {
+                        hex += &format!("{:016X}", self.digits[i]);
+                    }
                 } else {
-                    hex += &format!("{:X}", self.digits[i]);
+                    if lower {
+                        hex += &format!("{:x}", self.digits[i]);
+                    } else {
+                        hex += &format!("{:X}", self.digits[i]);
+                    }
                     is_started = true;
                 }
             }
@@ -66,37 +169,123 @@ impl<const N: usize> Bigi<N> {
         hex
     }
 
-    /// Converts hex string into an integer.
+    /// Converts hex string into an integer. An empty digit string gives zero,
+    /// otherwise it panics if the string is not a valid hex (see `parse_hex`).
     pub fn from_hex(hex: &str) -> Bigi<N> {
-        let hex_without_pref = hex.trim_start_matches("0x");
-        let mut res = Bigi::<N>::from(0);
+        match Self::parse_hex(hex) {
+            Err("Empty hex string") => Bigi::<N>::from(0),
+            res => res.unwrap(),
+        }
+    }
 
-        let length = hex_without_pref.chars().count();
+    /// Parses hex string into an integer. The digits can be in upper
+    /// or lower case, the prefix `0x` is optional and the digits can be
+    /// separated by `_`. Leading zeros are allowed in any amount.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// assert_eq!(Bigi::<4>::parse_hex("0xdead_BEEF"), Ok(bigi![4; 0xdeadbeef]));
+    /// assert_eq!(Bigi::<4>::parse_hex("1c"), Ok(bigi![4; 28]));
+    /// assert_eq!(Bigi::<4>::parse_hex("0x1g"), Err("Invalid hex digit"));
+    /// assert_eq!(Bigi::<1>::parse_hex("0x10000000000000000"), Err("Hex number overflow"));
+    /// ```
+    pub fn parse_hex(hex: &str) -> Result<Bigi<N>, &'static str> {
+        let digits = hex.strip_prefix("0x")
+            .or_else(|| hex.strip_prefix("0X"))
+            .unwrap_or(hex);
+        let mut res = Bigi::<N>::new();
+        let mut count = 0;
 
-        for i in 0..N {
-            if 16 * i >= length {
-                break;
+        for ch in digits.chars().rev() {
+            if ch == '_' {
+                continue;
+            }
+            let nibble = ch.to_digit(16).ok_or("Invalid hex digit")? as u64;
+            if nibble > 0 {
+                if count >= N << 4 {
+                    return Err("Hex number overflow");
+                }
+                res.digits[count >> 4] |= nibble << ((count & 15) << 2);
             }
+            count += 1;
+        }
 
-            let start_idx = if length >= 16 * (i + 1) {
-                length - 16 * (i + 1)
-            } else { 0 };
-            let end_idx = length - 16 * i;
-            let hex_sliced = &hex_without_pref[start_idx..end_idx];
-            res.digits[i] = u64::from_str_radix(hex_sliced, 16).unwrap();
+        if count == 0 {
+            return Err("Empty hex string");
         }
 
-        res
+        Ok(res)
+    }
+
+    /// Converts the integer into a binary string.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// assert_eq!(bigi![4; 11].to_binary(), "0b1011");
+    /// ```
+    pub fn to_binary(&self) -> String {
+        let mut binary = "0b".to_string();
+        binary.extend(self.bits_be().map(|bit| if bit { '1' } else { '0' }));
+
+        if binary == "0b" {
+            binary += "0";
+        }
+
+        binary
+    }
+
+    /// Converts binary string into an integer. It panics if the string is not
+    /// a valid binary (see `parse_binary`).
+    pub fn from_binary(binary: &str) -> Bigi<N> {
+        Self::parse_binary(binary).unwrap()
+    }
+
+    /// Parses binary string into an integer. The prefix `0b` is optional
+    /// and the digits can be separated by `_`.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// assert_eq!(Bigi::<4>::parse_binary("0b1011"), Ok(bigi![4; 11]));
+    /// assert_eq!(Bigi::<4>::parse_binary("1_0000"), Ok(bigi![4; 16]));
+    /// assert_eq!(Bigi::<4>::parse_binary("0b102"), Err("Invalid binary digit"));
+    /// ```
+    pub fn parse_binary(binary: &str) -> Result<Bigi<N>, &'static str> {
+        let digits = binary.strip_prefix("0b")
+            .or_else(|| binary.strip_prefix("0B"))
+            .unwrap_or(binary);
+        let mut res = Bigi::<N>::new();
+        let mut count = 0;
+
+        for ch in digits.chars().rev() {
+            match ch {
+                '_' => continue,
+                '0' => {},
+                '1' => {
+                    if count >= N << 6 {
+                        return Err("Binary number overflow");
+                    }
+                    res.set_bit(count);
+                },
+                _ => return Err("Invalid binary digit"),
+            }
+            count += 1;
+        }
+
+        if count == 0 {
+            return Err("Empty binary string");
+        }
+
+        Ok(res)
     }
 
     /// Converts the integer into a vector of bytes.
+    /// It is the same as `to_bytes_le`.
     pub fn to_bytes(&self) -> Vec<u8> {
-        self.digits.iter()
-            .map(|digit| digit.to_le_bytes())
-            .collect::<Vec<[u8; 8]>>().concat()
+        self.to_bytes_le()
     }
 
-    /// Converts bytes into an integer.
+    /// Converts bytes into an integer. The bytes are little-endian
+    /// and there must be at least `8 * N` of them.
     pub fn from_bytes(bytes: &[u8]) -> Bigi<N> {
         let mut res = Bigi::<N>::from(0);
 
@@ -108,6 +297,422 @@ impl<const N: usize> Bigi<N> {
 
         res
     }
+
+    /// Converts the integer into `8 * N` little-endian bytes.
+    pub fn to_bytes_le(&self) -> Vec<u8> {
+        self.digits.iter()
+            .map(|digit| digit.to_le_bytes())
+            .collect::<Vec<[u8; 8]>>().concat()
+    }
+
+    /// Converts the integer into `8 * N` big-endian bytes.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// assert_eq!(bigi![1; 0x0102].to_bytes_be(), vec![0, 0, 0, 0, 0, 0, 1, 2]);
+    /// ```
+    pub fn to_bytes_be(&self) -> Vec<u8> {
+        self.digits.iter().rev()
+            .map(|digit| digit.to_be_bytes())
+            .collect::<Vec<[u8; 8]>>().concat()
+    }
+
+    /// Converts the integer into little-endian bytes without leading zeros,
+    /// so zero gives no bytes.
+    pub fn to_bytes_le_trimmed(&self) -> Vec<u8> {
+        self.to_bytes_le_padded(self.byte_length())
+    }
+
+    /// Converts the integer into big-endian bytes without leading zeros,
+    /// so zero gives no bytes.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// assert_eq!(bigi![4; 0x0102].to_bytes_be_trimmed(), vec![1, 2]);
+    /// ```
+    pub fn to_bytes_be_trimmed(&self) -> Vec<u8> {
+        self.to_bytes_be_padded(self.byte_length())
+    }
+
+    /// Converts the integer into exactly `size` little-endian bytes padded
+    /// with zeros. It panics if the integer does not fit.
+    pub fn to_bytes_le_padded(&self, size: usize) -> Vec<u8> {
+        assert!(self.bit_length() <= size << 3, "Integer does not fit");
+        let mut bytes = self.to_bytes_le();
+        bytes.resize(size, 0);
+        bytes
+    }
+
+    /// Converts the integer into exactly `size` big-endian bytes padded
+    /// with zeros. It panics if the integer does not fit.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// assert_eq!(bigi![4; 0x0102].to_bytes_be_padded(4), vec![0, 0, 1, 2]);
+    /// ```
+    pub fn to_bytes_be_padded(&self, size: usize) -> Vec<u8> {
+        let mut bytes = self.to_bytes_le_padded(size);
+        bytes.reverse();
+        bytes
+    }
+
+    /// Converts little-endian bytes of any length into an integer. It panics
+    /// if the integer does not fit.
+    pub fn from_bytes_le(bytes: &[u8]) -> Bigi<N> {
+        Self::read_bytes_le(bytes.iter()).expect("Integer does not fit")
+    }
+
+    /// Converts big-endian bytes of any length into an integer. It panics
+    /// if the integer does not fit.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// assert_eq!(Bigi::<4>::from_bytes_be(&[1, 2]), bigi![4; 0x0102]);
+    /// ```
+    pub fn from_bytes_be(bytes: &[u8]) -> Bigi<N> {
+        Self::read_bytes_le(bytes.iter().rev()).expect("Integer does not fit")
+    }
+
+    /// Reads an integer from little-endian bytes, returns `None` if it does
+    /// not fit.
+    fn read_bytes_le<'a, I>(bytes: I) -> Option<Bigi<N>>
+            where I: Iterator<Item = &'a u8> {
+        let mut res = Bigi::<N>::new();
+        for (i, byte) in bytes.enumerate() {
+            if *byte > 0 {
+                if i >= N << 3 {
+                    return None;
+                }
+                res.set_byte(i, *byte);
+            }
+        }
+        Some(res)
+    }
+
+    /// Converts the integer into exactly `len` big-endian bytes of two's
+    /// complement, the integer is treated as negative if `negative` is true
+    /// (so it is the absolute value). It panics if the signed value does
+    /// not fit.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// assert_eq!(bigi![4; 0x80].to_twos_complement_bytes_be(false, 3), vec![0, 0, 0x80]);
+    /// assert_eq!(bigi![4; 0x80].to_twos_complement_bytes_be(true, 3), vec![0xff, 0xff, 0x80]);
+    /// ```
+    pub fn to_twos_complement_bytes_be(&self, negative: bool, len: usize) -> Vec<u8> {
+        let mut bytes = self.to_bytes_be_padded(len);
+        if negative && !self.is_zero() {
+            negate_bytes_be(&mut bytes);
+            assert!(bytes[0] & 0x80 != 0, "Integer does not fit");
+        } else {
+            assert!(bytes.first().is_none_or(|b| *b & 0x80 == 0),
+                    "Integer does not fit");
+        }
+        bytes
+    }
+
+    /// Converts the integer into big-endian bytes of two's complement
+    /// of the minimal length including the sign bit, the same as
+    /// `BigInteger.toByteArray` in Java does.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// assert_eq!(bigi![4; 0].to_twos_complement_bytes_be_trimmed(false), vec![0]);
+    /// assert_eq!(bigi![4; 0x80].to_twos_complement_bytes_be_trimmed(false), vec![0, 0x80]);
+    /// assert_eq!(bigi![4; 0x80].to_twos_complement_bytes_be_trimmed(true), vec![0x80]);
+    /// ```
+    pub fn to_twos_complement_bytes_be_trimmed(&self, negative: bool) -> Vec<u8> {
+        // -x needs as many bits as x - 1 plus the sign
+        let bits = if negative && !self.is_zero() {
+            (*self - 1).bit_length()
+        } else {
+            self.bit_length()
+        };
+        self.to_twos_complement_bytes_be(negative, (bits >> 3) + 1)
+    }
+
+    /// Converts big-endian bytes of two's complement (for example, from
+    /// `BigInteger.toByteArray` in Java) into the absolute value and
+    /// the sign flag that is true for negative numbers. It panics if
+    /// the absolute value does not fit.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// assert_eq!(Bigi::<4>::from_twos_complement_bytes_be(&[0, 0x80]), (bigi![4; 0x80], false));
+    /// assert_eq!(Bigi::<4>::from_twos_complement_bytes_be(&[0xff, 0x80]), (bigi![4; 0x80], true));
+    /// ```
+    pub fn from_twos_complement_bytes_be(bytes: &[u8]) -> (Bigi<N>, bool) {
+        let negative = bytes.first().is_some_and(|b| *b & 0x80 != 0);
+        if negative {
+            let mut bytes = bytes.to_vec();
+            negate_bytes_be(&mut bytes);
+            (Self::from_bytes_be(&bytes), true)
+        } else {
+            (Self::from_bytes_be(bytes), false)
+        }
+    }
+
+    /// Converts the integer into
+    /// [OpenPGP MPI](https://www.rfc-editor.org/rfc/rfc4880#section-3.2):
+    /// two bytes of the bit length followed by the big-endian bytes
+    /// without leading zeros. It panics if the bit length does not fit into
+    /// two bytes (the integer is longer than 65535 bits).
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// assert_eq!(bigi![4; 511].to_mpi(), vec![0, 9, 1, 255]);
+    /// ```
+    pub fn to_mpi(&self) -> Vec<u8> {
+        let bits = self.bit_length();
+        assert!(bits <= u16::MAX as usize, "Too large for MPI");
+        let mut res = (bits as u16).to_be_bytes().to_vec();
+        res.extend(self.to_bytes_be_trimmed());
+        res
+    }
+
+    /// Reads [OpenPGP MPI](https://www.rfc-editor.org/rfc/rfc4880#section-3.2)
+    /// from the beginning of `bytes`. It returns the integer and the number
+    /// of bytes read, so the next value starts right after it.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// assert_eq!(Bigi::<4>::from_mpi(&[0, 9, 1, 255, 0, 1, 1]), Ok((bigi![4; 511], 4)));
+    /// assert_eq!(Bigi::<4>::from_mpi(&[0, 9, 1]), Err("Truncated MPI"));
+    /// ```
+    pub fn from_mpi(bytes: &[u8]) -> Result<(Bigi<N>, usize), &'static str> {
+        if bytes.len() < 2 {
+            return Err("Truncated MPI");
+        }
+        let bits = u16::from_be_bytes([bytes[0], bytes[1]]) as usize;
+        let size = (bits + 7) >> 3;
+        if bytes.len() < size + 2 {
+            return Err("Truncated MPI");
+        }
+        if bits > N << 6 {
+            return Err("MPI overflow");
+        }
+
+        let res = Self::read_bytes_le(bytes[2..size + 2].iter().rev())
+            .ok_or("MPI overflow")?;
+        if res.bit_length() != bits {
+            return Err("Invalid MPI bit length");
+        }
+
+        Ok((res, size + 2))
+    }
+
+    /// Converts the integer into SSH
+    /// [mpint](https://www.rfc-editor.org/rfc/rfc4251#section-5): four bytes
+    /// of the length followed by the big-endian bytes without leading zeros.
+    /// A zero byte is prepended if the highest bit is set, because mpint
+    /// is signed.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// assert_eq!(bigi![4; 0x1234].to_ssh_mpint(), vec![0, 0, 0, 2, 0x12, 0x34]);
+    /// assert_eq!(bigi![4; 0x80].to_ssh_mpint(), vec![0, 0, 0, 2, 0, 0x80]);
+    /// ```
+    pub fn to_ssh_mpint(&self) -> Vec<u8> {
+        // One more bit for the sign
+        let size = if self.is_zero() { 0 } else { (self.bit_length() + 8) >> 3 };
+        let mut res = (size as u32).to_be_bytes().to_vec();
+        res.extend(self.to_bytes_be_padded(size));
+        res
+    }
+
+    /// Reads SSH [mpint](https://www.rfc-editor.org/rfc/rfc4251#section-5)
+    /// from the beginning of `bytes`. It returns the integer and the number
+    /// of bytes read. Negative and non-minimal encodings are rejected.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// assert_eq!(Bigi::<4>::from_ssh_mpint(&[0, 0, 0, 2, 0, 0x80]), Ok((bigi![4; 0x80], 6)));
+    /// assert_eq!(Bigi::<4>::from_ssh_mpint(&[0, 0, 0, 1, 0x80]), Err("Negative mpint"));
+    /// ```
+    pub fn from_ssh_mpint(bytes: &[u8]) -> Result<(Bigi<N>, usize), &'static str> {
+        if bytes.len() < 4 {
+            return Err("Truncated mpint");
+        }
+        let size = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+            as usize;
+        if bytes.len() - 4 < size {
+            return Err("Truncated mpint");
+        }
+        let body = &bytes[4..size + 4];
+
+        if let Some(first) = body.first() {
+            if *first & 0x80 != 0 {
+                return Err("Negative mpint");
+            }
+            if *first == 0 && body.get(1).is_none_or(|b| *b & 0x80 == 0) {
+                return Err("Non-minimal mpint");
+            }
+        }
+
+        let res = Self::read_bytes_le(body.iter().rev()).ok_or("Mpint overflow")?;
+        Ok((res, size + 4))
+    }
+
+    /// Converts the integer into [CBOR](https://www.rfc-editor.org/rfc/rfc8949#section-3.4.3)
+    /// using the preferred serialization: an unsigned integer if it fits
+    /// into *u64*, otherwise a byte string of the big-endian bytes with
+    /// tag 2 (unsigned bignum).
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// assert_eq!(bigi![4; 500].to_cbor(), vec![0x19, 0x01, 0xf4]);
+    /// assert_eq!(bigi![4; 0, 1].to_cbor(), vec![0xc2, 0x49, 1, 0, 0, 0, 0, 0, 0, 0, 0]);
+    /// ```
+    pub fn to_cbor(&self) -> Vec<u8> {
+        if self.get_order() <= 1 {
+            return cbor_head(0, self.digits[0]);
+        }
+        let bytes = self.to_bytes_be_trimmed();
+        let mut res = vec![0xc2];
+        res.extend(cbor_head(2, bytes.len() as u64));
+        res.extend(bytes);
+        res
+    }
+
+    /// Reads [CBOR](https://www.rfc-editor.org/rfc/rfc8949#section-3.4.3)
+    /// unsigned integer or unsigned bignum (tag 2) from the beginning
+    /// of `bytes`. It returns the integer and the number of bytes read.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// assert_eq!(Bigi::<4>::from_cbor(&[0x19, 0x01, 0xf4]), Ok((bigi![4; 500], 3)));
+    /// assert_eq!(Bigi::<4>::from_cbor(&[0xc2, 0x42, 0x01, 0xf4]), Ok((bigi![4; 500], 4)));
+    /// ```
+    pub fn from_cbor(bytes: &[u8]) -> Result<(Bigi<N>, usize), &'static str> {
+        let (major, value, offset) = read_cbor_head(bytes)?;
+        match major {
+            0 => Ok((Bigi::<N>::from(value), offset)),
+            6 if value == 2 => {
+                let (major, size, start) = read_cbor_head(&bytes[offset..])?;
+                if major != 2 {
+                    return Err("Bignum must be a byte string");
+                }
+                let start = offset + start;
+                if ((bytes.len() - start) as u64) < size {
+                    return Err("Truncated CBOR");
+                }
+                let end = start + size as usize;
+
+                let res = Self::read_bytes_le(bytes[start..end].iter().rev())
+                    .ok_or("CBOR bignum overflow")?;
+                Ok((res, end))
+            },
+            _ => Err("Not an unsigned integer"),
+        }
+    }
+
+    /// Converts the integer into words of `size` bytes the same way as
+    /// [mpz_export](https://gmplib.org/manual/Integer-Import-and-Export)
+    /// of GMP with zero nails: `order` is `1` for the most significant word
+    /// first or `-1` for the least significant first, `endian` is `1` for
+    /// big-endian bytes in a word, `-1` for little-endian and `0` for the
+    /// native one. The number of words is minimal, so zero gives no words.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// assert_eq!(bigi![4; 0x010203].export_words(1, 2, 1), vec![0, 1, 2, 3]);
+    /// assert_eq!(bigi![4; 0x010203].export_words(-1, 2, -1), vec![3, 2, 1, 0]);
+    /// ```
+    pub fn export_words(&self, order: i32, size: usize, endian: i32
+                        ) -> Vec<u8> {
+        assert!(order == 1 || order == -1, "Invalid word order");
+        assert!(size > 0, "Invalid word size");
+        let count = self.byte_length().div_ceil(size);
+        let mut bytes = self.to_bytes_le_padded(count * size);
+        Self::reorder_words(&mut bytes, order, size, endian);
+        bytes
+    }
+
+    /// Converts words of `size` bytes into an integer the same way as
+    /// [mpz_import](https://gmplib.org/manual/Integer-Import-and-Export)
+    /// of GMP with zero nails. The meaning of `order` and `endian` is
+    /// the same as in `export_words`.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// assert_eq!(Bigi::<4>::import_words(&[0, 1, 2, 3], 1, 2, 1), Ok(bigi![4; 0x010203]));
+    /// ```
+    pub fn import_words(data: &[u8], order: i32, size: usize, endian: i32
+                        ) -> Result<Bigi<N>, &'static str> {
+        assert!(order == 1 || order == -1, "Invalid word order");
+        assert!(size > 0, "Invalid word size");
+        if !data.len().is_multiple_of(size) {
+            return Err("Incomplete word");
+        }
+        let mut bytes = data.to_vec();
+        Self::reorder_words(&mut bytes, order, size, endian);
+
+        Self::read_bytes_le(bytes.iter()).ok_or("Import overflow")
+    }
+
+    /// Converts little-endian bytes into words with the given order and
+    /// endianness and back, as the conversion is an involution.
+    fn reorder_words(bytes: &mut [u8], order: i32, size: usize, endian: i32) {
+        let big_endian = match endian {
+            1 => true,
+            -1 => false,
+            0 => cfg!(target_endian = "big"),
+            _ => panic!("Invalid endianness"),
+        };
+        if order == 1 {
+            bytes.reverse();
+            if !big_endian {
+                bytes.chunks_mut(size).for_each(|word| word.reverse());
+            }
+        } else if big_endian {
+            bytes.chunks_mut(size).for_each(|word| word.reverse());
+        }
+    }
+}
+
+
+/// Negates big-endian bytes of two's complement in place.
+fn negate_bytes_be(bytes: &mut [u8]) {
+    let mut carry = true;
+    for byte in bytes.iter_mut().rev() {
+        (*byte, carry) = (!*byte).overflowing_add(carry as u8);
+    }
+}
+
+
+/// Encodes CBOR head of the major type and the argument in the shortest form.
+fn cbor_head(major: u8, value: u64) -> Vec<u8> {
+    let (info, size) = match value {
+        0..=23 => (value as u8, 0),
+        24..=0xff => (24, 1),
+        0x100..=0xffff => (25, 2),
+        0x10000..=0xffffffff => (26, 4),
+        _ => (27, 8),
+    };
+    let mut res = vec![(major << 5) | info];
+    res.extend(&value.to_be_bytes()[8 - size..]);
+    res
+}
+
+
+/// Decodes CBOR head into the major type, the argument and its size.
+fn read_cbor_head(bytes: &[u8]) -> Result<(u8, u64, usize), &'static str> {
+    let first = *bytes.first().ok_or("Truncated CBOR")?;
+    let info = first & 31;
+    let size = match info {
+        0..=23 => return Ok((first >> 5, info as u64, 1)),
+        24 => 1,
+        25 => 2,
+        26 => 4,
+        27 => 8,
+        _ => return Err("Unsupported CBOR argument"),
+    };
+    if bytes.len() <= size {
+        return Err("Truncated CBOR");
+    }
+    let value = bytes[1..size + 1].iter()
+        .fold(0u64, |acc, byte| (acc << 8) | (*byte as u64));
+    Ok((first >> 5, value, size + 1))
 }
 
 
@@ -130,6 +735,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_write_decimal() {
+        let mut s = String::new();
+        bigi![8; 0].write_decimal(&mut s).unwrap();
+        bigi![8; 28].write_decimal(&mut s).unwrap();
+        assert_eq!(s, "028");
+        for x in [bigi![8; 10_000_000_000_000_000_000],
+                  bigi![8; 9_999_999_999_999_999_999],
+                  bigi![8; 7, 0, 5], bigi![8; 0, 0, 0, 0, 0, 0, 0, 1]] {
+            let mut s = String::new();
+            x.write_decimal(&mut s).unwrap();
+            assert_eq!(Bigi::<8>::from_decimal(&s), x);
+        }
+    }
+
+    #[test]
+    fn test_grouped() {
+        assert_eq!(bigi![8; 0].grouped(',', 3).to_string(), "0");
+        assert_eq!(bigi![8; 999].grouped(',', 3).to_string(), "999");
+        assert_eq!(bigi![8; 1000].grouped(',', 3).to_string(), "1,000");
+        assert_eq!(bigi![8; 123456].grouped(',', 3).to_string(), "123,456");
+        assert_eq!(bigi![8; 123456].grouped('_', 1).to_string(), "1_2_3_4_5_6");
+        assert_eq!(
+            bigi![8; 0, 1].grouped('\'', 3).to_string(),
+            "18'446'744'073'709'551'616"
+        );
+    }
+
     #[test]
     fn test_from_decimal() {
         assert_eq!(Bigi::<8>::from_decimal("0"), bigi![8; 0]);
@@ -157,9 +790,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_to_hex_lower() {
+        assert_eq!(bigi![2; 0].to_hex_lower(), "0x0");
+        assert_eq!(bigi![2; 28].to_hex_lower(), "0x1c");
+        assert_eq!(bigi![2; 0xABCDEF, 0xF].to_hex_lower(), "0xf0000000000abcdef");
+        assert_eq!(bigi![2; 0xABCDEF, 0xF].to_hex_padded(),
+                   "0x000000000000000F0000000000ABCDEF");
+        assert_eq!(bigi![2; 0].to_hex_lower_padded(),
+                   "0x00000000000000000000000000000000");
+        let x = bigi![2; 0xABCDEF, 0xF];
+        assert_eq!(Bigi::<2>::from_hex(&x.to_hex_lower_padded()), x);
+    }
+
     #[test]
     fn test_from_hex() {
         assert_eq!(Bigi::<8>::from_hex("0x0"), bigi![8; 0]);
+        assert_eq!(Bigi::<8>::from_hex(""), bigi![8; 0]);
+        assert_eq!(Bigi::<8>::from_hex("0x"), bigi![8; 0]);
         assert_eq!(Bigi::<8>::from_hex("0x1C"), bigi![8; 28]);
         assert_eq!(
             Bigi::<8>::from_hex(
@@ -171,6 +819,55 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_hex() {
+        assert_eq!(Bigi::<2>::parse_hex("0"), Ok(bigi![2; 0]));
+        assert_eq!(Bigi::<2>::parse_hex("0X1c"), Ok(bigi![2; 28]));
+        assert_eq!(Bigi::<2>::parse_hex("abc"), Ok(bigi![2; 0xabc]));
+        assert_eq!(
+            Bigi::<2>::parse_hex("0x1_0000_0000_0000_000F"),
+            Ok(bigi![2; 15, 1])
+        );
+        assert_eq!(
+            Bigi::<2>::parse_hex("0x00000ffffffffffffffffffffffffffffffff"),
+            Ok(bigi![2; u64::MAX, u64::MAX])
+        );
+        assert_eq!(Bigi::<2>::parse_hex(""), Err("Empty hex string"));
+        assert_eq!(Bigi::<2>::parse_hex("0x_"), Err("Empty hex string"));
+        assert_eq!(Bigi::<2>::parse_hex("0x-1"), Err("Invalid hex digit"));
+        assert_eq!(Bigi::<2>::parse_hex("0x1 2"), Err("Invalid hex digit"));
+        assert_eq!(
+            Bigi::<2>::parse_hex("0x1ffffffffffffffffffffffffffffffff"),
+            Err("Hex number overflow")
+        );
+    }
+
+    #[test]
+    fn test_to_binary() {
+        assert_eq!(bigi![2; 0].to_binary(), "0b0");
+        assert_eq!(bigi![2; 1].to_binary(), "0b1");
+        assert_eq!(bigi![2; 28].to_binary(), "0b11100");
+        assert_eq!(bigi![2; 0, 1].to_binary(), format!("0b1{}", "0".repeat(64)));
+    }
+
+    #[test]
+    fn test_parse_binary() {
+        assert_eq!(Bigi::<2>::parse_binary("0"), Ok(bigi![2; 0]));
+        assert_eq!(Bigi::<2>::parse_binary("0B0001_1100"), Ok(bigi![2; 28]));
+        assert_eq!(
+            Bigi::<2>::parse_binary(&format!("0b1{}1", "0".repeat(63))),
+            Ok(bigi![2; 1, 1])
+        );
+        assert_eq!(Bigi::<2>::parse_binary("0b"), Err("Empty binary string"));
+        assert_eq!(Bigi::<2>::parse_binary("0b12"), Err("Invalid binary digit"));
+        assert_eq!(
+            Bigi::<2>::parse_binary(&format!("0b1{}", "0".repeat(128))),
+            Err("Binary number overflow")
+        );
+        let x = bigi![2; 0xABCDEF, 0xF];
+        assert_eq!(Bigi::<2>::from_binary(&x.to_binary()), x);
+    }
+
     #[test]
     fn test_to_bytes() {
         assert_eq!(
@@ -245,6 +942,228 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_mpi() {
+        assert_eq!(bigi![2; 0].to_mpi(), vec![0, 0]);
+        assert_eq!(bigi![2; 1].to_mpi(), vec![0, 1, 1]);
+        assert_eq!(bigi![2; 0x1ff].to_mpi(), vec![0, 9, 1, 0xff]);
+        assert_eq!(bigi![2; 0x0807060504030201, 0x09].to_mpi(),
+                   vec![0, 68, 9, 8, 7, 6, 5, 4, 3, 2, 1]);
+
+        assert_eq!(Bigi::<2>::from_mpi(&[0, 0]), Ok((bigi![2; 0], 2)));
+        assert_eq!(Bigi::<2>::from_mpi(&[0, 1, 1, 7]), Ok((bigi![2; 1], 3)));
+        assert_eq!(Bigi::<2>::from_mpi(&[0, 68, 9, 8, 7, 6, 5, 4, 3, 2, 1]),
+                   Ok((bigi![2; 0x0807060504030201, 0x09], 11)));
+        assert_eq!(Bigi::<2>::from_mpi(&[0]), Err("Truncated MPI"));
+        assert_eq!(Bigi::<2>::from_mpi(&[0, 9, 1]), Err("Truncated MPI"));
+        assert_eq!(Bigi::<2>::from_mpi(&[0, 2, 1]), Err("Invalid MPI bit length"));
+        assert_eq!(Bigi::<1>::from_mpi(&[0, 65, 1, 0, 0, 0, 0, 0, 0, 0, 0]),
+                   Err("MPI overflow"));
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..10 {
+            let x = Bigi::<4>::gen_random(&mut rng, 200, false);
+            assert_eq!(Bigi::<4>::from_mpi(&x.to_mpi()), Ok((x, x.to_mpi().len())));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Too large for MPI")]
+    fn test_mpi_too_large() {
+        let x = Bigi::<1025>::from(1) << 65535;
+        x.to_mpi();
+    }
+
+    #[test]
+    fn test_ssh_mpint() {
+        // Test vectors from RFC 4251
+        assert_eq!(bigi![2; 0].to_ssh_mpint(), vec![0, 0, 0, 0]);
+        assert_eq!(bigi![2; 0x9a378f9b2e332a7].to_ssh_mpint(),
+                   vec![0, 0, 0, 8, 0x09, 0xa3, 0x78, 0xf9, 0xb2, 0xe3, 0x32, 0xa7]);
+        assert_eq!(bigi![2; 0x80].to_ssh_mpint(), vec![0, 0, 0, 2, 0, 0x80]);
+        assert_eq!(bigi![2; 0, 0x8000000000000000].to_ssh_mpint().len(), 21);
+
+        assert_eq!(Bigi::<2>::from_ssh_mpint(&[0, 0, 0, 0]), Ok((bigi![2; 0], 4)));
+        assert_eq!(
+            Bigi::<2>::from_ssh_mpint(&[0, 0, 0, 8, 0x09, 0xa3, 0x78, 0xf9,
+                                        0xb2, 0xe3, 0x32, 0xa7, 0xff]),
+            Ok((bigi![2; 0x9a378f9b2e332a7], 12))
+        );
+        assert_eq!(Bigi::<2>::from_ssh_mpint(&[0, 0, 0]), Err("Truncated mpint"));
+        assert_eq!(Bigi::<2>::from_ssh_mpint(&[0, 0, 0, 2, 1]), Err("Truncated mpint"));
+        assert_eq!(Bigi::<2>::from_ssh_mpint(&[0, 0, 0, 1, 0xed]), Err("Negative mpint"));
+        assert_eq!(Bigi::<2>::from_ssh_mpint(&[0, 0, 0, 1, 0]), Err("Non-minimal mpint"));
+        assert_eq!(Bigi::<2>::from_ssh_mpint(&[0, 0, 0, 2, 0, 1]), Err("Non-minimal mpint"));
+        assert_eq!(Bigi::<1>::from_ssh_mpint(&[0, 0, 0, 9, 1, 0, 0, 0, 0, 0, 0, 0, 0]),
+                   Err("Mpint overflow"));
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..10 {
+            let x = Bigi::<4>::gen_random(&mut rng, 256, false);
+            let data = x.to_ssh_mpint();
+            assert_eq!(Bigi::<4>::from_ssh_mpint(&data), Ok((x, data.len())));
+        }
+    }
+
+    #[test]
+    fn test_cbor() {
+        // Examples from RFC 8949 (Appendix A)
+        assert_eq!(bigi![2; 0].to_cbor(), vec![0x00]);
+        assert_eq!(bigi![2; 23].to_cbor(), vec![0x17]);
+        assert_eq!(bigi![2; 24].to_cbor(), vec![0x18, 0x18]);
+        assert_eq!(bigi![2; 1000000].to_cbor(), vec![0x1a, 0x00, 0x0f, 0x42, 0x40]);
+        assert_eq!(bigi![2; u64::MAX].to_cbor(),
+                   vec![0x1b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]);
+        assert_eq!(bigi![2; 0, 1].to_cbor(),
+                   vec![0xc2, 0x49, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+
+        assert_eq!(Bigi::<2>::from_cbor(&[0x18, 0x18]), Ok((bigi![2; 24], 2)));
+        assert_eq!(
+            Bigi::<2>::from_cbor(&[0xc2, 0x49, 0x01, 0x00, 0x00, 0x00, 0x00,
+                                   0x00, 0x00, 0x00, 0x00]),
+            Ok((bigi![2; 0, 1], 11))
+        );
+        // Leading zeros and empty strings are allowed in bignums
+        assert_eq!(Bigi::<2>::from_cbor(&[0xc2, 0x43, 0, 0, 7]), Ok((bigi![2; 7], 5)));
+        assert_eq!(Bigi::<2>::from_cbor(&[0xc2, 0x40]), Ok((bigi![2; 0], 2)));
+        assert_eq!(Bigi::<2>::from_cbor(&[]), Err("Truncated CBOR"));
+        assert_eq!(Bigi::<2>::from_cbor(&[0x19, 0x01]), Err("Truncated CBOR"));
+        assert_eq!(Bigi::<2>::from_cbor(&[0xc2, 0x43, 0, 0]), Err("Truncated CBOR"));
+        assert_eq!(Bigi::<2>::from_cbor(&[0x20]), Err("Not an unsigned integer"));
+        assert_eq!(Bigi::<2>::from_cbor(&[0xc3, 0x41, 1]), Err("Not an unsigned integer"));
+        assert_eq!(Bigi::<2>::from_cbor(&[0xc2, 0x01]), Err("Bignum must be a byte string"));
+        assert_eq!(Bigi::<1>::from_cbor(&[0xc2, 0x49, 1, 0, 0, 0, 0, 0, 0, 0, 0]),
+                   Err("CBOR bignum overflow"));
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..10 {
+            let x = Bigi::<4>::gen_random(&mut rng, 256, false);
+            let data = x.to_cbor();
+            assert_eq!(Bigi::<4>::from_cbor(&data), Ok((x, data.len())));
+        }
+    }
+
+    #[test]
+    fn test_export_words() {
+        let x = bigi![2; 0x0807060504030201, 0x0a09];
+        assert_eq!(bigi![2; 0].export_words(1, 4, 1), vec![]);
+        assert_eq!(x.export_words(1, 4, 1),
+                   vec![0, 0, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1]);
+        assert_eq!(x.export_words(1, 4, -1),
+                   vec![9, 10, 0, 0, 5, 6, 7, 8, 1, 2, 3, 4]);
+        assert_eq!(x.export_words(-1, 4, 1),
+                   vec![4, 3, 2, 1, 8, 7, 6, 5, 0, 0, 10, 9]);
+        assert_eq!(x.export_words(-1, 4, -1),
+                   vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 0, 0]);
+        assert_eq!(x.export_words(1, 1, 0), vec![10, 9, 8, 7, 6, 5, 4, 3, 2, 1]);
+        assert_eq!(x.export_words(-1, 8, 0).len(), 16);
+    }
+
+    #[test]
+    fn test_import_words() {
+        let x = bigi![2; 0x0807060504030201, 0x0a09];
+        for order in [1, -1] {
+            for size in [1, 3, 4, 8, 16] {
+                for endian in [1, -1, 0] {
+                    let data = x.export_words(order, size, endian);
+                    assert_eq!(Bigi::<2>::import_words(&data, order, size, endian),
+                               Ok(x));
+                }
+            }
+        }
+        assert_eq!(Bigi::<2>::import_words(&[], 1, 4, 1), Ok(bigi![2; 0]));
+        assert_eq!(Bigi::<2>::import_words(&[1, 2, 3], 1, 2, 1),
+                   Err("Incomplete word"));
+        assert_eq!(Bigi::<1>::import_words(&[0, 0, 0, 0, 0, 0, 0, 0, 0, 1], 1, 1, 1),
+                   Ok(bigi![1; 1]));
+        assert_eq!(Bigi::<1>::import_words(&[1, 0, 0, 0, 0, 0, 0, 0, 0], 1, 1, 1),
+                   Err("Import overflow"));
+    }
+
+    #[test]
+    fn test_bytes_endian() {
+        let x = bigi![2; 0x0807060504030201, 0x0a09];
+        assert_eq!(x.to_bytes_le(), vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(x.to_bytes_be(), vec![0, 0, 0, 0, 0, 0, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1]);
+        assert_eq!(x.to_bytes_le_trimmed(), vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
+        assert_eq!(x.to_bytes_be_trimmed(), vec![10, 9, 8, 7, 6, 5, 4, 3, 2, 1]);
+        assert_eq!(x.to_bytes_le_padded(12), vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 0, 0]);
+        assert_eq!(x.to_bytes_be_padded(12), vec![0, 0, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1]);
+        assert_eq!(x.to_bytes_be_padded(20).len(), 20);
+        assert_eq!(bigi![2; 0].to_bytes_be_trimmed(), vec![]);
+        assert_eq!(bigi![2; 0].to_bytes_le_padded(0), vec![]);
+
+        assert_eq!(Bigi::<2>::from_bytes_le(&[]), bigi![2; 0]);
+        assert_eq!(Bigi::<2>::from_bytes_be(&[1, 0]), bigi![2; 256]);
+        assert_eq!(Bigi::<2>::from_bytes_le(&[1, 0]), bigi![2; 1]);
+        assert_eq!(Bigi::<1>::from_bytes_be(&[0, 0, 1, 2, 3, 4, 5, 6, 7, 8]),
+                   bigi![1; 0x0102030405060708]);
+
+        let mut rng = rand::thread_rng();
+        for bits in [0, 1, 8, 63, 64, 65, 128] {
+            let x = Bigi::<2>::gen_random(&mut rng, bits, false);
+            assert_eq!(Bigi::<2>::from_bytes_le(&x.to_bytes_le()), x);
+            assert_eq!(Bigi::<2>::from_bytes_be(&x.to_bytes_be()), x);
+            assert_eq!(Bigi::<2>::from_bytes_le(&x.to_bytes_le_trimmed()), x);
+            assert_eq!(Bigi::<2>::from_bytes_be(&x.to_bytes_be_trimmed()), x);
+            assert_eq!(Bigi::<2>::from_bytes_le(&x.to_bytes_le_padded(24)), x);
+            assert_eq!(Bigi::<2>::from_bytes_be(&x.to_bytes_be_padded(24)), x);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Integer does not fit")]
+    fn test_bytes_endian_overflow() {
+        Bigi::<1>::from_bytes_be(&[1, 0, 0, 0, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_twos_complement_bytes() {
+        // The same as BigInteger.toByteArray in Java
+        for (x, negative, bytes) in [
+            (0, false, vec![0]), (0, true, vec![0]),
+            (1, false, vec![1]), (1, true, vec![0xff]),
+            (127, false, vec![0x7f]), (128, true, vec![0x80]),
+            (128, false, vec![0, 0x80]), (129, true, vec![0xff, 0x7f]),
+            (255, false, vec![0, 0xff]), (256, true, vec![0xff, 0]),
+            (32768, true, vec![0x80, 0]), (32769, true, vec![0xff, 0x7f, 0xff]),
+        ] {
+            let x = bigi![2; x];
+            assert_eq!(x.to_twos_complement_bytes_be_trimmed(negative), bytes);
+            assert_eq!(Bigi::<2>::from_twos_complement_bytes_be(&bytes),
+                       (x, negative && !x.is_zero()));
+        }
+
+        assert_eq!(bigi![2; 1].to_twos_complement_bytes_be(true, 4), vec![0xff; 4]);
+        assert_eq!(bigi![2; 0].to_twos_complement_bytes_be(true, 0), vec![]);
+        assert_eq!(Bigi::<2>::from_twos_complement_bytes_be(&[]), (bigi![2; 0], false));
+        assert_eq!(Bigi::<1>::from_twos_complement_bytes_be(&[0xff; 12]), (bigi![1; 1], true));
+        assert_eq!(Bigi::<1>::from_twos_complement_bytes_be(&[0x80, 0, 0, 0, 0, 0, 0, 0]),
+                   (bigi![1; 1 << 63], true));
+
+        let mut rng = rand::thread_rng();
+        for bits in [1, 8, 63, 64, 65, 127] {
+            let x = Bigi::<2>::gen_random(&mut rng, bits, false);
+            for negative in [false, true] {
+                let bytes = x.to_twos_complement_bytes_be(negative, 20);
+                assert_eq!(Bigi::<2>::from_twos_complement_bytes_be(&bytes),
+                           (x, negative && !x.is_zero()));
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Integer does not fit")]
+    fn test_twos_complement_bytes_overflow() {
+        bigi![2; 128].to_twos_complement_bytes_be(false, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Integer does not fit")]
+    fn test_twos_complement_bytes_negative_overflow() {
+        bigi![2; 129].to_twos_complement_bytes_be(true, 1);
+    }
+
     #[bench]
     fn bench_to_decimal_256(bencher: &mut Bencher) {
         let mut rng = rand::thread_rng();
@@ -0,0 +1,325 @@
+//! This module implements iterators over the parts of `Bigi`.
+//!
+//! ```rust
+//! use bigi::Bigi;
+//!
+//! let a = Bigi::<4>::from(6);
+//! assert_eq!(a.bits().collect::<Vec<bool>>(), vec![false, true, true]);
+//! assert_eq!(a.bits_be().collect::<Vec<bool>>(), vec![true, true, false]);
+//! assert_eq!(a.windows(2).collect::<Vec<u64>>(), vec![2, 1]);
+//!
+//! let b: Bigi<4> = a.iter_digits().copied().collect();
+//! assert_eq!(b, a);
+//! ```
+
+use std::{iter, slice};
+use crate::base::Bigi;
+
+
+/// Iterator over the significant bits of an integer.
+pub struct Bits<'a, const N: usize> {
+    x: &'a Bigi<N>,
+    start: usize,
+    end: usize,
+}
+
+
+impl<'a, const N: usize> Iterator for Bits<'a, N> {
+    type Item = bool;
+
+    fn next(&mut self) -> Option<bool> {
+        if self.start < self.end {
+            self.start += 1;
+            Some(self.x.get_bit(self.start - 1))
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let size = self.end - self.start;
+        (size, Some(size))
+    }
+}
+
+
+impl<'a, const N: usize> iter::DoubleEndedIterator for Bits<'a, N> {
+    fn next_back(&mut self) -> Option<bool> {
+        if self.start < self.end {
+            self.end -= 1;
+            Some(self.x.get_bit(self.end))
+        } else {
+            None
+        }
+    }
+}
+
+
+impl<'a, const N: usize> iter::ExactSizeIterator for Bits<'a, N> {}
+
+
+/// Iterator over the windows of the significant bits of an integer.
+pub struct Windows<'a, const N: usize> {
+    x: &'a Bigi<N>,
+    width: usize,
+    start: usize,
+    end: usize,
+}
+
+
+impl<'a, const N: usize> Windows<'a, N> {
+    fn get(&self, idx: usize) -> u64 {
+        let pos = idx * self.width;
+        let (q, r) = (pos >> 6, pos & 63);
+        let mut bits = self.x.digits[q] >> r;
+        if r + self.width > 64 && q + 1 < N {
+            bits |= self.x.digits[q + 1] << (64 - r);
+        }
+        bits & (u64::MAX >> (64 - self.width))
+    }
+}
+
+
+impl<'a, const N: usize> Iterator for Windows<'a, N> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        if self.start < self.end {
+            self.start += 1;
+            Some(self.get(self.start - 1))
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let size = self.end - self.start;
+        (size, Some(size))
+    }
+}
+
+
+impl<'a, const N: usize> iter::DoubleEndedIterator for Windows<'a, N> {
+    fn next_back(&mut self) -> Option<u64> {
+        if self.start < self.end {
+            self.end -= 1;
+            Some(self.get(self.end))
+        } else {
+            None
+        }
+    }
+}
+
+
+impl<'a, const N: usize> iter::ExactSizeIterator for Windows<'a, N> {}
+
+
+impl<const N: usize> Bigi<N> {
+    /// Iterates over the significant bits of the integer from the least
+    /// significant one.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// let z = bigi![4; 11];
+    /// assert_eq!(z.bits().collect::<Vec<bool>>(), vec![true, true, false, true]);
+    /// ```
+    pub fn bits(&self) -> Bits<'_, N> {
+        Bits { x: self, start: 0, end: self.bit_length() }
+    }
+
+    /// Iterates over the significant bits of the integer from the most
+    /// significant one.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// let z = bigi![4; 11];
+    /// assert_eq!(z.bits_be().collect::<Vec<bool>>(), vec![true, false, true, true]);
+    /// ```
+    pub fn bits_be(&self) -> iter::Rev<Bits<'_, N>> {
+        self.bits().rev()
+    }
+
+    /// Iterates over the windows of `w` bits (from 1 to 64) of the integer
+    /// from the least significant one, so the integer is the sum of
+    /// `window * 2^(w * i)`. The highest window is padded with zeros.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// let z = bigi![4; 0b1011011];
+    /// assert_eq!(z.windows(3).collect::<Vec<u64>>(), vec![0b011, 0b011, 0b1]);
+    /// assert_eq!(z.windows(3).rev().collect::<Vec<u64>>(), vec![0b1, 0b011, 0b011]);
+    /// ```
+    pub fn windows(&self, w: usize) -> Windows<'_, N> {
+        assert!((1..=64).contains(&w), "Window must be from 1 to 64 bits");
+        Windows { x: self, width: w, start: 0, end: self.bit_length().div_ceil(w) }
+    }
+
+    /// Iterates over the significant *u64* digits of the integer from
+    /// the least significant one.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// let z = bigi![4; 2, 4];
+    /// assert_eq!(z.iter_digits().collect::<Vec<&u64>>(), vec![&2, &4]);
+    /// ```
+    pub fn iter_digits(&self) -> slice::Iter<'_, u64> {
+        self.digits[..self.get_order()].iter()
+    }
+
+    /// Creates an integer from *u64* digits given from the least significant
+    /// one. The digits that do not fit into the integer are ignored.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// let z = Bigi::<4>::from_limbs_iter([2, 4].into_iter());
+    /// assert_eq!(z, bigi![4; 2, 4]);
+    /// ```
+    pub fn from_limbs_iter<I: Iterator<Item = u64>>(limbs: I) -> Self {
+        let mut res = Self::new();
+        for (digit, limb) in res.digits.iter_mut().zip(limbs) {
+            *digit = limb;
+        }
+        res
+    }
+
+    /// Creates an integer from bits given from the least significant one.
+    /// The bits that do not fit into the integer are ignored.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// let z = Bigi::<4>::from_bits_le([true, true, false, true].into_iter());
+    /// assert_eq!(z, bigi![4; 11]);
+    /// ```
+    pub fn from_bits_le<I: Iterator<Item = bool>>(bits: I) -> Self {
+        let mut res = Self::new();
+        for (i, bit) in bits.take(N << 6).enumerate() {
+            if bit {
+                res.set_bit(i);
+            }
+        }
+        res
+    }
+
+    /// Creates an integer from bits given from the most significant one.
+    /// The highest bits that do not fit into the integer are ignored.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// let z = Bigi::<4>::from_bits_be([true, false, true, true].into_iter());
+    /// assert_eq!(z, bigi![4; 11]);
+    /// ```
+    pub fn from_bits_be<I: Iterator<Item = bool>>(bits: I) -> Self {
+        let bits: Vec<bool> = bits.collect();
+        Self::from_bits_le(bits.into_iter().rev())
+    }
+}
+
+
+impl<const N: usize> iter::FromIterator<u64> for Bigi<N> {
+    fn from_iter<I: IntoIterator<Item = u64>>(iter: I) -> Self {
+        Self::from_limbs_iter(iter.into_iter())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::bigi;
+    use super::*;
+    use test::Bencher;
+
+    #[test]
+    fn test_bits() {
+        assert_eq!(bigi![4; 0].bits().count(), 0);
+        assert_eq!(bigi![4; 1].bits().collect::<Vec<bool>>(), vec![true]);
+        let x = bigi![4; 5, 2];
+        let bits: Vec<bool> = x.bits().collect();
+        assert_eq!(bits.len(), 66);
+        assert_eq!(x.bits().len(), 66);
+        for (i, bit) in bits.iter().enumerate() {
+            assert_eq!(*bit, x.get_bit(i));
+        }
+    }
+
+    #[test]
+    fn test_bits_be() {
+        let x = bigi![4; 5, 2];
+        let mut bits: Vec<bool> = x.bits_be().collect();
+        bits.reverse();
+        assert_eq!(bits, x.bits().collect::<Vec<bool>>());
+    }
+
+    #[test]
+    fn test_windows() {
+        assert_eq!(bigi![4; 0].windows(4).count(), 0);
+        assert_eq!(bigi![4; 5, 2].windows(64).collect::<Vec<u64>>(), vec![5, 2]);
+        assert_eq!(bigi![4; 0, 0, 0, 1 << 63].windows(1).len(), 256);
+        assert_eq!(Bigi::<4>::MAX.windows(7).next_back(), Some(0b1111));
+
+        let mut rng = rand::thread_rng();
+        let x = Bigi::<4>::gen_random(&mut rng, 256, false);
+        for w in [1, 3, 5, 7, 16, 63, 64] {
+            let windows: Vec<u64> = x.windows(w).collect();
+            assert_eq!(windows.len(), x.bit_length().div_ceil(w));
+            let mut y = Bigi::<4>::new();
+            for window in x.windows(w).rev() {
+                y = (y << w) + window;
+            }
+            assert_eq!(y, x);
+            assert!(windows.iter().all(|d| w == 64 || *d < 1 << w));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Window must be from 1 to 64 bits")]
+    fn test_windows_zero() {
+        bigi![4; 5].windows(0);
+    }
+
+    #[test]
+    fn test_from_bits() {
+        assert_eq!(Bigi::<2>::from_bits_le(iter::empty()), bigi![2; 0]);
+        assert_eq!(Bigi::<2>::from_bits_be(iter::empty()), bigi![2; 0]);
+        assert_eq!(Bigi::<2>::from_bits_be([false, false, true, false].into_iter()),
+                   bigi![2; 2]);
+        assert_eq!(Bigi::<1>::from_bits_le((0..100).map(|i| i == 64)), bigi![1; 0]);
+        assert_eq!(Bigi::<1>::from_bits_be((0..100).map(|i| i == 99)), bigi![1; 1]);
+
+        let x = bigi![4; 5, 2, 0, 1 << 63];
+        assert_eq!(Bigi::<4>::from_bits_le(x.bits()), x);
+        assert_eq!(Bigi::<4>::from_bits_be(x.bits_be()), x);
+    }
+
+    #[test]
+    fn test_iter_digits() {
+        assert_eq!(bigi![4; 0].iter_digits().count(), 0);
+        assert_eq!(
+            bigi![4; 7, 0, 3].iter_digits().copied().collect::<Vec<u64>>(),
+            vec![7, 0, 3]
+        );
+    }
+
+    #[test]
+    fn test_from_iter() {
+        let x: Bigi<4> = vec![1u64, 2, 3].into_iter().collect();
+        assert_eq!(x, bigi![4; 1, 2, 3]);
+        let x: Bigi<2> = (1u64..10).collect();
+        assert_eq!(x, bigi![2; 1, 2]);
+        let x = bigi![4; 5, 6, 7, 8];
+        assert_eq!(x.iter_digits().copied().collect::<Bigi<4>>(), x);
+    }
+
+    #[bench]
+    fn bench_bits_256(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let x = Bigi::<8>::gen_random(&mut rng, 256, false);
+        bencher.iter(|| x.bits().filter(|b| *b).count());
+    }
+
+    #[bench]
+    fn bench_windows_256(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let x = Bigi::<8>::gen_random(&mut rng, 256, false);
+        bencher.iter(|| x.windows(5).sum::<u64>());
+    }
+}
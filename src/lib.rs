@@ -3,7 +3,7 @@
 //! the standard arithmetic operations, modular arithmetic, some algorithms
 //! for prime numbers (Miller-Rabin primality test, Fermat primality test,
 //! Euclidean algorithm, Tonelli–Shanks algorithm),
-//! Montgomery modular multiplication. Mostly Bigi is designed for cryptography
+//! Montgomery modular multiplication, Barrett reduction. Mostly Bigi is designed for cryptography
 //! issues, but it also can be applied anywhere else. The library is developed
 //! for Rust Nightly strictly.
 //!
@@ -11,6 +11,10 @@
 //! the number type is implemented as a generic structure with a fixed-size
 //! array of *u64*.
 #![feature(test)]
+#![cfg_attr(feature = "simd", feature(portable_simd))]
+// The right operands are borrowed on purpose to avoid copying wide arrays,
+// the owned forms of the operators are for convenience only
+#![allow(clippy::op_ref)]
 extern crate test;
 
 pub mod base;
@@ -21,6 +25,23 @@ pub mod operations;
 pub mod prime;
 pub mod modulo;
 pub mod montgomery;
+pub mod barrett;
+pub mod divider;
+pub mod powtable;
+pub mod ratio;
+pub mod fixed;
+pub mod accumulator;
+pub mod batch;
+pub mod matrix;
+pub mod iter;
+pub mod stream;
+pub mod ec;
+#[cfg(feature = "selftest")]
+pub mod selftest;
+#[cfg(feature = "simd")]
+mod simd;
+#[cfg(all(feature = "adx", target_arch = "x86_64"))]
+mod adx;
 
 pub use base::*;
 pub use convert::*;
@@ -30,3 +51,16 @@ pub use operations::*;
 pub use prime::*;
 pub use modulo::*;
 pub use montgomery::*;
+pub use barrett::*;
+pub use divider::*;
+pub use powtable::*;
+pub use ratio::*;
+pub use fixed::*;
+pub use accumulator::*;
+pub use batch::*;
+pub use matrix::*;
+pub use iter::*;
+pub use stream::*;
+pub use ec::*;
+#[cfg(feature = "selftest")]
+pub use selftest::*;
@@ -0,0 +1,160 @@
+//! This module implements small square matrices **Matrix** over the residues
+//! of a `Modulo` with multiplication and fast exponentiation. They allow
+//! to evaluate linear recurrences (Fibonacci, Lucas, Perrin sequences and so
+//! on) modulo `m` in `O(log n)` steps.
+//!
+//! ```rust
+//! use bigi::{Bigi, Modulo, Matrix};
+//!
+//! // Fibonacci numbers: [[1, 1], [1, 0]]^n = [[F(n+1), F(n)], [F(n), F(n-1)]]
+//! let m = Modulo::new(&Bigi::<4>::from(1000000007));
+//! let a = Matrix::new([
+//!     [Bigi::<4>::from(1), Bigi::<4>::from(1)],
+//!     [Bigi::<4>::from(1), Bigi::<4>::from(0)],
+//! ]);
+//! let f = a.pow(&Bigi::<4>::from(90), &m);
+//! assert_eq!(f.get(0, 1), &Bigi::<4>::from(2880067194370816120 % 1000000007));
+//! ```
+
+use crate::base::Bigi;
+use crate::modulo::Modulo;
+use crate::accumulator::Accumulator;
+
+
+/// Square matrix of size `D` with elements `Bigi<N>`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Matrix<const N: usize, const D: usize> {
+    rows: [[Bigi<N>; D]; D],
+}
+
+
+impl<const N: usize, const D: usize> Matrix<N, D> {
+    /// Creates a matrix from the given rows.
+    pub fn new(rows: [[Bigi<N>; D]; D]) -> Self {
+        Self { rows }
+    }
+
+    /// Creates a zero matrix.
+    pub fn zero() -> Self {
+        Self { rows: [[Bigi::<N>::new(); D]; D] }
+    }
+
+    /// Creates the identity matrix.
+    pub fn identity() -> Self {
+        let mut res = Self::zero();
+        for i in 0..D {
+            res.rows[i][i] = Bigi::<N>::from(1);
+        }
+        res
+    }
+
+    /// Gets the element in row `i` and column `j`.
+    pub fn get(&self, i: usize, j: usize) -> &Bigi<N> {
+        &self.rows[i][j]
+    }
+
+    /// Sets the element in row `i` and column `j`.
+    pub fn set(&mut self, i: usize, j: usize, value: &Bigi<N>) {
+        self.rows[i][j] = *value;
+    }
+
+    /// Modular matrix multiplication.
+    pub fn mul(&self, other: &Self, m: &Modulo<N>) -> Self {
+        let mut res = Self::zero();
+        for i in 0..D {
+            for j in 0..D {
+                let mut acc = Accumulator::<N>::new();
+                for k in 0..D {
+                    acc.add_mul(&self.rows[i][k], &other.rows[k][j]);
+                }
+                res.rows[i][j] = acc.reduce(m.modulo());
+            }
+        }
+        res
+    }
+
+    /// Modular multiplication of the matrix by a column vector.
+    /// ```rust
+    /// use bigi::{Bigi, Modulo, Matrix};
+    ///
+    /// let m = Modulo::new(&Bigi::<4>::from(7));
+    /// let a = Matrix::new([
+    ///     [Bigi::<4>::from(1), Bigi::<4>::from(2)],
+    ///     [Bigi::<4>::from(3), Bigi::<4>::from(4)],
+    /// ]);
+    /// let v = a.mul_vec(&[Bigi::<4>::from(5), Bigi::<4>::from(6)], &m);
+    /// assert_eq!(v, [Bigi::<4>::from(3), Bigi::<4>::from(4)]);  // [17, 39]
+    /// ```
+    pub fn mul_vec(&self, v: &[Bigi<N>; D], m: &Modulo<N>) -> [Bigi<N>; D] {
+        let mut res = [Bigi::<N>::new(); D];
+        for (row, r) in self.rows.iter().zip(res.iter_mut()) {
+            *r = m.dot(row, v);
+        }
+        res
+    }
+
+    /// Modular matrix exponentiation.
+    pub fn pow(&self, k: &Bigi<N>, m: &Modulo<N>) -> Self {
+        let mut res = Self::identity();
+        for i in 0..D {
+            m.normalize(&mut res.rows[i][i]);
+        }
+        let mut x = *self;
+        for bit in k.bits() {
+            if bit {
+                res = res.mul(&x, m);
+            }
+            x = x.mul(&x, m);
+        }
+        res
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::bigi;
+    use super::*;
+    use test::Bencher;
+
+    #[test]
+    fn test_mul() {
+        let m = Modulo::new(&bigi![4; 100]);
+        let a = Matrix::new([[bigi![4; 1], bigi![4; 2]],
+                             [bigi![4; 3], bigi![4; 4]]]);
+        let b = Matrix::new([[bigi![4; 5], bigi![4; 6]],
+                             [bigi![4; 7], bigi![4; 8]]]);
+        assert_eq!(
+            a.mul(&b, &m),
+            Matrix::new([[bigi![4; 19], bigi![4; 22]],
+                         [bigi![4; 43], bigi![4; 50]]])
+        );
+        assert_eq!(a.mul(&Matrix::identity(), &m), a);
+    }
+
+    #[test]
+    fn test_pow() {
+        let m = Modulo::new(&bigi![4; 1000]);
+        // Perrin sequence: P(n) = P(n - 2) + P(n - 3), P(0..3) = 3, 0, 2
+        let a = Matrix::new([[bigi![4; 0], bigi![4; 1], bigi![4; 1]],
+                             [bigi![4; 1], bigi![4; 0], bigi![4; 0]],
+                             [bigi![4; 0], bigi![4; 1], bigi![4; 0]]]);
+        let v = a.pow(&bigi![4; 20], &m)
+                 .mul_vec(&[bigi![4; 2], bigi![4; 0], bigi![4; 3]], &m);
+        assert_eq!(v[0], bigi![4; 486]);  // P(22)
+
+        assert_eq!(a.pow(&bigi![4; 0], &m), Matrix::identity());
+        let one = Modulo::new(&bigi![4; 1]);
+        assert_eq!(a.pow(&bigi![4; 0], &one), Matrix::zero());
+    }
+
+    #[bench]
+    fn bench_pow_256(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let m = Modulo::new(&Bigi::<4>::gen_random(&mut rng, 256, true));
+        let a = Matrix::new([[bigi![4; 1], bigi![4; 1]],
+                             [bigi![4; 1], bigi![4; 0]]]);
+        let k = Bigi::<4>::gen_random(&mut rng, 256, false);
+        bencher.iter(|| a.pow(&k, &m));
+    }
+}
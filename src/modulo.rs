@@ -1,23 +1,98 @@
 //! This modulo implements modular arithmetics as methods of the type **Modulo**.
 
+use std::sync::OnceLock;
 use crate::base::Bigi;
-use crate::prime::{euclidean_extended, sqrt_mod};
+use crate::prime::{euclidean_extended, miller_rabin, SqrtContext};
+use crate::barrett::BarrettAlg;
+use crate::accumulator::Accumulator;
 
 
+/// Number of Miller-Rabin rounds to check the primality of the modulo.
+const PRIMALITY_ROUNDS: usize = 40;
+
+
+/// Modular arithmetics for the modulo `modulo`. Some properties of
+/// the modulo (bit length, primality) are cached, so the modulo cannot be
+/// changed after creation.
+#[derive(Debug)]
 pub struct Modulo<const N: usize> {
-    pub modulo: Bigi<N>,
+    modulo: Bigi<N>,
+    bit_length: usize,
+    prime: OnceLock<bool>,
+    sqrt_ctx: OnceLock<SqrtContext<N>>,
+    barrett: OnceLock<BarrettAlg<N>>,
 }
 
 
 impl<const N: usize> Modulo<N> {
     /// Creates a modulo instance from the given integer.
     pub fn new(m: &Bigi<N>) -> Self {
-        Self { modulo: *m }
+        Self {
+            modulo: *m,
+            bit_length: m.bit_length(),
+            prime: OnceLock::new(),
+            sqrt_ctx: OnceLock::new(),
+            barrett: OnceLock::new(),
+        }
+    }
+
+    /// Creates a modulo instance checking that the modulo is greater than 1.
+    /// ```rust
+    /// use bigi::{Bigi, Modulo};
+    ///
+    /// assert!(Modulo::try_new(&Bigi::<4>::from(19)).is_ok());
+    /// assert_eq!(Modulo::try_new(&Bigi::<4>::from(1)).err(), Some("Modulo must be greater than 1"));
+    /// ```
+    pub fn try_new(m: &Bigi<N>) -> Result<Self, &'static str> {
+        if *m < 2 {
+            Err("Modulo must be greater than 1")
+        } else {
+            Ok(Self::new(m))
+        }
+    }
+
+    /// Creates a modulo instance with the primality known in advance,
+    /// so it is not tested.
+    pub fn with_prime(m: &Bigi<N>, prime: bool) -> Self {
+        let res = Self::new(m);
+        res.prime.set(prime).unwrap();
+        res
+    }
+
+    /// The modulo.
+    pub fn modulo(&self) -> &Bigi<N> {
+        &self.modulo
+    }
+
+    /// Bit length of the modulo (calculated on creation).
+    pub fn bit_length(&self) -> usize {
+        self.bit_length
+    }
+
+    /// Checks if the modulo is odd.
+    pub fn is_odd(&self) -> bool {
+        self.modulo.is_odd()
+    }
+
+    /// Checks if the modulo is prime. If the primality was not given on
+    /// creation, Miller-Rabin test is performed on the first call and
+    /// the result is reused after.
+    /// ```rust
+    /// use bigi::{Bigi, Modulo};
+    ///
+    /// assert!(Modulo::new(&Bigi::<4>::from(19)).is_prime());
+    /// assert!(!Modulo::new(&Bigi::<4>::from(21)).is_prime());
+    /// ```
+    pub fn is_prime(&self) -> bool {
+        *self.prime.get_or_init(|| miller_rabin(&self.modulo, PRIMALITY_ROUNDS))
     }
 
     /// Transforms given `x` into its reminder of the division `x` by the modulo.
     pub fn normalize(&self, x: &mut Bigi<N>) {
-        x.divide(&self.modulo);
+        // Shorter integers are reduced already
+        if x.bit_length() >= self.bit_length {
+            x.divide(&self.modulo);
+        }
     }
 
     /// Modular addition.
@@ -39,11 +114,11 @@ impl<const N: usize> Modulo<N> {
         }
     }
 
-    /// Modular multiplication.
+    /// Modular multiplication. The product is reduced by Barrett reduction,
+    /// its data is calculated on the first call and reused after.
     pub fn mul(&self, x: &Bigi<N>, y: &Bigi<N>) -> Bigi<N> {
-        let mut pair = x.multiply_overflowing(y);
-        pair.0.divide_overflowing(&self.modulo, &pair.1);
-        pair.0
+        let (lo, hi) = x.multiply_overflowing(y);
+        self.barrett().reduce_wide(&hi, &lo)
     }
 
     /// Modular division.
@@ -56,14 +131,202 @@ impl<const N: usize> Modulo<N> {
         euclidean_extended(&x, &self.modulo).1
     }
 
-    /// Modular exponentiation.
+    /// Modular exponentiation (with Barrett reduction as `mul`).
     pub fn pow(&self, x: &Bigi<N>, k: &Bigi<N>) -> Bigi<N> {
-        x.powmod(k, &self.modulo)
+        self.barrett().powmod(x, k)
+    }
+
+    /// Modular exponentiation with a signed exponent: `x^k` if `negative` is
+    /// false and `x^(-k) = (x^(-1))^k` otherwise. The base must be invertible
+    /// for a negative exponent.
+    /// ```rust
+    /// use bigi::{Bigi, Modulo};
+    ///
+    /// let m = Modulo::new(&Bigi::<4>::from(19));
+    /// let x = m.pow_signed(&Bigi::<4>::from(2), &Bigi::<4>::from(3), true);
+    /// assert_eq!(x, Bigi::<4>::from(12));  // 8 * 12 = 96 = 1 (mod 19)
+    /// ```
+    pub fn pow_signed(&self, x: &Bigi<N>, k: &Bigi<N>, negative: bool) -> Bigi<N> {
+        if negative && !k.is_zero() {
+            let (g, inv, _) = euclidean_extended(x, &self.modulo);
+            assert!(g == Bigi::<N>::from(1), "Non-invertible base");
+            self.pow(&inv, k)
+        } else {
+            self.pow(x, k)
+        }
     }
 
-    /// Modular square root (using Tonelli–Shanks algorithm).
+    /// Evaluates the polynomial `coeffs[0] + coeffs[1] * x + coeffs[2] * x^2 + ...`
+    /// by Horner's rule. The coefficients must be already reduced.
+    /// ```rust
+    /// use bigi::{Bigi, Modulo};
+    ///
+    /// let m = Modulo::new(&Bigi::<4>::from(19));
+    /// let coeffs = [Bigi::<4>::from(1), Bigi::<4>::from(2), Bigi::<4>::from(3)];
+    /// assert_eq!(m.eval_poly(&coeffs, &Bigi::<4>::from(5)), Bigi::<4>::from(10));  // 86 % 19
+    /// ```
+    pub fn eval_poly(&self, coeffs: &[Bigi<N>], x: &Bigi<N>) -> Bigi<N> {
+        let mut res = Bigi::<N>::new();
+        for c in coeffs.iter().rev() {
+            res = self.add(&self.mul(&res, x), c);
+        }
+        res
+    }
+
+    /// Modular dot product `a[0] * b[0] + a[1] * b[1] + ...`. The products
+    /// are summed in double width by `Accumulator`, so the total is reduced
+    /// only once.
+    /// ```rust
+    /// use bigi::{Bigi, Modulo};
+    ///
+    /// let m = Modulo::new(&Bigi::<4>::from(97));
+    /// let a = [Bigi::<4>::from(50), Bigi::<4>::from(70)];
+    /// let b = [Bigi::<4>::from(60), Bigi::<4>::from(80)];
+    /// assert_eq!(m.dot(&a, &b), Bigi::<4>::from(64));  // 8600 % 97
+    /// ```
+    pub fn dot(&self, a: &[Bigi<N>], b: &[Bigi<N>]) -> Bigi<N> {
+        assert_eq!(a.len(), b.len(), "Different lengths");
+        let mut acc = Accumulator::<N>::new();
+        for (x, y) in a.iter().zip(b.iter()) {
+            acc.add_mul(x, y);
+        }
+        acc.reduce(&self.modulo)
+    }
+
+    /// Modular square root (using Tonelli–Shanks algorithm). The modulo must
+    /// be prime, otherwise an error is returned. The data of the algorithm
+    /// is calculated on the first call and reused after.
     pub fn sqrt(&self, x: &Bigi<N>) -> Result<(Bigi<N>, Bigi<N>), &'static str> {
-        sqrt_mod(x, &self.modulo)
+        if !self.is_prime() {
+            return Err("Non-prime modulo");
+        }
+        self.sqrt_ctx.get_or_init(|| SqrtContext::new(&self.modulo)).sqrt(x)
+    }
+
+    /// Barrett reduction for the modulo.
+    fn barrett(&self) -> &BarrettAlg<N> {
+        self.barrett.get_or_init(|| BarrettAlg::new(&self.modulo))
+    }
+}
+
+
+
+/// The cached data is derived from the modulo, so only the modulo
+/// is compared.
+impl<const N: usize> PartialEq for Modulo<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.modulo == other.modulo
+    }
+}
+
+
+/// Only the modulo is serialized, the cached data (primality, square root
+/// context, Barrett reduction) is calculated again after loading
+/// on the first use.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename = "Modulo")]
+struct ModuloData<const N: usize> {
+    modulo: Bigi<N>,
+}
+
+
+#[cfg(feature = "serde")]
+impl<const N: usize> serde::Serialize for Modulo<N> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ModuloData { modulo: self.modulo }.serialize(serializer)
+    }
+}
+
+
+#[cfg(feature = "serde")]
+impl<'de, const N: usize> serde::Deserialize<'de> for Modulo<N> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = ModuloData::<N>::deserialize(deserializer)?;
+        Ok(Self::new(&data.modulo))
+    }
+}
+
+
+/// Modular arithmetics for a modulo that fits into *u64*. All the operations
+/// are performed in *u128*, so it is much faster than `Modulo` for small
+/// moduli (for example, residues modulo many small primes).
+/// ```rust
+/// use bigi::{bigi, Bigi, ModuloSmall};
+///
+/// let m = ModuloSmall::new(1000000007);
+/// assert_eq!(m.reduce(&bigi![4; 0, 1]), 582344008);  // 2^64 % m
+/// assert_eq!(m.mul(500000004, 2), 1);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct ModuloSmall {
+    pub modulo: u64,
+}
+
+
+impl ModuloSmall {
+    /// Creates a modulo instance from the given integer.
+    pub fn new(m: u64) -> Self {
+        assert!(m > 0, "Zero modulo");
+        Self { modulo: m }
+    }
+
+    /// Calculates the remainder of the division `x` by the modulo.
+    pub fn reduce<const N: usize>(&self, x: &Bigi<N>) -> u64 {
+        x.rem_u64(self.modulo)
+    }
+
+    /// Modular addition.
+    pub fn add(&self, x: u64, y: u64) -> u64 {
+        ((x as u128 + y as u128) % self.modulo as u128) as u64
+    }
+
+    /// Modular subtraction.
+    pub fn sub(&self, x: u64, y: u64) -> u64 {
+        if x >= y {
+            x - y
+        } else {
+            self.modulo - y + x
+        }
+    }
+
+    /// Modular multiplication.
+    pub fn mul(&self, x: u64, y: u64) -> u64 {
+        ((x as u128 * y as u128) % self.modulo as u128) as u64
+    }
+
+    /// Modular division.
+    pub fn div(&self, x: u64, y: u64) -> u64 {
+        self.mul(x, self.inv(y))
+    }
+
+    /// Modular inverse (using extended Euclidean algorithm). It panics if
+    /// `x` is not coprime with the modulo.
+    pub fn inv(&self, x: u64) -> u64 {
+        let (mut a, mut b) = (x as i128, self.modulo as i128);
+        let (mut u, mut v) = (1i128, 0i128);
+        while b != 0 {
+            let q = a / b;
+            (a, b) = (b, a - q * b);
+            (u, v) = (v, u - q * v);
+        }
+        assert!(a == 1, "Non-invertible element");
+        u.rem_euclid(self.modulo as i128) as u64
+    }
+
+    /// Modular exponentiation.
+    pub fn pow(&self, x: u64, k: u64) -> u64 {
+        let mut res = 1 % self.modulo;
+        let mut x = x % self.modulo;
+        let mut k = k;
+        while k > 0 {
+            if k & 1 == 1 {
+                res = self.mul(res, x);
+            }
+            x = self.mul(x, x);
+            k >>= 1;
+        }
+        res
     }
 }
 
@@ -103,6 +366,34 @@ mod tests {
         assert_eq!(x, bigi![4; 0]);
     }
 
+    #[test]
+    fn test_try_new() {
+        assert_eq!(Modulo::try_new(&bigi![4; 0]).err(), Some("Modulo must be greater than 1"));
+        assert_eq!(Modulo::try_new(&bigi![4; 1]).err(), Some("Modulo must be greater than 1"));
+        let m = Modulo::try_new(&bigi![4; 2]).unwrap();
+        assert_eq!(m.modulo(), &bigi![4; 2]);
+        assert_eq!(m.bit_length(), 2);
+        assert!(!m.is_odd());
+        assert!(m.is_prime());
+    }
+
+    #[test]
+    fn test_is_prime() {
+        let m = Modulo::new(&bigi![4; 0, 1]);
+        assert_eq!(m.bit_length(), 65);
+        assert!(!m.is_prime());
+        assert_eq!(m.sqrt(&bigi![4; 4]), Err("Non-prime modulo"));
+
+        let m = Modulo::with_prime(&bigi![4; 19], true);
+        assert!(m.is_prime());
+        assert_eq!(m.sqrt(&bigi![4; 4]), Ok((bigi![4; 2], bigi![4; 17])));
+
+        let mut rng = rand::thread_rng();
+        let p = crate::prime::gen_prime::<_, 4>(&mut rng, 128);
+        assert!(Modulo::new(&p).is_prime());
+        assert!(!Modulo::new(&(p * &bigi![4; 3])).is_prime());
+    }
+
     #[test]
     fn test_add() {
         let m = Modulo::new(&bigi![4; 19]);
@@ -159,6 +450,58 @@ mod tests {
         assert_eq!(m.pow(&bigi![4; 0], &bigi![4; 6]), bigi![4; 0]);
     }
 
+    #[test]
+    fn test_pow_signed() {
+        let m = Modulo::new(&bigi![4; 19]);
+        assert_eq!(m.pow_signed(&bigi![4; 3], &bigi![4; 5], false), bigi![4; 15]);
+        assert_eq!(m.pow_signed(&bigi![4; 3], &bigi![4; 5], true), bigi![4; 14]);
+        assert_eq!(m.pow_signed(&bigi![4; 3], &bigi![4; 0], true), bigi![4; 1]);
+        assert_eq!(m.pow_signed(&bigi![4; 0], &bigi![4; 0], true), bigi![4; 1]);
+        for k in 0..20 {
+            let x = bigi![4; 7];
+            assert_eq!(
+                m.mul(&m.pow_signed(&x, &bigi![4; k], true),
+                      &m.pow_signed(&x, &bigi![4; k], false)),
+                bigi![4; 1]
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Non-invertible base")]
+    fn test_pow_signed_non_invertible() {
+        let m = Modulo::new(&bigi![4; 15]);
+        m.pow_signed(&bigi![4; 5], &bigi![4; 2], true);
+    }
+
+    #[test]
+    fn test_eval_poly() {
+        let m = Modulo::new(&bigi![4; 19]);
+        let coeffs = [bigi![4; 1], bigi![4; 2], bigi![4; 3]];
+        assert_eq!(m.eval_poly(&coeffs, &bigi![4; 5]), bigi![4; 10]);
+        assert_eq!(m.eval_poly(&coeffs, &bigi![4; 0]), bigi![4; 1]);
+        assert_eq!(m.eval_poly(&[], &bigi![4; 5]), bigi![4; 0]);
+        assert_eq!(m.eval_poly(&[bigi![4; 7]], &bigi![4; 5]), bigi![4; 7]);
+    }
+
+    #[test]
+    fn test_dot() {
+        let m = Modulo::new(&bigi![4; 19]);
+        assert_eq!(m.dot(&[], &[]), bigi![4; 0]);
+        assert_eq!(m.dot(&[bigi![4; 3], bigi![4; 4]], &[bigi![4; 5], bigi![4; 6]]),
+                   bigi![4; 1]);
+
+        let mut rng = rand::thread_rng();
+        let m = Modulo::new(&Bigi::<4>::gen_random(&mut rng, 256, true));
+        let a: Vec<Bigi<4>> = (0..20)
+            .map(|_| Bigi::<4>::gen_random(&mut rng, 255, false)).collect();
+        let b: Vec<Bigi<4>> = (0..20)
+            .map(|_| Bigi::<4>::gen_random(&mut rng, 255, false)).collect();
+        let expected = a.iter().zip(b.iter())
+            .fold(bigi![4; 0], |acc, (x, y)| m.add(&acc, &m.mul(x, y)));
+        assert_eq!(m.dot(&a, &b), expected);
+    }
+
     #[test]
     fn test_sqrt_mod() {
         let m = Modulo::new(&bigi![4; 19]);
@@ -167,4 +510,72 @@ mod tests {
         assert_eq!(m.sqrt(&bigi![4; 16]), Ok((bigi![4; 4], bigi![4; 15])));
         assert_eq!(m.sqrt(&bigi![4; 1]), Ok((bigi![4; 1], bigi![4; 18])));
     }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serde() {
+        use serde_test::{Token, assert_tokens};
+
+        let m = Modulo::new(&bigi![4; 19]);
+        let tokens = [
+            Token::Struct { name: "Modulo", len: 1 },
+            Token::Str("modulo"), Token::Seq { len: Some(1) }, Token::U64(19), Token::SeqEnd,
+            Token::StructEnd,
+        ];
+        assert_tokens(&m, &tokens);
+
+        // The cached data is not serialized
+        m.sqrt(&bigi![4; 5]).unwrap();
+        m.mul(&bigi![4; 5], &bigi![4; 6]);
+        assert_tokens(&m, &tokens);
+    }
+
+    #[test]
+    fn test_eq() {
+        let a = Modulo::new(&bigi![4; 19]);
+        let b = Modulo::new(&bigi![4; 19]);
+        assert!(a.is_prime());
+        assert_eq!(a.sqrt(&bigi![4; 5]), Ok((bigi![4; 9], bigi![4; 10])));
+        assert_eq!(a, b);
+        assert_ne!(a, Modulo::new(&bigi![4; 23]));
+    }
+
+    #[test]
+    fn test_modulo_small() {
+        let m = ModuloSmall::new(19);
+        assert_eq!(m.reduce(&bigi![4; 192]), 2);
+        assert_eq!(m.reduce(&bigi![4; 0]), 0);
+        assert_eq!(m.add(13, 10), 4);
+        assert_eq!(m.sub(3, 7), 15);
+        assert_eq!(m.mul(13, 10), 16);
+        assert_eq!(m.div(4, 13), 12);
+        assert_eq!(m.inv(3), 13);
+        assert_eq!(m.pow(3, 5), 15);
+        assert_eq!(m.pow(0, 0), 1);
+        assert_eq!(ModuloSmall::new(1).pow(5, 0), 0);
+        assert_eq!(ModuloSmall::new(1).inv(0), 0);
+        assert_eq!(m.reduce(&bigi![4; 0, 0, 0, 1]), 11);  // 2^192 % 19
+
+        let mut rng = rand::thread_rng();
+        let p = crate::prime::gen_prime::<_, 4>(&mut rng, 64);
+        let ms = ModuloSmall::new(p.digits[0]);
+        let m = Modulo::new(&p);
+        for _ in 0..10 {
+            let x = Bigi::<4>::gen_random(&mut rng, 256, false);
+            let y = Bigi::<4>::gen_random(&mut rng, 64, false) % &p;
+            let xs = ms.reduce(&x);
+            assert_eq!(xs, (x % &p).digits[0]);
+            assert_eq!(ms.add(xs, y.digits[0]), m.add(&(x % &p), &y).digits[0]);
+            assert_eq!(ms.sub(xs, y.digits[0]), m.sub(&(x % &p), &y).digits[0]);
+            assert_eq!(ms.mul(xs, y.digits[0]), m.mul(&(x % &p), &y).digits[0]);
+            assert_eq!(ms.mul(ms.inv(xs), xs), 1);
+            assert_eq!(ms.pow(xs, y.digits[0]), m.pow(&(x % &p), &y).digits[0]);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Non-invertible element")]
+    fn test_modulo_small_non_invertible() {
+        ModuloSmall::new(21).inv(14);
+    }
 }
@@ -41,9 +41,11 @@
 //! ```
 
 use crate::base::Bigi;
-use crate::prime::euclidean_extended;
+use crate::prime::{inv_mod_2k, pow2_mod};
 
 
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MontgomeryAlg<const N: usize> {
     k: usize,
     n: Bigi<N>,
@@ -52,10 +54,13 @@ pub struct MontgomeryAlg<const N: usize> {
 
 
 impl<const N: usize> MontgomeryAlg<N> {
-    /// Creates a Montgomery arithmetics algoruthm instance.
+    /// Creates a Montgomery arithmetics algoruthm instance. The modulus `n`
+    /// must be odd.
     pub fn new(k: usize, n: &Bigi<N>) -> Self {
         assert!(k >= n.bit_length());
-        let ni = euclidean_extended(&(Bigi::<N>::from(1) << k), n).2;
+        // n' = -n^(-1) mod 2^k by Newton's iteration instead of extended
+        // Euclidean algorithm
+        let ni = (Bigi::<N>::new() - &inv_mod_2k(n, k)).mod_2k(k);
         Self { k: k, n: *n, ni: ni }
     }
 
@@ -71,13 +76,22 @@ impl<const N: usize> MontgomeryAlg<N> {
 
     /// Montgomery multiplication over the images.
     pub fn mul(&self, a: &Bigi<N>, b: &Bigi<N>) -> Bigi<N> {
-        let t = *a * b;
+        self.reduce(*a * b)
+    }
+
+    /// Montgomery square of the image, the same as `self.mul(a, a)`.
+    pub fn square(&self, a: &Bigi<N>) -> Bigi<N> {
+        self.reduce(a.square())
+    }
+
+    /// Montgomery reduction: `t * 2^(-k) % n`.
+    fn reduce(&self, t: Bigi<N>) -> Bigi<N> {
         if t.is_zero() {
             return Bigi::<N>::from(0);
         }
         let mut res = (
             ((t.mod_2k(self.k) * &self.ni).mod_2k(self.k) * &self.n) >> self.k
-        ) + &(t >> self.k) + &Bigi::<N>::from(1);
+        ) + &(t >> self.k) + 1;
         while res >= self.n {
             res -= &self.n;
         }
@@ -86,13 +100,13 @@ impl<const N: usize> MontgomeryAlg<N> {
 
     /// Montgomery exponentiation over the images.
     pub fn powmod(&self, a: &Bigi<N>, p: &Bigi<N>) -> Bigi<N> {
-        let mut res = self.to_repr(&Bigi::<N>::from(1));
+        let mut res = pow2_mod(self.k, &self.n);
         let mut a2 = a.clone();
-        for bit in 0..p.bit_length() {
-            if p.get_bit(bit) {
+        for bit in p.bits() {
+            if bit {
                 res = self.mul(&res, &a2);
             }
-            a2 = self.mul(&a2, &a2);
+            a2 = self.square(&a2);
         }
         res
     }
@@ -103,9 +117,22 @@ impl<const N: usize> MontgomeryAlg<N> {
 mod tests {
     use crate::bigi;
     use super::*;
-    use crate::prime::gen_prime;
+    use crate::prime::{gen_prime, euclidean_extended};
     use test::Bencher;
 
+    #[test]
+    fn test_new() {
+        let mut rng = rand::thread_rng();
+        for bits in [5, 64, 100, 128, 256] {
+            let mut n = Bigi::<8>::gen_random(&mut rng, bits, true);
+            n.digits[0] |= 1;
+            let mgr = MontgomeryAlg::new(bits, &n);
+            let (g, _, b) = euclidean_extended(&(bigi![8; 1] << bits), &n);
+            assert_eq!(g, bigi![8; 1]);
+            assert_eq!(mgr.ni, b);
+        }
+    }
+
     #[test]
     fn test_to_repr() {
         let n = bigi![4; 23];
@@ -142,6 +169,15 @@ mod tests {
         assert_eq!(mgr.mul(&bigi![4; 9], &bigi![4; 9]), bigi![4; 9]);
     }
 
+    #[test]
+    fn test_square() {
+        let n = bigi![4; 23];
+        let mgr = MontgomeryAlg::new(5, &n);
+        for x in 0..23 {
+            assert_eq!(mgr.square(&bigi![4; x]), mgr.mul(&bigi![4; x], &bigi![4; x]));
+        }
+    }
+
     #[test]
     fn test_powmod() {
         let n = bigi![4; 23];
@@ -150,6 +186,20 @@ mod tests {
         assert_eq!(mgr.powmod(&bigi![4; 9], &bigi![4; 12]), bigi![4; 9]);
     }
 
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serde() {
+        use serde_test::{Token, assert_tokens};
+
+        assert_tokens(&MontgomeryAlg::new(5, &bigi![8; 23]), &[
+            Token::Struct { name: "MontgomeryAlg", len: 3 },
+            Token::Str("k"), Token::U64(5),
+            Token::Str("n"), Token::Seq { len: Some(1) }, Token::U64(23), Token::SeqEnd,
+            Token::Str("ni"), Token::Seq { len: Some(1) }, Token::U64(25), Token::SeqEnd,
+            Token::StructEnd,
+        ]);
+    }
+
     #[bench]
     fn bench_to_repr_256(bencher: &mut Bencher) {
         let mut rng = rand::thread_rng();
@@ -197,4 +247,12 @@ mod tests {
             let _ = mgr.from_repr(&zm);
         });
     }
+
+
+    #[bench]
+    fn bench_new_256(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let n = gen_prime::<_, 8>(&mut rng, 256);
+        bencher.iter(|| MontgomeryAlg::new(256, &n));
+    }
 }
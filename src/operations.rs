@@ -1,10 +1,42 @@
 //! This module implements basic arithmetic operations: addition, subtraction,
 //! multiplication, division, modular exponentiation, comparison, shift right,
-//! shift left and some other useful functions.
+//! shift left, bitwise operations and some other useful functions.
 
-use std::{ops, cmp};
+use std::{ops, cmp, hint};
 use crate::base::Bigi;
-
+#[cfg(feature = "simd")]
+use crate::simd;
+#[cfg(all(feature = "adx", target_arch = "x86_64"))]
+use crate::adx;
+
+
+/// Minimum number of digits of both operands for `mul_parallel` to split
+/// the work between threads.
+#[cfg(feature = "rayon")]
+pub const PARALLEL_MUL_THRESHOLD: usize = 32;
+
+/// Minimum number of significant digits of both operands for
+/// the multiplication to switch from the schoolbook algorithm to
+/// [Karatsuba algorithm](https://en.wikipedia.org/wiki/Karatsuba_algorithm).
+pub const KARATSUBA_THRESHOLD: usize = 32;
+
+/// Minimum number of significant digits of both operands for
+/// the multiplication to switch from Karatsuba algorithm to
+/// [Toom-3 algorithm](https://en.wikipedia.org/wiki/Toom%E2%80%93Cook_multiplication).
+pub const TOOM3_THRESHOLD: usize = 256;
+
+/// Minimum number of significant digits for the squaring to switch from
+/// the schoolbook algorithm to Karatsuba algorithm. It is higher than
+/// `KARATSUBA_THRESHOLD`, because the schoolbook squaring calculates every
+/// product of different digits once. Toom-3 is not used for squares, it was
+/// measured slower than Karatsuba squaring up to 1024 digits.
+pub const KARATSUBA_SQUARE_THRESHOLD: usize = 64;
+
+/// Minimum number of significant digits of the divisor and the quotient
+/// for the division to switch from Knuth Algorithm D to Burnikel-Ziegler
+/// recursive division (C. Burnikel, J. Ziegler, Fast Recursive Division,
+/// 1998).
+pub const BURNIKEL_ZIEGLER_THRESHOLD: usize = 64;
 
 impl<const N: usize> Bigi<N> {
     /// Checks if the integer is zero.
@@ -12,7 +44,7 @@ impl<const N: usize> Bigi<N> {
     /// use bigi::{bigi, Bigi};
     ///
     /// let z = bigi![8; 0];
-    /// assert_eq!(z.is_zero(), true);
+    /// assert!(z.is_zero());
     /// ```
     pub fn is_zero(&self) -> bool {
         for i in 0..N {
@@ -28,7 +60,7 @@ impl<const N: usize> Bigi<N> {
     /// use bigi::{bigi, Bigi};
     ///
     /// let z = bigi![8; 17, 12];
-    /// assert_eq!(z.is_odd(), true);
+    /// assert!(z.is_odd());
     /// ```
     pub fn is_odd(&self) -> bool {
         self.digits[0] & 1 == 1
@@ -39,7 +71,7 @@ impl<const N: usize> Bigi<N> {
     /// use bigi::{bigi, Bigi};
     ///
     /// let z = bigi![8; 18, 12];
-    /// assert_eq!(z.is_even(), true);
+    /// assert!(z.is_even());
     /// ```
     pub fn is_even(&self) -> bool {
         self.digits[0] & 1 == 0
@@ -71,12 +103,63 @@ impl<const N: usize> Bigi<N> {
         }
     }
 
+    /// Gets the length of the integer in bytes, so zero has no bytes.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// let z = bigi![8; 18, 12];
+    /// assert_eq!(z.byte_length(), 9);
+    /// ```
+    pub fn byte_length(&self) -> usize {
+        (self.bit_length() + 7) >> 3
+    }
+
+    /// Gets the number of ones in the binary representation.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// let z = bigi![8; 18, 12];
+    /// assert_eq!(z.count_ones(), 4);
+    /// ```
+    pub fn count_ones(&self) -> usize {
+        self.digits.iter().map(|d| d.count_ones() as usize).sum()
+    }
+
+    /// Gets the number of leading zero bits out of `N * 64`.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// let z = bigi![8; 18, 12];
+    /// assert_eq!(z.leading_zeros(), 444);
+    /// ```
+    pub fn leading_zeros(&self) -> usize {
+        match self.digits.iter().rposition(|d| *d != 0) {
+            Some(i) => ((N - i - 1) << 6) + self.digits[i].leading_zeros() as usize,
+            None => N << 6,
+        }
+    }
+
+    /// Gets the number of trailing zero bits, that is the largest `s`
+    /// such that the integer is divisible by `2^s` (`N * 64` for zero).
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// let z = bigi![8; 0, 12];
+    /// assert_eq!(z.trailing_zeros(), 66);
+    /// ```
+    pub fn trailing_zeros(&self) -> usize {
+        match self.digits.iter().position(|d| *d != 0) {
+            Some(i) => (i << 6) + self.digits[i].trailing_zeros() as usize,
+            None => N << 6,
+        }
+    }
+
     /// Gets certain bit of the integer.
     /// ```rust
     /// use bigi::{bigi, Bigi};
     ///
     /// let z = bigi![8; 18, 12];
-    /// assert_eq!(z.get_bit(66), true);
+    /// assert!(z.get_bit(66));
     /// ```
     pub fn get_bit(&self, bit: usize) -> bool {
         let quot = bit >> 6;
@@ -84,6 +167,119 @@ impl<const N: usize> Bigi<N> {
         (self.digits[quot] & (1 << rem)) != 0
     }
 
+    /// Sets certain bit of the integer to one.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// let mut z = bigi![8; 18, 12];
+    /// z.set_bit(64);
+    /// assert_eq!(z, bigi![8; 18, 13]);
+    /// ```
+    pub fn set_bit(&mut self, bit: usize) {
+        self.digits[bit >> 6] |= 1 << (bit & 63);
+    }
+
+    /// Sets certain bit of the integer to zero.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// let mut z = bigi![8; 18, 12];
+    /// z.clear_bit(66);
+    /// assert_eq!(z, bigi![8; 18, 8]);
+    /// ```
+    pub fn clear_bit(&mut self, bit: usize) {
+        self.digits[bit >> 6] &= !(1 << (bit & 63));
+    }
+
+    /// Sets certain bit of the integer to the given value.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// let mut z = bigi![8; 18, 12];
+    /// z.assign_bit(0, true);
+    /// z.assign_bit(1, false);
+    /// assert_eq!(z, bigi![8; 17, 12]);
+    /// ```
+    pub fn assign_bit(&mut self, bit: usize, value: bool) {
+        if value {
+            self.set_bit(bit);
+        } else {
+            self.clear_bit(bit);
+        }
+    }
+
+    /// Inverts certain bit of the integer.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// let mut z = bigi![8; 18, 12];
+    /// z.toggle_bit(1);
+    /// z.toggle_bit(3);
+    /// assert_eq!(z, bigi![8; 24, 12]);
+    /// ```
+    pub fn toggle_bit(&mut self, bit: usize) {
+        self.digits[bit >> 6] ^= 1 << (bit & 63);
+    }
+
+    /// Gets certain byte of the integer, the byte 0 is the least
+    /// significant one.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// let z = bigi![8; 0x1234, 12];
+    /// assert_eq!(z.get_byte(1), 0x12);
+    /// assert_eq!(z.get_byte(8), 12);
+    /// ```
+    pub fn get_byte(&self, byte: usize) -> u8 {
+        (self.digits[byte >> 3] >> ((byte & 7) << 3)) as u8
+    }
+
+    /// Sets certain byte of the integer to the given value.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// let mut z = bigi![8; 0x1234, 12];
+    /// z.set_byte(0, 0xff);
+    /// z.set_byte(9, 1);
+    /// assert_eq!(z, bigi![8; 0x12ff, 0x010c]);
+    /// ```
+    pub fn set_byte(&mut self, byte: usize, value: u8) {
+        let shift = (byte & 7) << 3;
+        let digit = &mut self.digits[byte >> 3];
+        *digit = (*digit & !(0xff << shift)) | ((value as u64) << shift);
+    }
+
+    /// Rotates the bits of the integer to the left by `k` as a word of
+    /// `N * 64` bits, so the highest bits come to the lowest positions.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// let z = bigi![2; 3, 1 << 63];
+    /// assert_eq!(z.rotate_left(1), bigi![2; 7, 0]);
+    /// assert_eq!(z.rotate_left(64), bigi![2; 1 << 63, 3]);
+    /// ```
+    pub fn rotate_left(&self, k: usize) -> Bigi<N> {
+        let k = k % (N << 6);
+        if k == 0 {
+            return *self;
+        }
+        (*self << k) | &(*self >> ((N << 6) - k))
+    }
+
+    /// Rotates the bits of the integer to the right by `k` as a word of
+    /// `N * 64` bits, so the lowest bits come to the highest positions.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// let z = bigi![2; 3, 1 << 63];
+    /// assert_eq!(z.rotate_right(1), bigi![2; 1, (1 << 63) | (1 << 62)]);
+    /// assert_eq!(z.rotate_right(128), z);
+    /// ```
+    pub fn rotate_right(&self, k: usize) -> Bigi<N> {
+        let k = k % (N << 6);
+        self.rotate_left((N << 6) - k)
+    }
+
     /// Gets `index + 1` where `index` is the idnex of the last non-zero digit.
     /// ```rust
     /// use bigi::{bigi, Bigi};
@@ -101,6 +297,8 @@ impl<const N: usize> Bigi<N> {
 
     /// Performs division by given *divisor*. The funcion returns the quotient.
     /// This method changes the object so it equals to the reminder in the end.
+    /// It panics if *divisor* is zero, the same as `/` and `%` do, use
+    /// `checked_div` and `checked_rem` to handle this case.
     /// ```rust
     /// use bigi::{bigi, Bigi};
     ///
@@ -111,93 +309,254 @@ impl<const N: usize> Bigi<N> {
     /// assert_eq!(c, bigi![8; 3]);
     /// ```
     pub fn divide(&mut self, divisor: &Bigi<N>) -> Bigi<N> {
+        assert!(!divisor.is_zero(), "Division by zero");
         let mut res = Bigi::<N>::new();
-
         let order1 = self.get_order();
         let order2 = divisor.get_order();
-
         if order1 >= order2 {
-            let mut shf = order1 - order2;
-
-            loop {
-                let extra = if order2 + shf < N {
-                    self.digits[order2 + shf]
-                } else { 0u64 };
-
-                // Skipping if the divider is less than shifted divisor
-                if extra == 0 {
-                    let is_less: bool = {
-                        let mut result = false;
-                        for i in (0..order2).rev() {
-                            if self.digits[i + shf] > divisor.digits[i] {
-                                result = false;
-                                break;
-                            }
-                            if self.digits[i + shf] < divisor.digits[i] {
-                                result = true;
-                                break;
-                            }
-                        }
-                        result
-                    };
-
-                    if is_less {
-                        if shf != 0 {
-                            shf -= 1;
-                            continue;
-                        } else {
-                            break;
-                        }
-                    }
-                }
+            let mut scratch = [[0; N]; 9];
+            divide_slices(&mut self.digits[..order1], &divisor.digits[..order2],
+                          &mut res.digits, scratch.as_flattened_mut());
+        }
+        res
+    }
 
-                // Calculating factor
-                let factor = {
-                    let top = self.lead_u128();
-                    let result;
-
-                    let bottom = if extra > 0 {
-                        divisor.digits[order2 - 1] as u128
-                    } else {
-                        if order2 == 1 && shf > 0 {
-                            divisor.lead_u128() << 64
-                        } else {
-                            divisor.lead_u128()
-                        }
-                    };
-
-                    if top == bottom {
-                        result = 1;
-                    } else {
-                        result = top / (bottom + 1);
-                    }
-
-                    result as u64
-                };
-
-                // Adding factor to the result
-                res.digits[shf] += factor;
-
-                // Reducing dividend
-                let mut fw: u128 = 0;
-                for i in 0..order2 {
-                    fw = (divisor.digits[i] as u128) * (factor as u128) + fw;
-                    let pair = self.digits[i + shf].overflowing_sub(fw as u64);
-                    self.digits[i + shf] = pair.0;
-                    fw >>= 64;
-                    if pair.1 {
-                        fw += 1;
-                    }
-                }
-                if fw > 0 && order2 + shf < N {
-                    self.digits[order2 + shf] -= fw as u64;
+    /// Calculates the quotient and the remainder of the division by
+    /// *divisor* without changing the integer.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// let a = bigi![8; 14];
+    /// assert_eq!(a.div_rem(&bigi![8; 4]), (bigi![8; 3], bigi![8; 2]));
+    /// ```
+    pub fn div_rem(&self, divisor: &Bigi<N>) -> (Bigi<N>, Bigi<N>) {
+        let mut quot = Bigi::<N>::new();
+        let mut rem = Bigi::<N>::new();
+        Bigi::div_rem_into(&mut quot, &mut rem, self, divisor);
+        (quot, rem)
+    }
+
+    /// Division that returns `None` if *divisor* is zero.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// assert_eq!(bigi![8; 14].checked_div(&bigi![8; 4]), Some(bigi![8; 3]));
+    /// assert_eq!(bigi![8; 14].checked_div(&bigi![8; 0]), None);
+    /// ```
+    pub fn checked_div(&self, divisor: &Bigi<N>) -> Option<Bigi<N>> {
+        if divisor.is_zero() {
+            None
+        } else {
+            Some(self.div_rem(divisor).0)
+        }
+    }
+
+    /// Remainder that returns `None` if *divisor* is zero.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// assert_eq!(bigi![8; 14].checked_rem(&bigi![8; 4]), Some(bigi![8; 2]));
+    /// assert_eq!(bigi![8; 14].checked_rem(&bigi![8; 0]), None);
+    /// ```
+    pub fn checked_rem(&self, divisor: &Bigi<N>) -> Option<Bigi<N>> {
+        if divisor.is_zero() {
+            None
+        } else {
+            Some(self.div_rem(divisor).1)
+        }
+    }
+
+    /// Performs short division by the digit *d*. The integer is replaced
+    /// with the quotient and the remainder is returned.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// let mut a = bigi![4; 0, 1];
+    /// assert_eq!(a.div_u64(10), 6);
+    /// assert_eq!(a, bigi![4; 1844674407370955161]);
+    /// ```
+    pub fn div_u64(&mut self, d: u64) -> u64 {
+        assert!(d != 0, "Division by zero");
+        let order = self.get_order();
+        if order <= 1 {
+            let rem = self.digits[0] % d;
+            self.digits[0] /= d;
+            return rem;
+        }
+
+        // The dividend is normalized on the fly together with the divisor,
+        // so the remainder is shifted back in the end
+        let shift = d.leading_zeros();
+        let d = d << shift;
+        let v = reciprocal(d);
+        let mut rem = if shift > 0 { self.digits[order - 1] >> (64 - shift) } else { 0 };
+        for i in (0..order).rev() {
+            let mut digit = self.digits[i] << shift;
+            if shift > 0 && i > 0 {
+                digit |= self.digits[i - 1] >> (64 - shift);
+            }
+            (self.digits[i], rem) = div2by1(rem, digit, d, v);
+        }
+        rem >> shift
+    }
+
+    /// Calculates the remainder of the division by the digit *d*.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// assert_eq!(bigi![4; 0, 1].rem_u64(10), 6);
+    /// ```
+    pub fn rem_u64(&self, d: u64) -> u64 {
+        let mut x = *self;
+        x.div_u64(d)
+    }
+
+    /// Division that is known to be exact (`divisor` divides `self`)
+    /// by [Jebelean's algorithm](https://doi.org/10.1016/0747-7171(93)90064-2):
+    /// the quotient digits are found from the lowest one by multiplication
+    /// by the inverse of the lowest divisor digit modulo `2^64`, so no trial
+    /// quotients and corrections are needed. If the division is not exact,
+    /// the result is meaningless (it is checked in debug builds only).
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// let a = bigi![4; 0, 15];  // 15 * 2^64
+    /// assert_eq!(a.exact_div(&bigi![4; 5]), bigi![4; 0, 3]);
+    /// ```
+    pub fn exact_div(&self, divisor: &Bigi<N>) -> Bigi<N> {
+        assert!(!divisor.is_zero(), "Division by zero");
+        debug_assert!((*self % divisor).is_zero(), "Division is not exact");
+
+        // Removing common powers of 2 to make the divisor odd
+        let zeros = {
+            let idx = divisor.digits.iter().position(|d| *d != 0).unwrap();
+            (idx << 6) + divisor.digits[idx].trailing_zeros() as usize
+        };
+        let mut rest = *self >> zeros;
+        let d = *divisor >> zeros;
+
+        // Inverse of the lowest digit modulo 2^64 by Newton's iteration
+        let d0 = d.digits[0];
+        let mut d0_inv = d0;
+        for _ in 0..5 {
+            d0_inv = d0_inv.wrapping_mul(2u64.wrapping_sub(d0.wrapping_mul(d0_inv)));
+        }
+
+        let mut res = Bigi::<N>::new();
+        let order1 = rest.get_order();
+        let order2 = d.get_order();
+        if order1 < order2 {
+            return res;
+        }
+
+        for i in 0..=(order1 - order2) {
+            let q = rest.digits[i].wrapping_mul(d0_inv);
+            res.digits[i] = q;
+
+            // Subtracting q * d * 2^(64 * i)
+            let mut fw: u128 = 0;
+            for j in i..order1 {
+                if j - i < order2 {
+                    fw += d.digits[j - i] as u128 * q as u128;
                 }
+                let (diff, borrow) = rest.digits[j].overflowing_sub(fw as u64);
+                rest.digits[j] = diff;
+                fw = (fw >> 64) + borrow as u128;
             }
         }
 
         res
     }
 
+    /// The same as `exact_div`, named after the other `div_*` methods.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// assert_eq!(bigi![4; 0, 15].div_exact(&bigi![4; 5]), bigi![4; 0, 3]);
+    /// ```
+    pub fn div_exact(&self, divisor: &Bigi<N>) -> Bigi<N> {
+        self.exact_div(divisor)
+    }
+
+    /// Division rounded down, the same as `/` for unsigned integers.
+    pub fn div_floor(&self, divisor: &Bigi<N>) -> Bigi<N> {
+        *self / divisor
+    }
+
+    /// Division rounded up.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// assert_eq!(bigi![4; 22].div_ceil(&bigi![4; 7]), bigi![4; 4]);
+    /// assert_eq!(bigi![4; 21].div_ceil(&bigi![4; 7]), bigi![4; 3]);
+    /// ```
+    pub fn div_ceil(&self, divisor: &Bigi<N>) -> Bigi<N> {
+        let (quot, rem) = self.div_rem(divisor);
+        if rem.is_zero() {
+            quot
+        } else {
+            quot + 1
+        }
+    }
+
+    /// Division rounded to the nearest integer, the half is rounded up.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// assert_eq!(bigi![4; 24].div_round(&bigi![4; 7]), bigi![4; 3]);
+    /// assert_eq!(bigi![4; 25].div_round(&bigi![4; 7]), bigi![4; 4]);
+    /// assert_eq!(bigi![4; 7].div_round(&bigi![4; 2]), bigi![4; 4]);
+    /// ```
+    pub fn div_round(&self, divisor: &Bigi<N>) -> Bigi<N> {
+        let (quot, rem) = self.div_rem(divisor);
+        // 2 * rem >= divisor without overflow
+        if rem >= *divisor - &rem {
+            quot + 1
+        } else {
+            quot
+        }
+    }
+
+    /// Remainder of `div_floor`: `self - divisor * self.div_floor(divisor)`,
+    /// the same as `%`.
+    pub fn rem_floor(&self, divisor: &Bigi<N>) -> Bigi<N> {
+        *self % divisor
+    }
+
+    /// Remainder of `div_ceil` taken with the opposite sign, so it is
+    /// nonnegative: `divisor * self.div_ceil(divisor) - self`.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// assert_eq!(bigi![4; 22].rem_ceil(&bigi![4; 7]), bigi![4; 6]);
+    /// ```
+    pub fn rem_ceil(&self, divisor: &Bigi<N>) -> Bigi<N> {
+        let rem = *self % divisor;
+        if rem.is_zero() {
+            rem
+        } else {
+            *divisor - &rem
+        }
+    }
+
+    /// Absolute value of the remainder of `div_round`: the distance from
+    /// `self` to the nearest multiple of `divisor`.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// assert_eq!(bigi![4; 24].rem_round(&bigi![4; 7]), bigi![4; 3]);
+    /// assert_eq!(bigi![4; 26].rem_round(&bigi![4; 7]), bigi![4; 2]);
+    /// ```
+    pub fn rem_round(&self, divisor: &Bigi<N>) -> Bigi<N> {
+        let rem = *self % divisor;
+        let rest = *divisor - &rem;
+        if rem >= rest {
+            rest
+        } else {
+            rem
+        }
+    }
+
     /// Performs power `p` and modulo of the division by `m`.
     /// ```rust
     /// use bigi::{bigi, Bigi};
@@ -223,19 +582,137 @@ impl<const N: usize> Bigi<N> {
 
         let mut res = Bigi::<N>::from(1);
         let mut x = self.clone();
-        for bit in 0..p.bit_length() {
-            if p.get_bit(bit) {
-                let pair = res.multiply_overflowing(&x);
-                res = pair.0;
-                res.divide_overflowing(&m, &pair.1);
+        let mut hi = Bigi::<N>::new();
+        for bit in p.bits() {
+            if bit {
+                res.mul_assign_scratch(&x, &mut hi);
+                res.divide_overflowing(&m, &hi);
+            }
+            (x, hi) = x.square_overflowing();
+            x.divide_overflowing(m, &hi);
+        }
+        res
+    }
+
+    /// Performs power `p` and modulo of the division by `m` with
+    /// [Montgomery ladder](https://en.wikipedia.org/wiki/Exponentiation_by_squaring#Montgomery's_ladder_technique),
+    /// so the sequence of operations does not depend on the bits of `p`,
+    /// the base or the result: all `N * 64` bits are processed with two
+    /// Montgomery multiplications over all `N` digits (see `mont_mul_ct`),
+    /// and the branches are replaced with masked swaps. It is slower than
+    /// `powmod`, use it for private exponents. The modulus must be odd,
+    /// it is treated as public: the constants of Montgomery form are
+    /// calculated from it by the ordinary division.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// let a = bigi![8; 3];
+    /// assert_eq!(a.powmod_ct(&bigi![8; 4], &bigi![8; 7]), bigi![8; 4]);
+    /// ```
+    pub fn powmod_ct(&self, p: &Bigi<N>, m: &Bigi<N>) -> Bigi<N> {
+        assert!(m.is_odd(), "Modulus must be odd");
+
+        // -m^(-1) mod 2^64 by Newton's iteration, every step doubles
+        // the number of correct bits
+        let mut mi: u64 = 1;
+        for _ in 0..6 {
+            mi = mi.wrapping_mul(2u64.wrapping_sub(m.digits[0].wrapping_mul(mi)));
+        }
+        let mi = mi.wrapping_neg();
+
+        // 2^(64 N) % m and its square
+        let r = (Bigi::<N>::new() - m) % m;
+        let (mut r2, hi) = r.widening_mul(&r);
+        r2.divide_overflowing(m, &hi);
+
+        let mut r0 = r;
+        let mut r1 = mont_mul_ct(self, &r2, m, mi);
+        for bit in (0..(N << 6)).rev() {
+            // (r0, r1) becomes (r0^2, r0 r1) for zero bit and (r0 r1, r1^2)
+            // for one bit
+            let swap = (p.digits[bit >> 6] >> (bit & 63)) & 1;
+            cswap(&mut r0, &mut r1, swap);
+            r1 = mont_mul_ct(&r0, &r1, m, mi);
+            r0 = mont_mul_ct(&r0, &r0, m, mi);
+            cswap(&mut r0, &mut r1, swap);
+        }
+        mont_mul_ct(&r0, &Bigi::<N>::from(1), m, mi)
+    }
+
+    /// Performs power `d` modulo `m` for a private RSA exponent `d` and checks
+    /// the result against the public exponent `e`: `(x^d)^e = x (mod m)`.
+    /// A wrong result caused by a fault (a hardware error or an attack like
+    /// [Bellcore's](https://doi.org/10.1007/3-540-69053-0_4)) can reveal
+    /// the private key, so in this case an error is returned instead.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// let m = bigi![4; 3233];  // 61 * 53
+    /// let x = bigi![4; 65];
+    /// assert_eq!(x.powmod_verified(&bigi![4; 413], &bigi![4; 17], &m), Ok(bigi![4; 588]));
+    /// assert_eq!(x.powmod_verified(&bigi![4; 412], &bigi![4; 17], &m), Err("Fault detected"));
+    /// ```
+    pub fn powmod_verified(&self, d: &Bigi<N>, e: &Bigi<N>, m: &Bigi<N>
+                           ) -> Result<Bigi<N>, &'static str> {
+        let res = self.powmod(d, m);
+        if res.powmod(e, m) == *self % m {
+            Ok(res)
+        } else {
+            Err("Fault detected")
+        }
+    }
+
+    /// Performs power `e` given as *u64* and modulo of the division by `m`.
+    /// It scans only the significant bits of `e` from the highest one,
+    /// so small exponents (like `65537`) are cheap.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// let a = bigi![8; 3];
+    /// assert_eq!(a.powmod_u64(65537, &bigi![8; 1000]), bigi![8; 363]);
+    /// ```
+    pub fn powmod_u64(&self, e: u64, m: &Bigi<N>) -> Bigi<N> {
+        if e == 0 {
+            return Bigi::<N>::from(1) % m;
+        }
+        let x = *self % m;
+        let mut res = x;
+        for i in (0..(63 - e.leading_zeros())).rev() {
+            let (lo, hi) = res.square_overflowing();
+            res = lo;
+            res.divide_overflowing(m, &hi);
+            if (e >> i) & 1 == 1 {
+                let (lo, hi) = res.multiply_overflowing(&x);
+                res = lo;
+                res.divide_overflowing(m, &hi);
             }
-            let pair = x.multiply_overflowing(&x);
-            x = pair.0;
-            x.divide_overflowing(&m, &pair.1);
         }
         res
     }
 
+    /// Calculates `self^e` by square-and-multiply. It returns `None` if
+    /// the power does not fit into `N` digits.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// assert_eq!(bigi![2; 10].pow(19), Some(bigi![2; 10000000000000000000]));
+    /// assert_eq!(bigi![2; 2].pow(127), Some(bigi![2; 0, 1 << 63]));
+    /// assert_eq!(bigi![2; 2].pow(128), None);
+    /// ```
+    pub fn pow(&self, e: u64) -> Option<Bigi<N>> {
+        if e == 0 {
+            return Some(Bigi::<N>::from(1));
+        }
+        let mut res = *self;
+        for i in (0..(63 - e.leading_zeros())).rev() {
+            res = res.checked_mul(&res)?;
+            if (e >> i) & 1 == 1 {
+                res = res.checked_mul(self)?;
+            }
+        }
+        Some(res)
+    }
+
     /// Calculates the reminder of the division by 2 power `k`.
     /// ```rust
     /// use bigi::{bigi, Bigi};
@@ -244,6 +721,9 @@ impl<const N: usize> Bigi<N> {
     /// assert_eq!(a.mod_2k(5), bigi![8; 27]);
     /// ```
     pub fn mod_2k(&self, k: usize) -> Bigi<N> {
+        if k >= N * 64 {
+            return *self;
+        }
         let mut res = self.clone();
         let q = k >> 6;
         let r = k & 63;
@@ -254,440 +734,2483 @@ impl<const N: usize> Bigi<N> {
         res
     }
 
-    /// Multiplitcation with overflow.
+    /// Multiplication that splits the digits of `other` into blocks
+    /// and multiplies `self` by the blocks in parallel (using `rayon`),
+    /// the partial products are summed after. The result is the same as
+    /// of `*`. It makes sense for large integers only (`Bigi<64>` and wider),
+    /// for the operands shorter than `PARALLEL_MUL_THRESHOLD` digits
+    /// the ordinary multiplication is performed.
     /// ```rust
     /// use bigi::{bigi, Bigi};
     ///
-    /// let a = bigi![2; 4402752814420623592, 77189580264184];
-    /// let b = bigi![2; 16242343048349248772, 4571967601559393757];
-    /// let (c, overflow) = a.multiply_overflowing(&b);
-    ///
-    /// assert_eq!(c, bigi![2; 18314275272483195808, 4916496906824170722]);
-    /// assert_eq!(overflow, bigi![2; 14967786748685025341, 19131195116908]);
+    /// let a = Bigi::<64>::from(12345);
+    /// let b = Bigi::<64>::from(6789);
+    /// assert_eq!(a.mul_parallel(&b), a * &b);
     /// ```
-    pub fn multiply_overflowing(&self, other: &Bigi<N>) -> (Bigi<N>, Bigi<N>) {
-        let mut res = Bigi::<N>::new();
-        let mut overflow = Bigi::<N>::new();
-        for i in 0..N {
-            let mut fw: u128 = 0;
-            for j in 0..(N - i) {
-                fw = (other.digits[i] as u128) * (self.digits[j] as u128) +
-                     (res.digits[i + j] as u128) + fw;
-                res.digits[i + j] = fw as u64;
-                fw >>= 64;
-            }
-            for j in (N - i)..N {
-                fw = (other.digits[i] as u128) * (self.digits[j] as u128) +
-                     (overflow.digits[i + j - N] as u128) + fw;
-                overflow.digits[i + j - N] = fw as u64;
-                fw >>= 64;
-            }
-            if fw > 0 {
-                overflow.digits[i] += fw as u64;
-            }
+    #[cfg(feature = "rayon")]
+    pub fn mul_parallel(&self, other: &Bigi<N>) -> Bigi<N> {
+        use rayon::prelude::*;
+
+        let order = other.get_order();
+        if cmp::min(self.get_order(), order) < PARALLEL_MUL_THRESHOLD {
+            return *self * other;
         }
-        (res, overflow)
+        let blocks = cmp::min(
+            rayon::current_num_threads(), order / (PARALLEL_MUL_THRESHOLD >> 1)
+        );
+        let size = order.div_ceil(blocks);
+        (0..blocks).into_par_iter()
+            .map(|k| {
+                let mut res = Bigi::<N>::new();
+                self.mul_rows(other, k * size, cmp::min((k + 1) * size, order), &mut res);
+                res
+            })
+            .reduce(Bigi::<N>::new, |a, b| a + &b)
     }
 
-    /// Division with overflow.
+    /// Writes `a + b` into `dst` without creating temporary copies of
+    /// the operands. The overflow is dropped.
     /// ```rust
     /// use bigi::{bigi, Bigi};
     ///
-    /// let mut c = bigi![2; 18314275272483195888, 4916496906824170722];
-    /// let overflow = bigi![2; 14967786748685025341, 19131195116908];
-    /// let b = bigi![2; 16242343048349248772, 4571967601559393757];
-    ///
-    /// let a = c.divide_overflowing(&b, &overflow);
-    ///
-    /// assert_eq!(a, bigi![2; 4402752814420623592, 77189580264184]);
-    /// assert_eq!(c, bigi![2; 80]);
+    /// let mut c = Bigi::<4>::new();
+    /// Bigi::add_into(&mut c, &bigi![4; 3], &bigi![4; 5]);
+    /// assert_eq!(c, bigi![4; 8]);
     /// ```
-    pub fn divide_overflowing(&mut self, divisor: &Bigi<N>, overflow: &Bigi<N>
-                ) -> Bigi<N> {
-        if overflow.is_zero() {
-            return self.divide(divisor);
+    pub fn add_into(dst: &mut Bigi<N>, a: &Bigi<N>, b: &Bigi<N>) {
+        #[cfg(all(feature = "adx", target_arch = "x86_64"))]
+        adx::add_into(&mut dst.digits, &a.digits, &b.digits);
+        #[cfg(not(all(feature = "adx", target_arch = "x86_64")))]
+        {
+            let mut fw = false;
+            for i in 0..N {
+                let pair1 = a.digits[i].overflowing_add(b.digits[i]);
+                let pair2 = pair1.0.overflowing_add(fw as u64);
+                dst.digits[i] = pair2.0;
+                fw = pair1.1 || pair2.1;
+            }
         }
+    }
 
-        let mut res = Bigi::<N>::new();
-        let mut overflow_mut = overflow.clone();
-
-        let order1 = overflow_mut.get_order() + N;
-        let order2 = divisor.get_order();
+    /// Writes `a - b` into `dst` without creating temporary copies of
+    /// the operands. The result is taken modulo `2^(64N)` if `a < b`.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// let mut c = Bigi::<4>::new();
+    /// Bigi::sub_into(&mut c, &bigi![4; 8], &bigi![4; 5]);
+    /// assert_eq!(c, bigi![4; 3]);
+    /// ```
+    pub fn sub_into(dst: &mut Bigi<N>, a: &Bigi<N>, b: &Bigi<N>) {
+        #[cfg(all(feature = "adx", target_arch = "x86_64"))]
+        adx::sub_into(&mut dst.digits, &a.digits, &b.digits);
+        #[cfg(not(all(feature = "adx", target_arch = "x86_64")))]
+        {
+            let mut fw = false;
+            for i in 0..N {
+                let pair1 = a.digits[i].overflowing_sub(b.digits[i]);
+                let pair2 = pair1.0.overflowing_sub(fw as u64);
+                dst.digits[i] = pair2.0;
+                fw = pair1.1 || pair2.1;
+            }
+        }
+    }
 
-        let mut shf = order1 - order2;
+    /// Writes `a * b` into `dst` without creating temporary copies of
+    /// the operands. The overflow is dropped.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// let mut c = Bigi::<4>::new();
+    /// Bigi::mul_into(&mut c, &bigi![4; 3], &bigi![4; 5]);
+    /// assert_eq!(c, bigi![4; 15]);
+    /// ```
+    pub fn mul_into(dst: &mut Bigi<N>, a: &Bigi<N>, b: &Bigi<N>) {
+        if let Some(product) = a.mul_large(b) {
+            dst.digits = product[0];
+            return;
+        }
+        dst.digits = [0; N];
+        a.mul_rows(b, 0, b.get_order(), dst);
+    }
 
-        loop {
-            let extra = if order2 + shf < N {
-                self.digits[order2 + shf]
-            } else if order2 + shf - N < N {
-                overflow_mut.digits[order2 + shf - N]
+    /// Writes the quotient and the remainder of `a / b` into `quot` and
+    /// `rem`, the remainder is calculated in place, so the operands are not
+    /// copied to temporary integers.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// let mut q = Bigi::<4>::new();
+    /// let mut r = Bigi::<4>::new();
+    /// Bigi::div_rem_into(&mut q, &mut r, &bigi![4; 14], &bigi![4; 4]);
+    /// assert_eq!((q, r), (bigi![4; 3], bigi![4; 2]));
+    /// ```
+    pub fn div_rem_into(quot: &mut Bigi<N>, rem: &mut Bigi<N>, a: &Bigi<N>, b: &Bigi<N>) {
+        rem.digits = a.digits;
+        *quot = rem.divide(b);
+    }
+
+    /// Addition that returns `None` if the sum does not fit into `N` digits.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// assert_eq!(bigi![2; 5].checked_add(&bigi![2; 3]), Some(bigi![2; 8]));
+    /// assert_eq!(bigi![2; 5, u64::MAX].checked_add(&bigi![2; 0, 1]), None);
+    /// ```
+    pub fn checked_add(&self, other: &Bigi<N>) -> Option<Bigi<N>> {
+        let res = *self + other;
+        // The sum is wrapped if and only if it is less than an operand
+        if res < *self {
+            None
+        } else {
+            Some(res)
+        }
+    }
+
+    /// Subtraction that returns `None` if `other` is greater than `self`.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// assert_eq!(bigi![2; 5].checked_sub(&bigi![2; 3]), Some(bigi![2; 2]));
+    /// assert_eq!(bigi![2; 3].checked_sub(&bigi![2; 5]), None);
+    /// ```
+    pub fn checked_sub(&self, other: &Bigi<N>) -> Option<Bigi<N>> {
+        if *other > *self {
+            None
+        } else {
+            Some(*self - other)
+        }
+    }
+
+    /// Multiplication that returns `None` if the product does not fit into
+    /// `N` digits.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// assert_eq!(bigi![2; 5].checked_mul(&bigi![2; 3]), Some(bigi![2; 15]));
+    /// assert_eq!(bigi![2; 0, 1].checked_mul(&bigi![2; 0, 1]), None);
+    /// ```
+    pub fn checked_mul(&self, other: &Bigi<N>) -> Option<Bigi<N>> {
+        // The orders of the operands decide unless their sum is N + 1
+        let order = self.get_order() + other.get_order();
+        if order <= N {
+            Some(*self * other)
+        } else if order > N + 1 {
+            None
+        } else {
+            let (res, overflow) = self.widening_mul(other);
+            if overflow.is_zero() {
+                Some(res)
             } else {
-                0u64
-            };
-
-            // Skipping if the divider is less than shifted divisor
-            if extra == 0 {
-                let is_less: bool = {
-                    let mut result = false;
-                    for i in (0..order2).rev() {
-                        let value = if i + shf < N {
-                            self.digits[i + shf]
-                        } else {
-                            overflow_mut.digits[i + shf - N]
-                        };
-                        if value > divisor.digits[i] {
-                            result = false;
-                            break;
-                        }
-                        if value < divisor.digits[i] {
-                            result = true;
-                            break;
-                        }
-                    }
-                    result
-                };
-
-                if is_less {
-                    if shf != 0 {
-                        shf -= 1;
-                        continue;
-                    } else {
-                        break;
-                    }
-                }
+                None
             }
+        }
+    }
 
-            // Calculating factor
-            let factor = {
-                let top = {
-                    let overflow_order = overflow_mut.get_order();
-                    if overflow_order >= 2 {
-                        overflow_mut.lead_u128()
-                    } else if overflow_order == 0 {
-                        self.lead_u128()
-                    } else {
-                        ((overflow_mut.digits[0] as u128) << 64) +
-                        (self.digits[N - 1] as u128)
-                    }
-                };
-                let result;
-
-                let bottom = if extra > 0 {
-                    divisor.digits[order2 - 1] as u128
-                } else {
-                    if order2 == 1 && shf > 0 {
-                        divisor.lead_u128() << 64
-                    } else {
-                        divisor.lead_u128()
-                    }
-                };
-
-                if top == bottom {
-                    result = 1;
-                } else {
-                    result = top / (bottom + 1);
-                }
+    /// Addition that returns `Bigi::MAX` if the sum does not fit into `N`
+    /// digits.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// assert_eq!(bigi![2; 5].saturating_add(&bigi![2; 3]), bigi![2; 8]);
+    /// assert_eq!(Bigi::<2>::MAX.saturating_add(&bigi![2; 1]), Bigi::<2>::MAX);
+    /// ```
+    pub fn saturating_add(&self, other: &Bigi<N>) -> Bigi<N> {
+        self.checked_add(other).unwrap_or(Bigi::<N>::MAX)
+    }
+
+    /// Subtraction that returns zero if `other` is greater than `self`.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// assert_eq!(bigi![2; 5].saturating_sub(&bigi![2; 3]), bigi![2; 2]);
+    /// assert_eq!(bigi![2; 3].saturating_sub(&bigi![2; 5]), bigi![2; 0]);
+    /// ```
+    pub fn saturating_sub(&self, other: &Bigi<N>) -> Bigi<N> {
+        self.checked_sub(other).unwrap_or_default()
+    }
+
+    /// Calculates the absolute difference `|self - other|`.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// assert_eq!(bigi![2; 5].abs_diff(&bigi![2; 3]), bigi![2; 2]);
+    /// assert_eq!(bigi![2; 3].abs_diff(&bigi![2; 5]), bigi![2; 2]);
+    /// ```
+    pub fn abs_diff(&self, other: &Bigi<N>) -> Bigi<N> {
+        if self >= other {
+            self - other
+        } else {
+            other - self
+        }
+    }
+
+    /// Multiplication that returns `Bigi::MAX` if the product does not fit
+    /// into `N` digits.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// assert_eq!(bigi![2; 5].saturating_mul(&bigi![2; 3]), bigi![2; 15]);
+    /// assert_eq!(bigi![2; 0, 1].saturating_mul(&bigi![2; 0, 1]), Bigi::<2>::MAX);
+    /// ```
+    pub fn saturating_mul(&self, other: &Bigi<N>) -> Bigi<N> {
+        self.checked_mul(other).unwrap_or(Bigi::<N>::MAX)
+    }
+
+    /// Shift left that returns `None` if any nonzero bit is shifted out of
+    /// `N` digits, so `self << k` is exactly `self * 2^k`.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// assert_eq!(bigi![2; 5].checked_shl(125), Some(bigi![2; 0, 5 << 61]));
+    /// assert_eq!(bigi![2; 5].checked_shl(126), None);
+    /// assert_eq!(bigi![2; 0].checked_shl(1000), Some(bigi![2; 0]));
+    /// ```
+    pub fn checked_shl(&self, k: usize) -> Option<Bigi<N>> {
+        if k > self.leading_zeros() && !self.is_zero() {
+            return None;
+        }
+        Some(*self << k)
+    }
+
+    /// Shift right that returns `None` if `k` is not less than `N * 64`,
+    /// the same as `checked_shr` of the primitive integers. The lowest bits
+    /// are dropped as in `>>`.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// assert_eq!(bigi![2; 0, 5].checked_shr(65), Some(bigi![2; 2]));
+    /// assert_eq!(bigi![2; 0, 5].checked_shr(128), None);
+    /// ```
+    pub fn checked_shr(&self, k: usize) -> Option<Bigi<N>> {
+        if k >= N << 6 {
+            return None;
+        }
+        Some(*self >> k)
+    }
+
+    /// Adds an integer of another width `Bigi<M>` (usually narrower)
+    /// without converting it to `Bigi<N>`. The overflow is dropped.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// let a = bigi![8; u64::MAX, 7];
+    /// assert_eq!(a.add_smaller(&bigi![2; 1, 1]), bigi![8; 0, 9]);
+    /// ```
+    pub fn add_smaller<const M: usize>(&self, other: &Bigi<M>) -> Bigi<N> {
+        let mut res = *self;
+        let size = cmp::min(other.get_order(), N);
+        add_slices(&mut res.digits, &other.digits[..size]);
+        res
+    }
+
+    /// Subtracts an integer of another width `Bigi<M>` (usually narrower)
+    /// without converting it to `Bigi<N>`. The result is taken modulo
+    /// `2^(64N)` if it is negative.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// let a = bigi![8; 0, 9];
+    /// assert_eq!(a.sub_smaller(&bigi![2; 1, 1]), bigi![8; u64::MAX, 7]);
+    /// ```
+    pub fn sub_smaller<const M: usize>(&self, other: &Bigi<M>) -> Bigi<N> {
+        let mut res = *self;
+        let size = cmp::min(other.get_order(), N);
+        sub_slices(&mut res.digits, &other.digits[..size]);
+        res
+    }
 
-                result as u64
-            };
+    /// Multiplies by an integer of another width `Bigi<M>` (usually
+    /// narrower) without converting it to `Bigi<N>`, so only the significant
+    /// digits of both operands are multiplied. The overflow is dropped.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// let a = bigi![8; 0, 3];
+    /// assert_eq!(a.mul_by_smaller(&bigi![2; 5]), bigi![8; 0, 15]);
+    /// ```
+    pub fn mul_by_smaller<const M: usize>(&self, other: &Bigi<M>) -> Bigi<N> {
+        // The digits of other above N do not affect the result
+        let a = &self.digits[..self.get_order()];
+        let b = &other.digits[..cmp::min(other.get_order(), N)];
+        let mul = |res: &mut [u64]| {
+            if cmp::min(a.len(), b.len()) >= KARATSUBA_THRESHOLD {
+                let mut scratch = [[0; N]; 4];
+                mul_slices(a, b, res, scratch.as_flattened_mut());
+            } else {
+                mul_slices(a, b, res, &mut []);
+            }
+        };
+
+        // The product is written in place if it fits
+        let size = a.len() + b.len();
+        if size <= N {
+            let mut res = Bigi::<N>::new();
+            mul(&mut res.digits[..size]);
+            res
+        } else {
+            let mut product = [[0; N]; 2];
+            mul(&mut product.as_flattened_mut()[..size]);
+            Bigi { digits: product[0] }
+        }
+    }
 
-            // Adding factor to the result
-            res.digits[shf] += factor;
+    /// Multiplies `self` by `other` in place keeping the low half of
+    /// the product in `self` and writing the high half into `scratch`
+    /// (an in-place analogue of `multiply_overflowing`). No temporary
+    /// product is created, so the same scratch integer can be reused in
+    /// a loop.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// let mut a = bigi![2; 4402752814420623592, 77189580264184];
+    /// let b = bigi![2; 16242343048349248772, 4571967601559393757];
+    /// let mut scratch = Bigi::<2>::new();
+    /// a.mul_assign_scratch(&b, &mut scratch);
+    ///
+    /// assert_eq!(a, bigi![2; 18314275272483195808, 4916496906824170722]);
+    /// assert_eq!(scratch, bigi![2; 14967786748685025341, 19131195116908]);
+    /// ```
+    pub fn mul_assign_scratch(&mut self, other: &Bigi<N>, scratch: &mut Bigi<N>) {
+        if let Some(product) = self.mul_large(other) {
+            [self.digits, scratch.digits] = product;
+            return;
+        }
 
-            // Reducing dividend
+        // The digits of self are moved to scratch, and the high half of
+        // the product replaces them as soon as they are taken
+        scratch.digits = self.digits;
+        self.digits = [0; N];
+        for i in 0..N {
+            let a = scratch.digits[i] as u128;
             let mut fw: u128 = 0;
-            for i in 0..order2 {
-                fw = (divisor.digits[i] as u128) * (factor as u128) + fw;
-
-                let pair;
-                if i + shf < N {
-                    pair = self.digits[i + shf].overflowing_sub(fw as u64);
-                    self.digits[i + shf] = pair.0;
-                } else {
-                    pair = overflow_mut.digits[i + shf - N]
-                        .overflowing_sub(fw as u64);
-                    overflow_mut.digits[i + shf - N] = pair.0;
+            for j in 0..(N - i) {
+                fw += a * (other.digits[j] as u128) +
+                      (self.digits[i + j] as u128);
+                self.digits[i + j] = fw as u64;
+                fw >>= 64;
+            }
+            for j in (N - i)..N {
+                fw += a * (other.digits[j] as u128) +
+                      (scratch.digits[i + j - N] as u128);
+                scratch.digits[i + j - N] = fw as u64;
+                fw >>= 64;
+            }
+            scratch.digits[i] = fw as u64;
+        }
+    }
+
+    /// Full product of two integers as a pair `(low, high)` of its halves,
+    /// so `self * other = low + high * 2^(64 * N)`. Nothing is lost, so any
+    /// reduction (modular, Barrett, fixed point) can be applied to the
+    /// double width product.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// let a = bigi![2; 4402752814420623592, 77189580264184];
+    /// let b = bigi![2; 16242343048349248772, 4571967601559393757];
+    /// let (low, high) = a.widening_mul(&b);
+    ///
+    /// assert_eq!(low, bigi![2; 18314275272483195808, 4916496906824170722]);
+    /// assert_eq!(high, bigi![2; 14967786748685025341, 19131195116908]);
+    /// ```
+    pub fn widening_mul(&self, other: &Bigi<N>) -> (Bigi<N>, Bigi<N>) {
+        if let Some(product) = self.mul_large(other) {
+            return (Bigi { digits: product[0] }, Bigi { digits: product[1] });
+        }
+        #[cfg(all(feature = "simd", target_feature = "avx512f"))]
+        if let Some(product) = simd::widening_mul(&self.digits, &other.digits) {
+            return (Bigi { digits: product[0] }, Bigi { digits: product[1] });
+        }
+        #[cfg(all(feature = "adx", target_arch = "x86_64"))]
+        {
+            let mut product = [[0; N]; 2];
+            let flat = product.as_flattened_mut();
+            for i in 0..N {
+                flat[i + N] = adx::mul_add_row(&mut flat[i..(i + N)],
+                                               &self.digits, other.digits[i]);
+            }
+            (Bigi { digits: product[0] }, Bigi { digits: product[1] })
+        }
+        #[cfg(not(all(feature = "adx", target_arch = "x86_64")))]
+        {
+            let mut res = Bigi::<N>::new();
+            let mut overflow = Bigi::<N>::new();
+            for i in 0..N {
+                let mut fw: u128 = 0;
+                for j in 0..(N - i) {
+                    fw += (other.digits[i] as u128) * (self.digits[j] as u128) +
+                          (res.digits[i + j] as u128);
+                    res.digits[i + j] = fw as u64;
+                    fw >>= 64;
+                }
+                for j in (N - i)..N {
+                    fw += (other.digits[i] as u128) * (self.digits[j] as u128) +
+                          (overflow.digits[i + j - N] as u128);
+                    overflow.digits[i + j - N] = fw as u64;
+                    fw >>= 64;
                 }
+                if fw > 0 {
+                    overflow.digits[i] += fw as u64;
+                }
+            }
+            (res, overflow)
+        }
+    }
+
+    /// The high half of the product, the same as `self.widening_mul(other).1`.
+    /// If the product fits into `N` digits by the orders of the operands,
+    /// nothing is multiplied.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// let a = bigi![2; 0, 1 << 63];
+    /// assert_eq!(a.mul_high(&bigi![2; 0, 6]), bigi![2; 0, 3]);
+    /// ```
+    pub fn mul_high(&self, other: &Bigi<N>) -> Bigi<N> {
+        if self.get_order() + other.get_order() <= N {
+            return Bigi::<N>::new();
+        }
+        self.widening_mul(other).1
+    }
+
+    /// Multiplitcation with overflow, the same as `widening_mul`.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// let a = bigi![2; 4402752814420623592, 77189580264184];
+    /// let b = bigi![2; 16242343048349248772, 4571967601559393757];
+    /// let (c, overflow) = a.multiply_overflowing(&b);
+    ///
+    /// assert_eq!(c, bigi![2; 18314275272483195808, 4916496906824170722]);
+    /// assert_eq!(overflow, bigi![2; 14967786748685025341, 19131195116908]);
+    /// ```
+    pub fn multiply_overflowing(&self, other: &Bigi<N>) -> (Bigi<N>, Bigi<N>) {
+        self.widening_mul(other)
+    }
+
+    /// Square of the integer, the same as `self * self`. Every product of
+    /// two different digits is calculated once, so it is almost twice
+    /// faster. The overflow is dropped.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// let a = bigi![4; 12, 3];
+    /// assert_eq!(a.square(), a * &a);
+    /// ```
+    pub fn square(&self) -> Bigi<N> {
+        self.square_overflowing().0
+    }
+
+    /// Square with overflow, the same as `self.widening_mul(self)`.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// let a = bigi![2; 4402752814420623592, 77189580264184];
+    /// assert_eq!(a.square_overflowing(), a.widening_mul(&a));
+    /// ```
+    pub fn square_overflowing(&self) -> (Bigi<N>, Bigi<N>) {
+        // The halves are adjacent in memory, so the product is written
+        // as a single slice
+        let mut product = [[0; N]; 2];
+        // Short integers are squared over all the digits, because the loops
+        // of constant length are unrolled and work faster
+        let order = if N < KARATSUBA_THRESHOLD { N } else { self.get_order() };
+        let res = &mut product.as_flattened_mut()[..order << 1];
+        if order >= KARATSUBA_SQUARE_THRESHOLD {
+            let mut scratch = [[0; N]; 4];
+            square_slices(&self.digits[..order], res, scratch.as_flattened_mut());
+        } else {
+            square_slices(&self.digits[..order], res, &mut []);
+        }
+        (Bigi { digits: product[0] }, Bigi { digits: product[1] })
+    }
+
+    /// Integer square root `floor(sqrt(self))` by Newton's method. The first
+    /// approximation `2^ceil(bits / 2)` is not less than the root, so the
+    /// approximations decrease until the root is reached.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// assert_eq!(bigi![4; 1000].isqrt(), bigi![4; 31]);
+    /// assert_eq!(bigi![4; 0, 0, 1].isqrt(), bigi![4; 0, 1]);
+    /// ```
+    pub fn isqrt(&self) -> Bigi<N> {
+        if self.is_zero() {
+            return Bigi::<N>::new();
+        }
+        let mut r = Bigi::<N>::from(1) << ((self.bit_length() + 1) >> 1);
+        loop {
+            let y = (r + &(*self / &r)) >> 1;
+            if y >= r {
+                return r;
+            }
+            r = y;
+        }
+    }
+
+    /// Division with overflow.
+    /// ```rust
+    /// use bigi::{bigi, Bigi};
+    ///
+    /// let mut c = bigi![2; 18314275272483195888, 4916496906824170722];
+    /// let overflow = bigi![2; 14967786748685025341, 19131195116908];
+    /// let b = bigi![2; 16242343048349248772, 4571967601559393757];
+    ///
+    /// let a = c.divide_overflowing(&b, &overflow);
+    ///
+    /// assert_eq!(a, bigi![2; 4402752814420623592, 77189580264184]);
+    /// assert_eq!(c, bigi![2; 80]);
+    /// ```
+    pub fn divide_overflowing(&mut self, divisor: &Bigi<N>, overflow: &Bigi<N>
+                ) -> Bigi<N> {
+        if overflow.is_zero() {
+            return self.divide(divisor);
+        }
+        assert!(!divisor.is_zero(), "Division by zero");
+
+        // The halves are adjacent in memory, so the dividend and
+        // the quotient are processed as single slices
+        let mut dividend = [self.digits, overflow.digits];
+        let mut quotient = [[0; N]; 2];
+        let mut scratch = [[0; N]; 12];
+        let order1 = N + overflow.get_order();
+        let order2 = divisor.get_order();
+        divide_slices(&mut dividend.as_flattened_mut()[..order1],
+                      &divisor.digits[..order2], quotient.as_flattened_mut(),
+                      scratch.as_flattened_mut());
+
+        // The remainder is less than the divisor, so it fits
+        self.digits = dividend[0];
+        Bigi { digits: quotient[0] }
+    }
 
+    /// Adds the products of `self` by the digits `other[start..end]`
+    /// shifted to their positions to `res`, the digits above `N` are dropped.
+    /// `res` must have no nonzero digits above the position `start`.
+    fn mul_rows(&self, other: &Bigi<N>, start: usize, end: usize,
+                res: &mut Bigi<N>) {
+        // Zero high digits of the operands are skipped
+        let order1 = self.get_order();
+        for i in start..end {
+            let mut fw: u128 = 0;
+            let size = cmp::min(order1, N - i);
+            for j in 0..size {
+                fw += (other.digits[i] as u128) * (self.digits[j] as u128) +
+                      (res.digits[i + j] as u128);
+                res.digits[i + j] = fw as u64;
                 fw >>= 64;
-                if pair.1 {
-                    fw += 1;
-                }
             }
-            if fw > 0 && order2 + shf < N << 1 {
-                if order2 + shf < N {
-                    self.digits[order2 + shf] -= fw as u64;
-                } else {
-                    overflow_mut.digits[order2 + shf - N] -= fw as u64;
-                }
+            if i + size < N {
+                res.digits[i + size] = fw as u64;
             }
         }
+    }
+
+    /// Calculates the full product of the significant digits by Karatsuba
+    /// algorithm if both operands have at least `KARATSUBA_THRESHOLD`
+    /// digits, otherwise returns `None`. The product is returned as its low
+    /// and high halves on the stack.
+    fn mul_large(&self, other: &Bigi<N>) -> Option<[[u64; N]; 2]> {
+        let order1 = self.get_order();
+        let order2 = other.get_order();
+        if cmp::min(order1, order2) < KARATSUBA_THRESHOLD {
+            return None;
+        }
+        let mut product = [[0; N]; 2];
+        let mut scratch = [[0; N]; 4];
+        mul_slices(&self.digits[..order1], &other.digits[..order2],
+                   &mut product.as_flattened_mut()[..(order1 + order2)],
+                   scratch.as_flattened_mut());
+        Some(product)
+    }
+}
+
+
+/// Writes the full product of `a` and `b` into `res` that must be
+/// `a.len() + b.len()` digits long. `scratch` keeps the intermediate values
+/// of Karatsuba and Toom-3 algorithms, so nothing is allocated, it must be
+/// at least `2 * (a.len() + b.len())` digits if both operands have
+/// `KARATSUBA_THRESHOLD` digits or more, otherwise it is not used.
+pub(crate) fn mul_slices(a: &[u64], b: &[u64], res: &mut [u64], scratch: &mut [u64]) {
+    let (a, b) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    if b.len() < KARATSUBA_THRESHOLD {
+        mul_schoolbook(a, b, res);
+    } else if b.len() <= a.len() / 2 {
+        // Unbalanced operands: a is multiplied by b in chunks of its size
+        res.fill(0);
+        let (chunk_res, scratch) = scratch.split_at_mut(b.len() << 1);
+        for (k, chunk) in a.chunks(b.len()).enumerate() {
+            let size = chunk.len() + b.len();
+            mul_slices(chunk, b, &mut chunk_res[..size], scratch);
+            add_slices(&mut res[k * b.len()..], &chunk_res[..size]);
+        }
+    } else if b.len() >= TOOM3_THRESHOLD && 3 * b.len() > 2 * a.len() {
+        mul_toom3(a, b, res, scratch);
+    } else {
+        mul_karatsuba(a, b, res, scratch);
+    }
+}
+
+
+/// Swaps `a` and `b` if `swap` is one and keeps them if it is zero, without
+/// branches on `swap`.
+fn cswap<const N: usize>(a: &mut Bigi<N>, b: &mut Bigi<N>, swap: u64) {
+    let mask = hint::black_box(swap.wrapping_neg());
+    for (x, y) in a.digits.iter_mut().zip(b.digits.iter_mut()) {
+        let t = (*x ^ *y) & mask;
+        *x ^= t;
+        *y ^= t;
+    }
+}
+
+
+/// Montgomery product `a b 2^(-64 N) % m` for odd `m` and `b < m` by CIOS
+/// method (Koç, Acar, Kaliski, Analyzing and comparing Montgomery
+/// multiplication algorithms, 1996), `mi` is `-m^(-1) mod 2^64`. All `N`
+/// digits are processed whatever the operands are, the intermediate value
+/// is less than `2 m`, so it is finished by a single masked subtraction.
+fn mont_mul_ct<const N: usize>(a: &Bigi<N>, b: &Bigi<N>, m: &Bigi<N>,
+                               mi: u64) -> Bigi<N> {
+    let mut t = [0; N];
+    let mut top: u64 = 0;
+    for i in 0..N {
+        // t += a * b[i]
+        let mut fw: u128 = 0;
+        for (z, x) in t.iter_mut().zip(a.digits.iter()) {
+            fw += (*z as u128) + (*x as u128) * (b.digits[i] as u128);
+            *z = fw as u64;
+            fw >>= 64;
+        }
+        let high = (top as u128) + fw;
+
+        // t = (t + u * m) / 2^64, the lowest digit becomes zero
+        let u = t[0].wrapping_mul(mi);
+        let mut fw = ((t[0] as u128) + (m.digits[0] as u128) * (u as u128)) >> 64;
+        for j in 1..N {
+            fw += (t[j] as u128) + (m.digits[j] as u128) * (u as u128);
+            t[j - 1] = fw as u64;
+            fw >>= 64;
+        }
+        let high = high + fw;
+        t[N - 1] = high as u64;
+        top = (high >> 64) as u64;
+    }
+
+    // t - m is taken unless it is negative
+    let mut res = [0; N];
+    let mut borrow: u64 = 0;
+    for ((z, x), y) in res.iter_mut().zip(t.iter()).zip(m.digits.iter()) {
+        let (diff, b1) = x.overflowing_sub(*y);
+        let (diff, b2) = diff.overflowing_sub(borrow);
+        *z = diff;
+        borrow = (b1 | b2) as u64;
+    }
+    let keep = hint::black_box(((top < borrow) as u64).wrapping_neg());
+    for (z, x) in res.iter_mut().zip(t.iter()) {
+        *z = (*x & keep) | (*z & !keep);
+    }
+    Bigi { digits: res }
+}
+
+
+/// Divides `u` by `v` using Knuth Algorithm D (The Art of Computer
+/// Programming, Vol. 2, 4.3.1): the operands are normalized so the highest
+/// bit of the divisor is set, then every digit of the quotient is estimated
+/// by the two highest digits of the divisor and corrected at most twice.
+/// Wide operands are divided recursively (see `BURNIKEL_ZIEGLER_THRESHOLD`).
+/// The quotient is written into `q` (the digits that do not fit are
+/// dropped), the remainder replaces `u`. `v` must have nonzero highest digit
+/// and not be longer than `u`, `scratch` must be at least
+/// `u.len() + v.len() + 1` digits, or `3 * (u.len() + 1) + 5 * v.len()`
+/// digits if the division is recursive.
+pub(crate) fn divide_slices(u: &mut [u64], v: &[u64], q: &mut [u64], scratch: &mut [u64]) {
+    let n = v.len();
+
+    // Normalization
+    let s = v[n - 1].leading_zeros();
+    let (un, scratch) = scratch.split_at_mut(u.len() + 1);
+    let (vn, scratch) = scratch.split_at_mut(n);
+    shift_slice_left(v, s, vn);
+    un[u.len()] = if s > 0 { u[u.len() - 1] >> (64 - s) } else { 0 };
+    shift_slice_left(u, s, &mut un[..u.len()]);
+
+    divide_normalized(un, vn, reciprocal(vn[n - 1]), q, scratch);
+
+    // Denormalization of the remainder
+    u.fill(0);
+    shift_slice_right(&un[..(n + 1)], s, &mut u[..n]);
+}
+
+
+/// Division of the normalized operands: `un` has an extra highest digit
+/// less than the highest digit of `vn`, `v` is the reciprocal of
+/// the highest digit of `vn`. The quotient is written into `q` (the digits
+/// that do not fit are dropped), the normalized remainder is left in
+/// the lowest digits of `un`. `scratch` is used by the recursive division
+/// only, it must be at least `2 * un.len() + 4 * vn.len()` digits then.
+pub(crate) fn divide_normalized(un: &mut [u64], vn: &[u64], v: u64, q: &mut [u64],
+                                scratch: &mut [u64]) {
+    let n = vn.len();
+    if n >= BURNIKEL_ZIEGLER_THRESHOLD && un.len() - n >= BURNIKEL_ZIEGLER_THRESHOLD {
+        divide_recursive(un, vn, v, q, scratch);
+    } else {
+        divide_schoolbook(un, vn, v, q);
+    }
+}
+
+
+/// Burnikel-Ziegler division with the same arguments as
+/// `divide_normalized`. The operands are padded with lowest zero digits,
+/// so the length of the divisor is `j * 2^t` with `j` not greater than
+/// the threshold, then the dividend is divided by blocks of this length
+/// from the highest one. The padded operands and the quotient are placed
+/// in `scratch` once, the rest of it is shared by all the levels.
+fn divide_recursive(un: &mut [u64], vn: &[u64], v: u64, q: &mut [u64],
+                    scratch: &mut [u64]) {
+    let n = vn.len();
+    let m = un.len() - n - 1;
+    let mut blocks = 1;
+    while n > blocks * BURNIKEL_ZIEGLER_THRESHOLD {
+        blocks <<= 1;
+    }
+    let size = n.div_ceil(blocks) * blocks;
+    let k = size - n;
+
+    let (b, scratch) = scratch.split_at_mut(size);
+    b[..k].fill(0);
+    b[k..].copy_from_slice(vn);
+    let (a, scratch) = scratch.split_at_mut(un.len() + k);
+    a[..k].fill(0);
+    a[k..].copy_from_slice(un);
+    let (quot, scratch) = scratch.split_at_mut(m + 1);
+    quot.fill(0);
+
+    // The highest digits that do not make a whole block
+    let rest = (m + 1) % size;
+    if rest > 0 {
+        divide_schoolbook(&mut a[(m + 1 - rest)..], b, v, &mut quot[(m + 1 - rest)..]);
+    }
+    for j in (0..(m + 1 - rest)).step_by(size).rev() {
+        divide_2n_1n(&mut a[j..(j + 2 * size)], b, v, &mut quot[j..(j + size)], scratch);
+    }
+
+    un.fill(0);
+    un[..n].copy_from_slice(&a[k..(k + n)]);
+    let size = cmp::min(q.len(), m + 1);
+    q[..size].copy_from_slice(&quot[..size]);
+}
+
+
+/// Divides `a` of `2n` digits by the normalized `b` of `n` digits,
+/// the highest `n` digits of `a` must be less than `b`. The quotient of `n`
+/// digits is written into `q`, the remainder is left in the lowest digits
+/// of `a`. `scratch` must be at least `3n` digits.
+fn divide_2n_1n(a: &mut [u64], b: &[u64], v: u64, q: &mut [u64], scratch: &mut [u64]) {
+    let n = b.len();
+    if n % 2 == 1 || n <= BURNIKEL_ZIEGLER_THRESHOLD {
+        divide_schoolbook(a, b, v, q);
+        return;
+    }
+    let h = n / 2;
+    divide_3n_2n(&mut a[h..], b, v, &mut q[h..], scratch);
+    divide_3n_2n(&mut a[..(3 * h)], b, v, &mut q[..h], scratch);
+}
+
+
+/// Divides `a` of `3h` digits by the normalized `b` of `2h` digits,
+/// the highest `2h` digits of `a` must be less than `b`. The quotient of `h`
+/// digits is written into `q`, the remainder is left in the lowest digits
+/// of `a`. `scratch` must be at least `6h` digits.
+fn divide_3n_2n(a: &mut [u64], b: &[u64], v: u64, q: &mut [u64], scratch: &mut [u64]) {
+    let h = q.len();
+    let (b2, b1) = b.split_at(h);
+
+    // The quotient of the highest digits by the highest half of b, then
+    // the remainder a - q * b is c * X + a3 - q * b2 where X = 2^(64h)
+    if cmp_slices(&a[(2 * h)..], b1) == cmp::Ordering::Less {
+        divide_2n_1n(&mut a[h..], b1, v, q, scratch);
+    } else {
+        // The highest halves are equal, so the quotient is X - 1 and
+        // c = a1 * X + a2 - (X - 1) * b1 = a2 + b1
+        q.fill(u64::MAX);
+        a[(2 * h)..].fill(0);
+        add_slices(&mut a[h..], b1);
+    }
+    let (d, scratch) = scratch.split_at_mut(2 * h);
+    mul_slices(q, b2, d, scratch);
+
+    // The estimate is corrected at most twice
+    let mut negative = sub_slices(a, d);
+    while negative {
+        sub_slices(q, &[1]);
+        negative = !add_slices(a, b);
+    }
+}
+
+
+/// The main loop of Algorithm D for the normalized operands with the same
+/// arguments as `divide_normalized`.
+fn divide_schoolbook(un: &mut [u64], vn: &[u64], v: u64, q: &mut [u64]) {
+    let n = vn.len();
+    let m = un.len() - n - 1;
+    let top = vn[n - 1];
+
+    // Division by a single digit
+    if n == 1 {
+        let mut rem = un[m + 1];
+        un[m + 1] = 0;
+        for j in (0..=m).rev() {
+            let (qj, r) = div2by1(rem, un[j], top, v);
+            if j < q.len() {
+                q[j] = qj;
+            }
+            rem = r;
+            un[j] = 0;
+        }
+        un[0] = rem;
+        return;
+    }
+
+    let next = vn[n - 2] as u128;
+    for j in (0..=m).rev() {
+        // Estimation of the quotient digit, the highest digit of the dividend
+        // can be equal to the one of the divisor only
+        let (mut qhat, mut rhat) = if un[j + n] < top {
+            let (qj, r) = div2by1(un[j + n], un[j + n - 1], top, v);
+            (qj as u128, r as u128)
+        } else {
+            (u64::MAX as u128, (un[j + n - 1] as u128) + (top as u128))
+        };
+        while rhat >> 64 == 0 && qhat * next > (rhat << 64) | (un[j + n - 2] as u128) {
+            qhat -= 1;
+            rhat += top as u128;
+        }
+
+        // Multiplication and subtraction
+        let mut fw: u128 = 0;
+        let mut borrow = false;
+        for i in 0..n {
+            fw += qhat * (vn[i] as u128);
+            let pair1 = un[i + j].overflowing_sub(fw as u64);
+            let pair2 = pair1.0.overflowing_sub(borrow as u64);
+            un[i + j] = pair2.0;
+            borrow = pair1.1 || pair2.1;
+            fw >>= 64;
+        }
+        let pair1 = un[j + n].overflowing_sub(fw as u64);
+        let pair2 = pair1.0.overflowing_sub(borrow as u64);
+        un[j + n] = pair2.0;
+
+        // The estimate was one too large, the divisor is added back
+        if pair1.1 || pair2.1 {
+            qhat -= 1;
+            let carry = add_slices(&mut un[j..(j + n)], vn);
+            un[j + n] = un[j + n].wrapping_add(carry as u64);
+        }
+
+        if j < q.len() {
+            q[j] = qhat as u64;
+        }
+    }
+}
+
+
+/// Reciprocal `floor((2^128 - 1) / d) - 2^64` of a normalized digit `d`
+/// (with the highest bit set) for `div2by1`.
+pub(crate) fn reciprocal(d: u64) -> u64 {
+    (u128::MAX / d as u128) as u64
+}
+
+
+/// Divides `u1 * 2^64 + u0` by a normalized digit `d` using its reciprocal
+/// `v` instead of the hardware division (Möller and Granlund, "Improved
+/// division by invariant integers", algorithm 4). `u1` must be less than
+/// `d`. Returns the quotient and the remainder.
+#[inline]
+pub(crate) fn div2by1(u1: u64, u0: u64, d: u64, v: u64) -> (u64, u64) {
+    let p = (v as u128) * (u1 as u128) + (((u1 as u128) << 64) | (u0 as u128));
+    let mut q1 = ((p >> 64) as u64).wrapping_add(1);
+    let mut r = u0.wrapping_sub(q1.wrapping_mul(d));
+    if r > p as u64 {
+        q1 = q1.wrapping_sub(1);
+        r = r.wrapping_add(d);
+    }
+    if r >= d {
+        q1 += 1;
+        r -= d;
+    }
+    (q1, r)
+}
+
+
+/// Writes `src` shifted right by `shift < 64` bits into `dst` that is one
+/// digit shorter, the lowest bits are dropped.
+pub(crate) fn shift_slice_right(src: &[u64], shift: u32, dst: &mut [u64]) {
+    for i in 0..dst.len() {
+        dst[i] = src[i] >> shift;
+        if shift > 0 {
+            dst[i] |= src[i + 1] << (64 - shift);
+        }
+    }
+}
+
+
+/// Writes `src` shifted left by `shift < 64` bits into `dst` of the same
+/// length, the bits shifted out are dropped.
+pub(crate) fn shift_slice_left(src: &[u64], shift: u32, dst: &mut [u64]) {
+    for i in (0..src.len()).rev() {
+        dst[i] = src[i] << shift;
+        if shift > 0 && i > 0 {
+            dst[i] |= src[i - 1] >> (64 - shift);
+        }
+    }
+}
+
+
+/// Writes the square of `a` into `res` that must be `2 * a.len()` zero
+/// digits. `scratch` must be at least `4 * a.len()` digits if `a` has
+/// `KARATSUBA_SQUARE_THRESHOLD` digits or more, otherwise it is not used.
+#[inline(always)]
+fn square_slices(a: &[u64], res: &mut [u64], scratch: &mut [u64]) {
+    if a.len() >= KARATSUBA_SQUARE_THRESHOLD {
+        square_karatsuba(a, res, scratch);
+        return;
+    }
+
+    // Products of different digits
+    let n = a.len();
+    for i in 0..n {
+        let x = a[i] as u128;
+        let mut fw: u128 = 0;
+        for (digit, y) in res[(2 * i + 1)..(i + n)].iter_mut().zip(&a[(i + 1)..]) {
+            fw += x * (*y as u128) + (*digit as u128);
+            *digit = fw as u64;
+            fw >>= 64;
+        }
+        res[i + n] = fw as u64;
+    }
+
+    // They are doubled and the squares of the digits are added
+    let mut shifted = 0;
+    for digit in res.iter_mut() {
+        (*digit, shifted) = ((*digit << 1) | shifted, *digit >> 63);
+    }
+    let mut fw: u128 = 0;
+    for (pair, x) in res.chunks_exact_mut(2).zip(a) {
+        let sq = (*x as u128) * (*x as u128);
+        fw += (pair[0] as u128) + (sq as u64 as u128);
+        pair[0] = fw as u64;
+        fw = (pair[1] as u128) + (sq >> 64) + (fw >> 64);
+        pair[1] = fw as u64;
+        fw >>= 64;
+    }
+}
+
+
+/// Karatsuba squaring: `a^2 = z2 * X^2 + (z1 - z2 - z0) * X + z0` where
+/// `X = 2^(64m)`, `z0 = a0^2`, `z2 = a1^2` and `z1 = (a0 + a1)^2`.
+/// The sum of the halves is kept in `res` until `z1` is calculated into
+/// `scratch`.
+fn square_karatsuba(a: &[u64], res: &mut [u64], scratch: &mut [u64]) {
+    let m = a.len() / 2;
+    let (a0, a1) = a.split_at(m);
+
+    let size = a1.len() + 1;
+    let (z1, scratch) = scratch.split_at_mut(size << 1);
+    z1.fill(0);
+    let sa = &mut res[..size];
+    sum_slices(a0, a1, sa);
+    square_slices(sa, z1, scratch);
+    sa.fill(0);
+
+    let (z0, z2) = res.split_at_mut(m << 1);
+    square_slices(a0, z0, scratch);
+    square_slices(a1, z2, scratch);
+    sub_slices(z1, z0);
+    sub_slices(z1, z2);
+
+    let size = cmp::min(z1.len(), res.len() - m);
+    add_slices(&mut res[m..], &z1[..size]);
+}
+
+
+/// Schoolbook multiplication of the slices, `res` is overwritten.
+fn mul_schoolbook(a: &[u64], b: &[u64], res: &mut [u64]) {
+    res.fill(0);
+    for (i, x) in b.iter().enumerate() {
+        #[cfg(all(feature = "adx", target_arch = "x86_64"))]
+        {
+            res[i + a.len()] = adx::mul_add_row(&mut res[i..(i + a.len())], a, *x);
+        }
+        #[cfg(not(all(feature = "adx", target_arch = "x86_64")))]
+        {
+            let mut fw: u128 = 0;
+            for (digit, y) in res[i..(i + a.len())].iter_mut().zip(a) {
+                fw += (*x as u128) * (*y as u128) + (*digit as u128);
+                *digit = fw as u64;
+                fw >>= 64;
+            }
+            res[i + a.len()] = fw as u64;
+        }
+    }
+}
+
+
+/// Karatsuba multiplication for `a.len() / 2 < b.len() <= a.len()`:
+/// `a * b = z2 * X^2 + (z1 - z2 - z0) * X + z0` where `X = 2^(64m)`,
+/// `z0 = a0 * b0`, `z2 = a1 * b1` and `z1 = (a0 + a1) * (b0 + b1)`.
+/// The sums of the halves are kept in `res` until `z1` is calculated into
+/// `scratch`, so every level takes about `a.len()` digits of `scratch`.
+fn mul_karatsuba(a: &[u64], b: &[u64], res: &mut [u64], scratch: &mut [u64]) {
+    let m = a.len() / 2;
+    let (a0, a1) = a.split_at(m);
+    let (b0, b1) = b.split_at(m);
+
+    let size_a = a1.len() + 1;
+    let size_b = cmp::max(b0.len(), b1.len()) + 1;
+    let (z1, scratch) = scratch.split_at_mut(size_a + size_b);
+    let (sa, sb) = res[..(size_a + size_b)].split_at_mut(size_a);
+    sum_slices(a0, a1, sa);
+    sum_slices(b0, b1, sb);
+    mul_slices(sa, sb, z1, scratch);
+
+    // z0 and z2 are written to their places at once
+    let (z0, z2) = res.split_at_mut(m << 1);
+    mul_slices(a0, b0, z0, scratch);
+    mul_slices(a1, b1, z2, scratch);
+    sub_slices(z1, z0);
+    sub_slices(z1, z2);
+
+    // The middle term fits into the result, so its high digits are zeros
+    let size = cmp::min(z1.len(), res.len() - m);
+    add_slices(&mut res[m..], &z1[..size]);
+}
+
+
+/// Writes `x0 + x1` into `dst` that is longer than both of them.
+fn sum_slices(x0: &[u64], x1: &[u64], dst: &mut [u64]) {
+    dst.fill(0);
+    dst[..x0.len()].copy_from_slice(x0);
+    add_slices(dst, x1);
+}
+
+
+/// Toom-3 multiplication for `2 * a.len() / 3 < b.len() <= a.len()`.
+/// The operands are split into three parts as polynomials in `X = 2^(64k)`,
+/// the product is evaluated in the points `0, 1, -1, -2, inf` and
+/// interpolated by the sequence of Bodrato. The signed intermediate values
+/// are kept as magnitudes with separate signs in `res` and `scratch`.
+fn mul_toom3(a: &[u64], b: &[u64], res: &mut [u64], scratch: &mut [u64]) {
+    let k = a.len().div_ceil(3);
+    let split = |x| {
+        let (x0, x12) = <[u64]>::split_at(x, k);
+        let (x1, x2) = x12.split_at(cmp::min(k, x12.len()));
+        (x0, x1, x2)
+    };
+    let (a0, a1, a2) = split(a);
+    let (b0, b1, b2) = split(b);
+
+    // The values in the points fit into k + 1 digits, their products
+    // and the coefficients fit into w digits
+    let e = k + 1;
+    let w = e << 1;
+    let (s1, scratch) = scratch.split_at_mut(w);
+    let (s2, scratch) = scratch.split_at_mut(w);
+
+    // r1 = p(1) * q(1) in s1
+    let (p, q) = res[..w].split_at_mut(e);
+    sum_slices(a0, a1, p);
+    add_slices(p, a2);
+    sum_slices(b0, b1, q);
+    add_slices(q, b2);
+    mul_slices(p, q, s1, scratch);
+
+    // rm1 = p(-1) * q(-1) in s2
+    sum_slices(a0, a2, p);
+    let pn = add_signed(p, false, a1, true);
+    sum_slices(b0, b2, q);
+    let qn = add_signed(q, false, b1, true);
+    mul_slices(p, q, s2, scratch);
+    let rm1n = pn != qn;
+
+    // rm2 = p(-2) * q(-2) after p and q in res
+    let eval = |x: &mut [u64], negative, x0, x2| {
+        let negative = add_signed(x, negative, x2, false);
+        double_slice(x);
+        add_signed(x, negative, x0, true)
+    };
+    let pn = eval(p, pn, a0, a2);
+    let qn = eval(q, qn, b0, b2);
+    let (pq, rest) = res.split_at_mut(w);
+    let rm2 = &mut rest[..w];
+    mul_slices(&pq[..e], &pq[e..], rm2, scratch);
+
+    // c3 = (rm2 - r1) / 3 in place of rm2, c1 = (r1 - rm1) / 2 in s1
+    let c3n = add_signed(rm2, pn != qn, s1, true);
+    div_exact_slice(rm2, 3);
+    let c1n = add_signed(s1, false, s2, !rm1n);
+    div_exact_slice(s1, 2);
+
+    // c2 = rm1 - r0 in s2, r0 is placed at the beginning of res
+    let r0 = &mut res[..(k << 1)];
+    mul_slices(a0, b0, r0, scratch);
+    let c2n = add_signed(s2, rm1n, r0, true);
+
+    // rinf at the beginning of the left scratch
+    let (rinf, scratch) = scratch.split_at_mut(a2.len() + b2.len());
+    mul_slices(a2, b2, rinf, scratch);
+
+    // c3 = (c2 - c3) / 2 + 2 * rinf, c2 = c2 + c1 - rinf, c1 = c1 - c3
+    let c3 = &mut res[w..(w << 1)];
+    let c3n = add_signed(c3, !c3n, s2, c2n);
+    div_exact_slice(c3, 2);
+    let c3n = add_signed(c3, c3n, rinf, false);
+    let c3n = add_signed(c3, c3n, rinf, false);
+    let c2n = add_signed(s2, c2n, s1, c1n);
+    let c2n = add_signed(s2, c2n, rinf, true);
+    let c1n = add_signed(s1, c1n, c3, !c3n);
+    debug_assert!([(c1n, &*s1), (c2n, &*s2), (c3n, &*c3)].iter()
+                  .all(|(negative, c)| !negative || c.iter().all(|d| *d == 0)));
+
+    // All the coefficients are nonnegative and their sum fits into res
+    let c3 = {
+        let dst = &mut scratch[..w];
+        dst.copy_from_slice(&res[w..(w << 1)]);
+        dst
+    };
+    res[(k << 1)..].fill(0);
+    // rinf is longer than its place only if b2 is empty, so it is zero
+    let size = res.len() - (k << 2);
+    res[(k << 2)..].copy_from_slice(&rinf[..size]);
+    for (i, c) in [s1, s2, c3].into_iter().enumerate() {
+        let start = (i + 1) * k;
+        let size = cmp::min(w, res.len() - start);
+        add_slices(&mut res[start..], &c[..size]);
+    }
+}
+
+
+/// Adds the signed value `y` to the signed value `x` that is not shorter,
+/// the magnitudes are stored separately from the signs. Returns the new
+/// sign of `x`.
+fn add_signed(x: &mut [u64], x_negative: bool, y: &[u64], y_negative: bool) -> bool {
+    if x_negative == y_negative {
+        add_slices(x, y);
+        x_negative
+    } else if sub_slices(x, y) {
+        // Two's complement gives the magnitude of the negative difference
+        for digit in x.iter_mut() {
+            *digit = !*digit;
+        }
+        add_slices(x, &[1]);
+        !x_negative
+    } else {
+        x_negative
+    }
+}
+
+
+/// Multiplies the slice by 2 in place.
+fn double_slice(x: &mut [u64]) {
+    let mut fw = 0;
+    for digit in x.iter_mut() {
+        let next = *digit >> 63;
+        *digit = (*digit << 1) | fw;
+        fw = next;
+    }
+}
+
+
+/// Division by a small number that divides the slice exactly.
+fn div_exact_slice(x: &mut [u64], divisor: u64) {
+    let mut rem: u128 = 0;
+    for digit in x.iter_mut().rev() {
+        let y = (rem << 64) | (*digit as u128);
+        *digit = (y / divisor as u128) as u64;
+        rem = y % divisor as u128;
+    }
+    debug_assert_eq!(rem, 0);
+}
+
+
+/// Compares the slices of digits as integers.
+pub(crate) fn cmp_slices(a: &[u64], b: &[u64]) -> cmp::Ordering {
+    let order = |x: &[u64]| x.iter().rposition(|d| *d != 0).map_or(0, |i| i + 1);
+    let (order1, order2) = (order(a), order(b));
+    if order1 != order2 {
+        return order1.cmp(&order2);
+    }
+    a[..order1].iter().rev().cmp(b[..order2].iter().rev())
+}
+
+
+/// Adds `src` to `dst` (not shorter) propagating the carry, returns
+/// the final carry.
+fn add_slices(dst: &mut [u64], src: &[u64]) -> bool {
+    let mut fw = false;
+    for i in 0..dst.len() {
+        if i >= src.len() && !fw {
+            break;
+        }
+        let digit = if i < src.len() { src[i] } else { 0 };
+        let pair1 = dst[i].overflowing_add(digit);
+        let pair2 = pair1.0.overflowing_add(fw as u64);
+        dst[i] = pair2.0;
+        fw = pair1.1 || pair2.1;
+    }
+    fw
+}
+
+
+/// Subtracts `src` from `dst` (not shorter) propagating the borrow,
+/// returns the final borrow.
+pub(crate) fn sub_slices(dst: &mut [u64], src: &[u64]) -> bool {
+    let mut fw = false;
+    for i in 0..dst.len() {
+        if i >= src.len() && !fw {
+            break;
+        }
+        let digit = if i < src.len() { src[i] } else { 0 };
+        let pair1 = dst[i].overflowing_sub(digit);
+        let pair2 = pair1.0.overflowing_sub(fw as u64);
+        dst[i] = pair2.0;
+        fw = pair1.1 || pair2.1;
+    }
+    fw
+}
+
+
+impl<const N: usize> ops::Add<&Bigi<N>> for Bigi<N> {
+    type Output = Bigi<N>;
+
+    fn add(self, other: &Bigi<N>) -> Bigi<N> {
+        let mut res = self;
+        res += other;
+        res
+    }
+}
+
+
+impl<const N: usize> ops::AddAssign<&Bigi<N>> for Bigi<N> {
+    fn add_assign(&mut self, other: &Bigi<N>) {
+        #[cfg(feature = "simd")]
+        if simd::add_assign(&mut self.digits, &other.digits).is_some() {
+            return;
+        }
+        #[cfg(all(feature = "adx", target_arch = "x86_64"))]
+        adx::add_assign(&mut self.digits, &other.digits);
+        #[cfg(not(all(feature = "adx", target_arch = "x86_64")))]
+        {
+            let mut fw: u64 = 0;
+            for i in 0..N {
+                let pair = self.digits[i].overflowing_add(other.digits[i]);
+                self.digits[i] = pair.0.overflowing_add(fw).0;
+                fw = (pair.1 || (fw == 1 && self.digits[i] == 0)) as u64;
+            }
+        }
+    }
+}
+
+
+impl<const N: usize> ops::Sub<&Bigi<N>> for Bigi<N> {
+    type Output = Bigi<N>;
+
+    fn sub(self, other: &Bigi<N>) -> Bigi<N> {
+        let mut res = self;
+        res -= other;
+        res
+    }
+}
+
+
+impl<const N: usize> ops::SubAssign<&Bigi<N>> for Bigi<N> {
+    fn sub_assign(&mut self, other: &Bigi<N>) {
+        #[cfg(feature = "simd")]
+        if simd::sub_assign(&mut self.digits, &other.digits).is_some() {
+            return;
+        }
+        #[cfg(all(feature = "adx", target_arch = "x86_64"))]
+        adx::sub_assign(&mut self.digits, &other.digits);
+        #[cfg(not(all(feature = "adx", target_arch = "x86_64")))]
+        {
+            let mut fw: u64 = 0;
+            for i in 0..N {
+                let pair = self.digits[i].overflowing_sub(other.digits[i]);
+                self.digits[i] = pair.0.overflowing_sub(fw).0;
+                fw = (pair.1 || (fw == 1 && pair.0 == 0)) as u64;
+            }
+        }
+    }
+}
+
+
+impl<const N: usize> ops::Mul<&Bigi<N>> for Bigi<N> {
+    type Output = Bigi<N>;
+
+    fn mul(self, other: &Bigi<N>) -> Bigi<N> {
+        let mut res = Bigi::<N>::new();
+        Bigi::mul_into(&mut res, &self, other);
+        res
+    }
+}
+
+
+impl<const N: usize> ops::MulAssign<&Bigi<N>> for Bigi<N> {
+    fn mul_assign(&mut self, other: &Bigi<N>) {
+        if let Some(product) = self.mul_large(other) {
+            self.digits = product[0];
+            return;
+        }
+
+        // The digits of self are taken from the most significant one, so
+        // every row of the product is added to the positions that are not
+        // needed anymore
+        for i in (0..self.get_order()).rev() {
+            let a = self.digits[i] as u128;
+            self.digits[i] = 0;
+            let mut fw: u128 = 0;
+            for j in 0..(N - i) {
+                fw += a * (other.digits[j] as u128) +
+                      (self.digits[i + j] as u128);
+                self.digits[i + j] = fw as u64;
+                fw >>= 64;
+            }
+        }
+    }
+}
+
+
+impl<const N: usize> ops::Add<u64> for Bigi<N> {
+    type Output = Bigi<N>;
+
+    fn add(self, other: u64) -> Bigi<N> {
+        let mut res = self;
+        res += other;
+        res
+    }
+}
+
+
+impl<const N: usize> ops::AddAssign<u64> for Bigi<N> {
+    fn add_assign(&mut self, other: u64) {
+        let mut fw = other;
+        for digit in self.digits.iter_mut() {
+            let carry;
+            (*digit, carry) = digit.overflowing_add(fw);
+            if !carry {
+                break;
+            }
+            fw = 1;
+        }
+    }
+}
+
+
+impl<const N: usize> ops::Sub<u64> for Bigi<N> {
+    type Output = Bigi<N>;
+
+    fn sub(self, other: u64) -> Bigi<N> {
+        let mut res = self;
+        res -= other;
+        res
+    }
+}
+
+
+impl<const N: usize> ops::SubAssign<u64> for Bigi<N> {
+    fn sub_assign(&mut self, other: u64) {
+        let mut fw = other;
+        for digit in self.digits.iter_mut() {
+            let borrow;
+            (*digit, borrow) = digit.overflowing_sub(fw);
+            if !borrow {
+                break;
+            }
+            fw = 1;
+        }
+    }
+}
+
+
+impl<const N: usize> ops::Mul<u64> for Bigi<N> {
+    type Output = Bigi<N>;
+
+    fn mul(self, other: u64) -> Bigi<N> {
+        let mut res = self;
+        res *= other;
+        res
+    }
+}
+
+
+impl<const N: usize> ops::MulAssign<u64> for Bigi<N> {
+    fn mul_assign(&mut self, other: u64) {
+        let order = self.get_order();
+        let b = other as u128;
+        let mut fw: u128 = 0;
+        for i in 0..order {
+            fw += (self.digits[i] as u128) * b;
+            self.digits[i] = fw as u64;
+            fw >>= 64;
+        }
+        if order < N {
+            self.digits[order] = fw as u64;
+        }
+    }
+}
+
+
+impl<const N: usize> ops::Add<&Bigi<N>> for &Bigi<N> {
+    type Output = Bigi<N>;
+
+    fn add(self, other: &Bigi<N>) -> Bigi<N> {
+        let mut res = Bigi::<N>::new();
+        Bigi::add_into(&mut res, self, other);
+        res
+    }
+}
+
+
+impl<const N: usize> ops::Sub<&Bigi<N>> for &Bigi<N> {
+    type Output = Bigi<N>;
+
+    fn sub(self, other: &Bigi<N>) -> Bigi<N> {
+        let mut res = Bigi::<N>::new();
+        Bigi::sub_into(&mut res, self, other);
+        res
+    }
+}
+
+
+impl<const N: usize> ops::Mul<&Bigi<N>> for &Bigi<N> {
+    type Output = Bigi<N>;
+
+    fn mul(self, other: &Bigi<N>) -> Bigi<N> {
+        let mut res = Bigi::<N>::new();
+        Bigi::mul_into(&mut res, self, other);
+        res
+    }
+}
+
+
+impl<const N: usize> ops::Div<&Bigi<N>> for Bigi<N> {
+    type Output = Bigi<N>;
+
+    fn div(self, other: &Bigi<N>) -> Bigi<N> {
+        let mut dividend = self;
+        dividend.divide(other)
+    }
+}
+
+
+impl<const N: usize> ops::DivAssign<&Bigi<N>> for Bigi<N> {
+    fn div_assign(&mut self, other: &Bigi<N>) {
+        *self = self.divide(other);
+    }
+}
+
+
+impl<const N: usize> ops::Rem<&Bigi<N>> for Bigi<N> {
+    type Output = Bigi<N>;
+
+    fn rem(self, other: &Bigi<N>) -> Bigi<N> {
+        let mut res = self;
+        res %= other;
+        res
+    }
+}
+
+
+impl<const N: usize> ops::RemAssign<&Bigi<N>> for Bigi<N> {
+    fn rem_assign(&mut self, other: &Bigi<N>) {
+        self.divide(other);
+    }
+}
+
+
+impl<const N: usize> ops::Div<&Bigi<N>> for &Bigi<N> {
+    type Output = Bigi<N>;
+
+    fn div(self, other: &Bigi<N>) -> Bigi<N> {
+        *self / other
+    }
+}
+
+
+impl<const N: usize> ops::Rem<&Bigi<N>> for &Bigi<N> {
+    type Output = Bigi<N>;
+
+    fn rem(self, other: &Bigi<N>) -> Bigi<N> {
+        *self % other
+    }
+}
+
+
+/// Implements the operator for the owned right operand through
+/// the implementations for the borrowed one.
+macro_rules! forward_owned_binop {
+    ($imp:ident, $method:ident, $imp_assign:ident, $method_assign:ident) => {
+        impl<const N: usize> ops::$imp<Bigi<N>> for Bigi<N> {
+            type Output = Bigi<N>;
+
+            fn $method(self, other: Bigi<N>) -> Bigi<N> {
+                ops::$imp::$method(self, &other)
+            }
+        }
+
+        impl<const N: usize> ops::$imp<Bigi<N>> for &Bigi<N> {
+            type Output = Bigi<N>;
+
+            fn $method(self, other: Bigi<N>) -> Bigi<N> {
+                ops::$imp::$method(self, &other)
+            }
+        }
+
+        impl<const N: usize> ops::$imp_assign<Bigi<N>> for Bigi<N> {
+            fn $method_assign(&mut self, other: Bigi<N>) {
+                ops::$imp_assign::$method_assign(self, &other);
+            }
+        }
+    };
+}
+
+
+forward_owned_binop!(Add, add, AddAssign, add_assign);
+forward_owned_binop!(Sub, sub, SubAssign, sub_assign);
+forward_owned_binop!(Mul, mul, MulAssign, mul_assign);
+forward_owned_binop!(Div, div, DivAssign, div_assign);
+forward_owned_binop!(Rem, rem, RemAssign, rem_assign);
+
+
+/// The bits shifted beyond `N` digits are dropped, so shifting by `N * 64`
+/// or more gives zero. Use `checked_shl` to detect the lost bits.
+impl<const N: usize> ops::ShlAssign<usize> for Bigi<N> {
+    fn shl_assign(&mut self, rhs: usize) {
+        if rhs >= N << 6 {
+            self.digits = [0; N];
+            return;
+        }
+        let rhs_q = rhs >> 6;
+        let rhs_r = rhs & 63;
+        let mut extra: u64 = 0;
+
+        for i in (0..(N - rhs_q)).rev() {
+            if rhs_r > 0 {
+                extra = self.digits[i] >> (64 - rhs_r);
+            }
+            if i < N - rhs_q - 1 {
+                self.digits[i + rhs_q + 1] += extra;
+            }
+            self.digits[i + rhs_q] = self.digits[i] << rhs_r;
+        }
+
+        for i in 0..rhs_q {
+            self.digits[i] = 0;
+        }
+    }
+}
+
+
+impl<const N: usize> ops::Shl<usize> for Bigi<N> {
+    type Output = Bigi<N>;
+
+    fn shl(self, rhs: usize) -> Bigi<N> {
+        let mut res = self;
+        res <<= rhs;
+        res
+    }
+}
+
+
+/// The lowest bits are dropped, so shifting by `N * 64` or more gives zero.
+impl<const N: usize> ops::ShrAssign<usize> for Bigi<N> {
+    fn shr_assign(&mut self, rhs: usize) {
+        if rhs >= N << 6 {
+            self.digits = [0; N];
+            return;
+        }
+        let rhs_q = rhs >> 6;
+        let rhs_r = rhs & 63;
+        let mut extra: u64 = 0;
+
+        for i in rhs_q..N {
+            if rhs_r > 0 {
+                extra = self.digits[i] << (64 - rhs_r);
+            }
+            self.digits[i - rhs_q] = self.digits[i] >> rhs_r;
+            if i > rhs_q {
+                self.digits[i - rhs_q - 1] += extra;
+            }
+        }
+
+        for i in 0..rhs_q {
+            self.digits[N - i - 1] = 0;
+        }
+    }
+}
+
+
+impl<const N: usize> ops::Shr<usize> for Bigi<N> {
+    type Output = Bigi<N>;
+
+    fn shr(self, rhs: usize) -> Bigi<N> {
+        let mut res = self;
+        res >>= rhs;
+        res
+    }
+}
+
+
+impl<const N: usize> ops::BitAnd<&Bigi<N>> for Bigi<N> {
+    type Output = Bigi<N>;
 
+    fn bitand(self, other: &Bigi<N>) -> Bigi<N> {
+        let mut res = self;
+        res &= other;
         res
     }
+}
 
-    fn lead_u128(&self) -> u128 {
-        for i in (0..N).rev() {
-            if self.digits[i] != 0 {
-                if i == 0 {
-                    return self.digits[i] as u128;
-                }
-                else {
-                    return ((self.digits[i] as u128) << 64) +
-                           (self.digits[i - 1] as u128);
-                }
-            }
+
+impl<const N: usize> ops::BitAndAssign<&Bigi<N>> for Bigi<N> {
+    fn bitand_assign(&mut self, other: &Bigi<N>) {
+        for (x, y) in self.digits.iter_mut().zip(other.digits.iter()) {
+            *x &= *y;
         }
-        0
     }
 }
 
 
-impl<const N: usize> ops::Add<&Bigi<N>> for Bigi<N> {
+impl<const N: usize> ops::BitOr<&Bigi<N>> for Bigi<N> {
     type Output = Bigi<N>;
 
-    fn add(self, other: &Bigi<N>) -> Bigi<N> {
-        let mut res = self.clone();
-        res += other;
+    fn bitor(self, other: &Bigi<N>) -> Bigi<N> {
+        let mut res = self;
+        res |= other;
         res
     }
 }
 
 
-impl<const N: usize> ops::AddAssign<&Bigi<N>> for Bigi<N> {
-    fn add_assign(&mut self, other: &Bigi<N>) {
-        let mut fw: u64 = 0;
-        for i in 0..N {
-            let pair = self.digits[i].overflowing_add(other.digits[i]);
-            self.digits[i] = pair.0.overflowing_add(fw).0;
-            fw = (pair.1 || (fw == 1 && self.digits[i] == 0)) as u64;
+impl<const N: usize> ops::BitOrAssign<&Bigi<N>> for Bigi<N> {
+    fn bitor_assign(&mut self, other: &Bigi<N>) {
+        for (x, y) in self.digits.iter_mut().zip(other.digits.iter()) {
+            *x |= *y;
         }
     }
 }
 
 
-impl<const N: usize> ops::Sub<&Bigi<N>> for Bigi<N> {
+impl<const N: usize> ops::BitXor<&Bigi<N>> for Bigi<N> {
     type Output = Bigi<N>;
 
-    fn sub(self, other: &Bigi<N>) -> Bigi<N> {
-        let mut res = self.clone();
-        res -= other;
+    fn bitxor(self, other: &Bigi<N>) -> Bigi<N> {
+        let mut res = self;
+        res ^= other;
         res
     }
 }
 
 
-impl<const N: usize> ops::SubAssign<&Bigi<N>> for Bigi<N> {
-    fn sub_assign(&mut self, other: &Bigi<N>) {
-        let mut fw: u64 = 0;
-        for i in 0..N {
-            let pair = self.digits[i].overflowing_sub(other.digits[i]);
-            self.digits[i] = pair.0.overflowing_sub(fw).0;
-            fw = (pair.1 || (fw == 1 && pair.0 == 0)) as u64;
+impl<const N: usize> ops::BitXorAssign<&Bigi<N>> for Bigi<N> {
+    fn bitxor_assign(&mut self, other: &Bigi<N>) {
+        for (x, y) in self.digits.iter_mut().zip(other.digits.iter()) {
+            *x ^= *y;
         }
     }
 }
 
 
-impl<const N: usize> ops::Mul<&Bigi<N>> for Bigi<N> {
+impl<const N: usize> ops::Not for Bigi<N> {
     type Output = Bigi<N>;
 
-    fn mul(self, other: &Bigi<N>) -> Bigi<N> {
-        let mut res = Bigi::<N>::new();
-        for i in 0..N {
-            let mut fw: u128 = 0;
-            for j in 0..(N - i) {
-                fw = (other.digits[i] as u128) * (self.digits[j] as u128) +
-                     (res.digits[i + j] as u128) + fw;
-                res.digits[i + j] = fw as u64;
-                fw >>= 64;
-            }
+    fn not(self) -> Bigi<N> {
+        let mut res = self;
+        for x in res.digits.iter_mut() {
+            *x = !*x;
         }
         res
     }
 }
 
 
-impl<const N: usize> ops::MulAssign<&Bigi<N>> for Bigi<N> {
-    fn mul_assign(&mut self, other: &Bigi<N>) {
-        *self = *self * other;
+impl<const N: usize> cmp::PartialEq for Bigi<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.digits == other.digits
     }
 }
 
 
-impl<const N: usize> ops::Div<&Bigi<N>> for Bigi<N> {
-    type Output = Bigi<N>;
+impl<const N: usize> cmp::Eq for Bigi<N> {}
 
-    fn div(self, other: &Bigi<N>) -> Bigi<N> {
-        let mut dividend = self.clone();
-        dividend.divide(other)
+
+impl<const N: usize> cmp::PartialOrd for Bigi<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
     }
 }
 
 
-impl<const N: usize> ops::DivAssign<&Bigi<N>> for Bigi<N> {
-    fn div_assign(&mut self, other: &Bigi<N>) {
-        *self = *self / other;
+impl<const N: usize> cmp::Ord for Bigi<N> {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        #[cfg(feature = "simd")]
+        if let Some(ordering) = simd::cmp(&self.digits, &other.digits) {
+            return ordering;
+        }
+        for i in (0..N).rev() {
+            if self.digits[i] > other.digits[i] {
+                return cmp::Ordering::Greater;
+            }
+            if self.digits[i] < other.digits[i] {
+                return cmp::Ordering::Less;
+            }
+        }
+        cmp::Ordering::Equal
     }
 }
 
 
-impl<const N: usize> ops::Rem<&Bigi<N>> for Bigi<N> {
-    type Output = Bigi<N>;
+impl<const N: usize> cmp::PartialEq<u64> for Bigi<N> {
+    fn eq(&self, other: &u64) -> bool {
+        self.partial_cmp(other) == Some(cmp::Ordering::Equal)
+    }
+}
 
-    fn rem(self, other: &Bigi<N>) -> Bigi<N> {
-        let mut res = self.clone();
-        res %= other;
-        res
+
+impl<const N: usize> cmp::PartialOrd<u64> for Bigi<N> {
+    fn partial_cmp(&self, other: &u64) -> Option<cmp::Ordering> {
+        for i in (1..N).rev() {
+            if self.digits[i] > 0 {
+                return Some(cmp::Ordering::Greater);
+            }
+        }
+        self.digits[0].partial_cmp(other)
     }
 }
 
 
-impl<const N: usize> ops::RemAssign<&Bigi<N>> for Bigi<N> {
-    fn rem_assign(&mut self, other: &Bigi<N>) {
-        self.divide(other);
+impl<const N: usize> cmp::PartialEq<Bigi<N>> for u64 {
+    fn eq(&self, other: &Bigi<N>) -> bool {
+        other == self
     }
 }
 
 
-impl<const N: usize> ops::ShlAssign<usize> for Bigi<N> {
-    fn shl_assign(&mut self, rhs: usize) {
-        let rhs_q = rhs >> 6;
-        let rhs_r = rhs & 63;
-        let mut extra: u64 = 0;
+impl<const N: usize> cmp::PartialOrd<Bigi<N>> for u64 {
+    fn partial_cmp(&self, other: &Bigi<N>) -> Option<cmp::Ordering> {
+        other.partial_cmp(self).map(cmp::Ordering::reverse)
+    }
+}
 
-        for i in (0..(N - rhs_q)).rev() {
-            if rhs_r > 0 {
-                extra = self.digits[i] >> (64 - rhs_r);
+
+#[cfg(test)]
+mod tests {
+    use crate::bigi;
+    use super::*;
+    use test::Bencher;
+
+    #[test]
+    fn test_add() {
+        assert_eq!(bigi![8; 2] + &bigi![8; 3], bigi![8; 5]);
+        assert_eq!(
+            bigi![8; 3567587328, 232, 0, 29] +
+            &bigi![8; 12312344, 1, 1234098120, 21556, 134236576],
+            bigi![8; 3579899672, 233, 1234098120, 21585, 134236576]
+        );
+    }
+
+    #[test]
+    fn test_sub() {
+        assert_eq!(bigi![8; 5] - &bigi![8; 3], bigi![8; 2]);
+        assert_eq!(
+            bigi![8; 3579899672, 233, 1234098120, 21585, 134236576] -
+            &bigi![8; 12312344, 1, 1234098120, 21556, 134236576],
+            bigi![8; 3567587328, 232, 0, 29]
+        );
+    }
+
+    #[test]
+    fn test_mul() {
+        assert_eq!(bigi![8; 5] * &bigi![8; 2], bigi![8; 10]);
+        assert_eq!(
+            bigi![8; 12312344, 1, 1234098120, 21556, 134236576] *
+            &bigi![8; 3567587328, 232, 0, 29],
+            bigi![8; 43925362432376832, 6424051136, 4402752814420623592,
+                     77189580264184, 478900707496709949, 66931731112,
+                     625124, 3892860704]
+        );
+    }
+
+    #[test]
+    fn test_operand_forms() {
+        let a = bigi![4; 100, 3];
+        let b = bigi![4; 7];
+        for (x, y) in [(a + b, a + &b), (&a + b, a + &b), (&a + &b, a + &b),
+                       (a - b, a - &b), (&a - b, a - &b), (&a - &b, a - &b),
+                       (a * b, a * &b), (&a * b, a * &b), (&a * &b, a * &b),
+                       (a / b, a / &b), (&a / b, a / &b), (&a / &b, a / &b),
+                       (a % b, a % &b), (&a % b, a % &b), (&a % &b, a % &b)] {
+            assert_eq!(x, y);
+        }
+
+        let mut x = a;
+        x += b;
+        x *= b;
+        x -= b;
+        assert_eq!(x, (a + &b) * &b - &b);
+        x %= b;
+        assert_eq!(x, bigi![4; 0]);
+        let mut x = a;
+        x /= b;
+        assert_eq!(x, a / &b);
+    }
+
+    #[test]
+    fn test_checked() {
+        let max = bigi![2; u64::MAX, u64::MAX];
+        assert_eq!(max.checked_add(&bigi![2; 0]), Some(max));
+        assert_eq!(max.checked_add(&bigi![2; 1]), None);
+        assert_eq!(bigi![2; 0].checked_sub(&bigi![2; 0]), Some(bigi![2; 0]));
+        assert_eq!(bigi![2; 0].checked_sub(&bigi![2; 1]), None);
+        assert_eq!(max.checked_mul(&bigi![2; 1]), Some(max));
+        assert_eq!(max.checked_mul(&bigi![2; 0]), Some(bigi![2; 0]));
+        assert_eq!(max.checked_mul(&bigi![2; 2]), None);
+        assert_eq!(bigi![2; 0, 1].checked_mul(&bigi![2; u64::MAX]), Some(bigi![2; 0, u64::MAX]));
+        assert_eq!(bigi![4; 0, 0, 1].checked_mul(&bigi![4; 0, 0, 1]), None);
+
+        let mut rng = rand::thread_rng();
+        for (bits1, bits2) in [(0, 256), (100, 150), (128, 128), (129, 128), (200, 100), (256, 256)] {
+            let x = Bigi::<4>::gen_random(&mut rng, bits1, false);
+            let y = Bigi::<4>::gen_random(&mut rng, bits2, false);
+            let sum = Bigi::<8>::from(&x) + &Bigi::<8>::from(&y);
+            assert_eq!(x.checked_add(&y).is_some(), sum.bit_length() <= 256);
+            let product = Bigi::<8>::from(&x) * &Bigi::<8>::from(&y);
+            assert_eq!(x.checked_mul(&y).is_some(), product.bit_length() <= 256);
+            if let Some(z) = x.checked_mul(&y) {
+                assert_eq!(Bigi::<8>::from(&z), product);
             }
-            if i < N - rhs_q - 1 {
-                self.digits[i + rhs_q + 1] += extra;
+            assert_eq!(x.checked_sub(&y).is_some(), x >= y);
+        }
+    }
+
+    #[test]
+    fn test_saturating() {
+        let max = Bigi::<2>::MAX;
+        assert_eq!(max.saturating_add(&bigi![2; 0]), max);
+        assert_eq!(max.saturating_add(&max), max);
+        assert_eq!(bigi![2; u64::MAX].saturating_add(&bigi![2; 1]), bigi![2; 0, 1]);
+        assert_eq!(bigi![2; 7].saturating_sub(&bigi![2; 7]), bigi![2; 0]);
+        assert_eq!(bigi![2; 0].saturating_sub(&max), bigi![2; 0]);
+        assert_eq!(bigi![2; 0, 1].saturating_sub(&bigi![2; 1]), bigi![2; u64::MAX]);
+        assert_eq!(max.saturating_mul(&bigi![2; 1]), max);
+        assert_eq!(max.saturating_mul(&bigi![2; 0]), bigi![2; 0]);
+        assert_eq!(max.saturating_mul(&max), max);
+        assert_eq!(bigi![2; 1 << 32].saturating_mul(&bigi![2; 1 << 32]), bigi![2; 0, 1]);
+    }
+
+    #[test]
+    fn test_abs_diff() {
+        assert_eq!(bigi![4; 7].abs_diff(&bigi![4; 7]), bigi![4; 0]);
+        assert_eq!(bigi![4; 0].abs_diff(&Bigi::<4>::MAX), Bigi::<4>::MAX);
+        assert_eq!(bigi![4; 1, 2].abs_diff(&bigi![4; 2, 1]), bigi![4; u64::MAX]);
+        assert_eq!(bigi![4; 2, 1].abs_diff(&bigi![4; 1, 2]), bigi![4; u64::MAX]);
+    }
+
+    #[test]
+    fn test_pow() {
+        assert_eq!(bigi![2; 0].pow(0), Some(bigi![2; 1]));
+        assert_eq!(bigi![2; 0].pow(5), Some(bigi![2; 0]));
+        assert_eq!(bigi![2; 1].pow(u64::MAX), Some(bigi![2; 1]));
+        assert_eq!(bigi![2; 3].pow(1), Some(bigi![2; 3]));
+        assert_eq!(bigi![2; 3].pow(80), Some(bigi![2; 3].powmod_u64(80, &bigi![2; 0, 1 << 63])));
+        assert_eq!(bigi![2; 3].pow(81), None);
+        assert_eq!(bigi![2; 2].pow(u64::MAX), None);
+        assert_eq!(bigi![2; 0, 1].pow(1), Some(bigi![2; 0, 1]));
+        assert_eq!(bigi![2; 0, 1].pow(2), None);
+
+        let mut x = bigi![4; 1];
+        for e in 0..162 {
+            assert_eq!(bigi![4; 3].pow(e), Some(x));
+            x *= 3;
+        }
+        assert_eq!(bigi![4; 3].pow(162), None);
+    }
+
+    #[test]
+    fn test_isqrt() {
+        for x in 0..1000u64 {
+            let r = bigi![1; x].isqrt().digits[0];
+            assert!(r * r <= x && (r + 1) * (r + 1) > x);
+        }
+        assert_eq!(Bigi::<1>::MAX.isqrt(), bigi![1; u32::MAX as u64]);
+        assert_eq!(Bigi::<4>::MAX.isqrt(), bigi![4; u64::MAX, u64::MAX]);
+
+        let mut rng = rand::thread_rng();
+        for bits in [1, 2, 63, 64, 65, 127, 128, 200, 255, 256] {
+            let x = Bigi::<4>::gen_random(&mut rng, bits, true);
+            let r = Bigi::<8>::from(&x.isqrt());
+            let x = Bigi::<8>::from(&x);
+            assert!(r * &r <= x);
+            assert!((r + 1) * &(r + 1) > x);
+            assert_eq!((r * &r).isqrt(), r);
+        }
+    }
+
+    #[test]
+    fn test_add_sub_u64() {
+        assert_eq!(bigi![4; 5] + 2, bigi![4; 7]);
+        assert_eq!(bigi![4; 5] - 2, bigi![4; 3]);
+        assert_eq!(bigi![4; u64::MAX, u64::MAX] + 1, bigi![4; 0, 0, 1]);
+        assert_eq!(bigi![4; 0, 0, 1] - 1, bigi![4; u64::MAX, u64::MAX]);
+        assert_eq!(bigi![2; u64::MAX, u64::MAX] + 2, bigi![2; 1]);
+        assert_eq!(bigi![2; 1] - 2, bigi![2; u64::MAX, u64::MAX]);
+
+        let mut rng = rand::thread_rng();
+        let ys = [0, 1, u64::MAX, rand::Rng::gen(&mut rng)];
+        for bits in [0, 1, 64, 100, 256] {
+            let x = Bigi::<4>::gen_random(&mut rng, bits, false);
+            for y in ys {
+                let mut z = x;
+                z += y;
+                assert_eq!(z, x + &Bigi::<4>::from(y));
+                assert_eq!(x + y, z);
+                let mut z = x;
+                z -= y;
+                assert_eq!(z, x - &Bigi::<4>::from(y));
+                assert_eq!(x - y, z);
             }
-            self.digits[i + rhs_q] = self.digits[i] << rhs_r;
         }
+    }
 
-        for i in 0..rhs_q {
-            self.digits[i] = 0;
+    #[test]
+    fn test_mul_u64() {
+        assert_eq!(bigi![4; 5] * 2, bigi![4; 10]);
+        assert_eq!(bigi![4; 0] * 7, bigi![4; 0]);
+        assert_eq!(bigi![2; u64::MAX, 1] * u64::MAX, bigi![2; 1, u64::MAX - 2]);
+        assert_eq!(bigi![2; 0, u64::MAX] * 2, bigi![2; 0, u64::MAX - 1]);
+
+        let mut rng = rand::thread_rng();
+        let ys = [0, 1, 1 << 63, u64::MAX, rand::Rng::gen(&mut rng)];
+        for bits in [0, 1, 64, 100, 200, 255, 256] {
+            let x = Bigi::<4>::gen_random(&mut rng, bits, false);
+            for y in ys {
+                let mut z = x;
+                z *= y;
+                assert_eq!(z, x * &Bigi::<4>::from(y));
+                assert_eq!(x * y, z);
+            }
         }
     }
-}
 
+    #[test]
+    fn test_mul_karatsuba() {
+        let mut rng = rand::thread_rng();
+        let mut scratch = Bigi::<128>::new();
+        for (bits1, bits2) in [(2048, 2048), (4096, 4096), (4096, 2048), (2048, 6000),
+                               (8000, 2100), (8192, 8192), (2047, 8192), (5000, 5000)] {
+            let x = Bigi::<128>::gen_random(&mut rng, bits1, false);
+            let y = Bigi::<128>::gen_random(&mut rng, bits2, false);
+            // mul_by_smaller is always schoolbook
+            let product = Bigi::<256>::from(&x).mul_by_smaller(&y);
+            let low = Bigi::<128>::from(&product);
+            let high = Bigi::<128>::from(&(product >> 8192));
+            assert_eq!(x * &y, low);
+            assert_eq!(&y * &x, low);
+            assert_eq!(x.multiply_overflowing(&y), (low, high));
+            let mut z = x;
+            z *= &y;
+            assert_eq!(z, low);
+            let mut z = x;
+            z.mul_assign_scratch(&y, &mut scratch);
+            assert_eq!((z, scratch), (low, high));
+        }
 
-impl<const N: usize> ops::Shl<usize> for Bigi<N> {
-    type Output = Bigi<N>;
+        // Carries through the middle term
+        let x = Bigi::<128>::from(1) << 8191;
+        let y = (Bigi::<128>::from(1) << 4096) - &Bigi::<128>::from(1);
+        let z = y * &y;
+        assert_eq!(z, Bigi::<128>::from(1) - &(Bigi::<128>::from(2) << 4096));
+        assert_eq!(y.multiply_overflowing(&y).1, Bigi::<128>::new());
+        assert_eq!((x - &Bigi::<128>::from(1)).multiply_overflowing(&x).0,
+                   x * &x - &x);
+    }
 
-    fn shl(self, rhs: usize) -> Bigi<N> {
-        let mut res = self.clone();
-        res <<= rhs;
-        res
+    #[test]
+    fn test_mul_toom3() {
+        let mut rng = rand::thread_rng();
+        for (bits1, bits2) in [(16384, 16384), (16384, 16383), (16383, 11000),
+                               (8192, 16384), (16384, 6000)] {
+            let x = Bigi::<256>::gen_random(&mut rng, bits1, false);
+            let y = Bigi::<256>::gen_random(&mut rng, bits2, false);
+            let product = Bigi::<512>::from(&x).mul_by_smaller(&y);
+            let low = Bigi::<256>::from(&product);
+            let high = Bigi::<256>::from(&(product >> 16384));
+            assert_eq!(x * &y, low);
+            assert_eq!(x.multiply_overflowing(&y), (low, high));
+        }
+
+        // Slices of all ones make the largest intermediate values, and
+        // the scratch is as short as allowed
+        for (len1, len2) in [(256, 256), (300, 257), (400, 270), (600, 599), (1000, 256),
+                             (32, 32), (63, 33), (64, 32), (65, 32), (67, 34), (97, 49),
+                             (100, 98), (257, 129), (1023, 1000), (1300, 867)] {
+            let a = vec![u64::MAX; len1];
+            let b = vec![u64::MAX; len2];
+            let mut res = vec![0; len1 + len2];
+            let mut scratch = vec![0; 2 * (len1 + len2)];
+            let mut expected = vec![0; len1 + len2];
+            mul_slices(&a, &b, &mut res, &mut scratch);
+            mul_schoolbook(&a, &b, &mut expected);
+            assert_eq!(res, expected);
+        }
+        // Random slices make negative values in the points
+        use rand::Rng;
+        for (len1, len2) in [(256, 256), (769, 513), (900, 700), (1537, 1100)] {
+            let a: Vec<u64> = (0..len1).map(|_| rng.gen()).collect();
+            let b: Vec<u64> = (0..len2).map(|_| rng.gen()).collect();
+            let mut res = vec![0; len1 + len2];
+            let mut scratch = vec![0; 2 * (len1 + len2)];
+            let mut expected = vec![0; len1 + len2];
+            mul_slices(&a, &b, &mut res, &mut scratch);
+            mul_schoolbook(&a, &b, &mut expected);
+            assert_eq!(res, expected);
+        }
     }
-}
 
+    #[test]
+    fn test_square() {
+        assert_eq!(bigi![4; 0].square(), bigi![4; 0]);
+        assert_eq!(bigi![4; u64::MAX].square(), bigi![4; 1, u64::MAX - 1]);
+        assert_eq!(bigi![2; u64::MAX, u64::MAX].square_overflowing(),
+                   (bigi![2; 1], bigi![2; u64::MAX - 1, u64::MAX]));
 
-impl<const N: usize> ops::ShrAssign<usize> for Bigi<N> {
-    fn shr_assign(&mut self, rhs: usize) {
-        let rhs_q = rhs >> 6;
-        let rhs_r = rhs & 63;
-        let mut extra: u64 = 0;
+        let mut rng = rand::thread_rng();
+        for bits in [1, 64, 100, 200, 256] {
+            let x = Bigi::<4>::gen_random(&mut rng, bits, false);
+            assert_eq!(x.square(), x * &x);
+            assert_eq!(x.square_overflowing(), x.multiply_overflowing(&x));
+        }
+        for bits in [2048, 3000, 4096, 8192] {
+            let x = Bigi::<128>::gen_random(&mut rng, bits, false);
+            assert_eq!(x.square(), x * &x);
+            assert_eq!(x.square_overflowing(), x.multiply_overflowing(&x));
+        }
 
-        for i in rhs_q..N {
-            if rhs_r > 0 {
-                extra = self.digits[i] << (64 - rhs_r);
+        // The scratch is as short as allowed
+        for len in [64, 65, 127, 300, 1000] {
+            let a = vec![u64::MAX; len];
+            let mut res = vec![0; len << 1];
+            let mut scratch = vec![0; len << 2];
+            let mut expected = vec![0; len << 1];
+            square_slices(&a, &mut res, &mut scratch);
+            mul_schoolbook(&a, &a, &mut expected);
+            assert_eq!(res, expected);
+        }
+    }
+
+    #[test]
+    fn test_mul_assign() {
+        let mut rng = rand::thread_rng();
+        let mut scratch = Bigi::<4>::gen_random(&mut rng, 256, false);
+        for (bits1, bits2) in [(0, 100), (100, 0), (64, 64), (200, 100), (256, 256)] {
+            let x = Bigi::<4>::gen_random(&mut rng, bits1, false);
+            let y = Bigi::<4>::gen_random(&mut rng, bits2, false);
+            let mut z = x;
+            z *= &y;
+            assert_eq!(z, x * &y);
+            let mut z = x;
+            z.mul_assign_scratch(&y, &mut scratch);
+            assert_eq!((z, scratch), x.multiply_overflowing(&y));
+        }
+        let max = Bigi::<2>::from_vec(&vec![u64::MAX, u64::MAX]);
+        let mut z = max;
+        let mut hi = Bigi::<2>::new();
+        z.mul_assign_scratch(&max, &mut hi);
+        assert_eq!((z, hi), (bigi![2; 1], bigi![2; u64::MAX - 1, u64::MAX]));
+    }
+
+    #[test]
+    fn test_smaller() {
+        let mut rng = rand::thread_rng();
+        for (bits1, bits2) in [(0, 100), (500, 0), (300, 128), (512, 128), (64, 64)] {
+            let x = Bigi::<8>::gen_random(&mut rng, bits1, false);
+            let y = Bigi::<2>::gen_random(&mut rng, bits2, false);
+            let y8 = Bigi::<8>::from(&y);
+            assert_eq!(x.add_smaller(&y), x + &y8);
+            assert_eq!(x.sub_smaller(&y), x - &y8);
+            assert_eq!(x.mul_by_smaller(&y), x * &y8);
+        }
+        // Wider operand is truncated
+        let x = bigi![2; 3, 1];
+        let y = bigi![4; 1, 2, 3, 4];
+        assert_eq!(x.add_smaller(&y), bigi![2; 4, 3]);
+        assert_eq!(x.mul_by_smaller(&y), bigi![2; 3, 7]);
+    }
+
+    #[test]
+    fn test_into() {
+        let mut rng = rand::thread_rng();
+        let mut dst = Bigi::<8>::gen_random(&mut rng, 512, false);
+        for _ in 0..10 {
+            let x = Bigi::<8>::gen_random(&mut rng, 400, false);
+            let y = Bigi::<8>::gen_random(&mut rng, 300, false);
+            Bigi::add_into(&mut dst, &x, &y);
+            assert_eq!(dst, x + &y);
+            assert_eq!(&x + &y, x + &y);
+            Bigi::sub_into(&mut dst, &x, &y);
+            assert_eq!(dst, x - &y);
+            assert_eq!(&y - &x, y - &x);
+            Bigi::mul_into(&mut dst, &x, &y);
+            assert_eq!(dst, x * &y);
+            assert_eq!(&x * &y, x * &y);
+        }
+        let max = Bigi::<2>::from_vec(&vec![u64::MAX, u64::MAX]);
+        let mut z = bigi![2; 5];
+        Bigi::add_into(&mut z, &max, &bigi![2; 1]);
+        assert_eq!(z, bigi![2; 0]);
+        Bigi::sub_into(&mut z, &bigi![2; 0], &bigi![2; 1]);
+        assert_eq!(z, max);
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn test_mul_parallel() {
+        let mut rng = rand::thread_rng();
+        for bits in [64, 2048, 4096, 8192] {
+            let x = Bigi::<128>::gen_random(&mut rng, bits, false);
+            let y = Bigi::<128>::gen_random(&mut rng, 8192 - bits, false);
+            assert_eq!(x.mul_parallel(&y), x * &y);
+            assert_eq!(y.mul_parallel(&x), x * &y);
+        }
+        // Truncated product
+        let x = Bigi::<128>::gen_random(&mut rng, 8000, false);
+        let y = Bigi::<128>::gen_random(&mut rng, 6000, false);
+        assert_eq!(x.mul_parallel(&y), x * &y);
+    }
+
+    #[test]
+    fn test_divide() {
+        let mut a = bigi![8; 43925362432376842, 6424051136,
+                             4402752814420623592, 77189580264184,
+                             478900707496709949, 66931731112,
+                             625124, 3892860704];
+        let b = bigi![8; 3567587328, 232, 0, 29];
+        let c = a.divide(&b);
+        assert_eq!(a, bigi![8; 10]);
+        assert_eq!(c, bigi![8; 12312344, 1, 1234098120, 21556, 134236576]);
+
+        // The highest digits of the divisor are all ones (secp256k1 prime)
+        let p = Bigi::<8>::from_hex(
+            "0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F"
+        );
+        let x = Bigi::<8>::from_hex(
+            "0x79BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798"
+        );
+        let mut y = x * &x;
+        let q = y.divide(&p);
+        assert!(y < p);
+        assert_eq!(q * &p + &y, x * &x);
+
+        let mut rng = rand::thread_rng();
+        for (bits1, bits2) in [(512, 256), (512, 64), (300, 299), (100, 200),
+                               (512, 511), (512, 1), (64, 64), (65, 64)] {
+            for top_ones in [false, true] {
+                let x = Bigi::<8>::gen_random(&mut rng, bits1, false);
+                let mut d = Bigi::<8>::gen_random(&mut rng, bits2, true);
+                if top_ones {
+                    let order = d.get_order();
+                    d.digits[order - 1] = u64::MAX;
+                    d.digits[order.saturating_sub(2)] = u64::MAX;
+                }
+                let mut r = x;
+                let q = r.divide(&d);
+                assert!(r < d);
+                assert_eq!(q * &d + &r, x);
             }
-            self.digits[i - rhs_q] = self.digits[i] >> rhs_r;
-            if i > rhs_q {
-                self.digits[i - rhs_q - 1] += extra;
+        }
+    }
+
+    #[test]
+    fn test_divide_recursive() {
+        let mut rng = rand::thread_rng();
+        for (bits1, bits2) in [(16384, 8192), (16384, 4096), (16000, 5000), (12000, 4097),
+                               (16384, 12000), (16383, 8255), (16384, 10000)] {
+            for top_ones in [false, true] {
+                let x = Bigi::<256>::gen_random(&mut rng, bits1, false);
+                let mut d = Bigi::<256>::gen_random(&mut rng, bits2, true);
+                if top_ones {
+                    let order = d.get_order();
+                    d.digits[(order - 8)..order].fill(u64::MAX);
+                }
+                let mut r = x;
+                let q = r.divide(&d);
+                assert!(r < d);
+                assert_eq!(q * &d + &r, x);
+            }
+        }
+
+        // Same quotients and remainders as Algorithm D
+        for (len1, len2) in [(200, 64), (200, 65), (256, 100), (300, 130), (513, 256)] {
+            for top_ones in [false, true] {
+                let mut un: Vec<u64> = (0..len1).map(|_| rand::Rng::gen(&mut rng)).collect();
+                let mut vn: Vec<u64> = (0..len2).map(|_| rand::Rng::gen(&mut rng)).collect();
+                vn[len2 - 1] |= 1 << 63;
+                if top_ones {
+                    vn[(len2 - 4)..].fill(u64::MAX);
+                    un[(len1 - 8)..].fill(u64::MAX);
+                }
+                un[len1 - 1] = vn[len2 - 1] - 1;
+                let v = reciprocal(vn[len2 - 1]);
+                let mut q = vec![0; len1 - len2];
+                let mut expected_un = un.clone();
+                let mut expected_q = q.clone();
+                let mut scratch = vec![0; 2 * len1 + 4 * len2];
+                divide_normalized(&mut un, &vn, v, &mut q, &mut scratch);
+                divide_schoolbook(&mut expected_un, &vn, v, &mut expected_q);
+                assert_eq!(q, expected_q);
+                assert_eq!(un, expected_un);
+            }
+        }
+    }
+
+    #[test]
+    fn test_div2by1() {
+        for d in [1 << 63, (1 << 63) + 1, u64::MAX - 1, u64::MAX, 0xdeadbeef << 32] {
+            let v = reciprocal(d);
+            for u1 in [0, 1, d >> 1, d - 1] {
+                for u0 in [0, 1, d - 1, u64::MAX] {
+                    let x = ((u1 as u128) << 64) | (u0 as u128);
+                    assert_eq!(div2by1(u1, u0, d, v),
+                               ((x / d as u128) as u64, (x % d as u128) as u64));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_div_rem() {
+        let a = bigi![8; 12312344, 1, 1234098120, 21556, 134236576];
+        let b = bigi![8; 3567587328, 232, 0, 29];
+        let (q, r) = a.div_rem(&b);
+        assert_eq!(q * &b + &r, a);
+        assert!(r < b);
+        assert_eq!(a, bigi![8; 12312344, 1, 1234098120, 21556, 134236576]);
+        assert_eq!(b.div_rem(&a), (bigi![8; 0], b));
+        assert_eq!(a.div_rem(&a), (bigi![8; 1], bigi![8; 0]));
+
+        let mut rng = rand::thread_rng();
+        let x = Bigi::<64>::gen_random(&mut rng, 4096, false);
+        let y = Bigi::<64>::gen_random(&mut rng, 2500, false);
+        let (mut q, mut r) = (Bigi::<64>::MAX, Bigi::<64>::MAX);
+        Bigi::div_rem_into(&mut q, &mut r, &x, &y);
+        assert_eq!((q, r), (&x / &y, &x % &y));
+        assert_eq!(q * &y + &r, x);
+    }
+
+    #[test]
+    fn test_div_u64() {
+        let mut x = bigi![4; 14];
+        assert_eq!(x.div_u64(4), 2);
+        assert_eq!(x, bigi![4; 3]);
+        let mut x = bigi![4; 0];
+        assert_eq!(x.div_u64(7), 0);
+        assert_eq!(x, bigi![4; 0]);
+        let mut x = bigi![2; u64::MAX, u64::MAX];
+        assert_eq!(x.div_u64(u64::MAX), 0);
+        assert_eq!(x, bigi![2; 1, 1]);
+
+        let mut rng = rand::thread_rng();
+        let ds = [1, 2, 3, 10, 1 << 63, u64::MAX, rand::Rng::gen(&mut rng)];
+        for bits in [0, 1, 64, 65, 100, 200, 255, 256] {
+            let x = Bigi::<4>::gen_random(&mut rng, bits, false);
+            for d in ds {
+                let mut q = x;
+                let r = q.div_u64(d);
+                let mut r2 = x;
+                assert_eq!(q, r2.divide(&Bigi::<4>::from(d)));
+                assert_eq!(Bigi::<4>::from(r), r2);
+                assert_eq!(x.rem_u64(d), r);
             }
         }
+    }
 
-        for i in 0..rhs_q {
-            self.digits[N - i - 1] = 0;
-        }
+    #[test]
+    #[should_panic(expected = "Division by zero")]
+    fn test_div_u64_by_zero() {
+        bigi![4; 1, 2].div_u64(0);
     }
-}
 
+    #[test]
+    #[should_panic(expected = "Division by zero")]
+    fn test_divide_by_zero() {
+        bigi![4; 5].divide(&bigi![4; 0]);
+    }
 
-impl<const N: usize> ops::Shr<usize> for Bigi<N> {
-    type Output = Bigi<N>;
+    #[test]
+    #[should_panic(expected = "Division by zero")]
+    fn test_rem_by_zero() {
+        let _ = bigi![4; 0] % &bigi![4; 0];
+    }
 
-    fn shr(self, rhs: usize) -> Bigi<N> {
-        let mut res = self.clone();
-        res >>= rhs;
-        res
+    #[test]
+    #[should_panic(expected = "Division by zero")]
+    fn test_divide_overflowing_by_zero() {
+        bigi![4; 5].divide_overflowing(&bigi![4; 0], &bigi![4; 1]);
     }
-}
 
+    #[test]
+    fn test_checked_div() {
+        let a = bigi![4; 12312344, 1, 1234098120];
+        let b = bigi![4; 3567587328, 232];
+        assert_eq!(a.checked_div(&b), Some(a / &b));
+        assert_eq!(a.checked_rem(&b), Some(a % &b));
+        assert_eq!(b.checked_div(&a), Some(bigi![4; 0]));
+        assert_eq!(b.checked_rem(&a), Some(b));
+        assert_eq!(a.checked_div(&bigi![4; 0]), None);
+        assert_eq!(a.checked_rem(&bigi![4; 0]), None);
+        assert_eq!(bigi![4; 0].checked_div(&bigi![4; 0]), None);
+    }
 
-impl<const N: usize> cmp::PartialEq for Bigi<N> {
-    fn eq(&self, other: &Self) -> bool {
-        self.digits == other.digits
+    #[test]
+    fn test_powmod_verified() {
+        let mut rng = rand::thread_rng();
+        let (p, q) = crate::prime::gen_prime_pair(&mut rng, 128, &bigi![8; 65537], false);
+        let n = p * &q;
+        let phi = (p - &bigi![8; 1]) * &(q - &bigi![8; 1]);
+        let e = bigi![8; 65537];
+        let d = crate::prime::inv_mod(&e, &phi);
+        let x = Bigi::<8>::gen_random(&mut rng, 200, false);
+        let y = x.powmod_verified(&d, &e, &n).unwrap();
+        assert_eq!(y.powmod(&e, &n), x);
+
+        // A fault in the exponent
+        let mut d_faulty = d;
+        d_faulty.digits[1] ^= 1 << 7;
+        assert_eq!(x.powmod_verified(&d_faulty, &e, &n), Err("Fault detected"));
     }
-}
 
+    #[test]
+    #[should_panic(expected = "Modulus must be odd")]
+    fn test_powmod_ct_even() {
+        bigi![4; 5].powmod_ct(&bigi![4; 3], &bigi![4; 10]);
+    }
 
-impl<const N: usize> cmp::PartialOrd for Bigi<N> {
-    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
-        for i in (0..N).rev() {
-            if self.digits[i] > other.digits[i] {
-                return Some(cmp::Ordering::Greater);
+    #[test]
+    fn test_mont_mul_ct() {
+        // The operands and the modulus of every order down to zero digits
+        // take the same loops, only the results are checked
+        let mut rng = rand::thread_rng();
+        for m_bits in [1, 63, 64, 130, 255, 256] {
+            let mut m = Bigi::<4>::gen_random(&mut rng, m_bits, true);
+            m.digits[0] |= 1;
+            let mut mi: u64 = 1;
+            for _ in 0..6 {
+                mi = mi.wrapping_mul(2u64.wrapping_sub(m.digits[0].wrapping_mul(mi)));
             }
-            if self.digits[i] < other.digits[i] {
-                return Some(cmp::Ordering::Less);
+            let mi = mi.wrapping_neg();
+            assert_eq!(m.digits[0].wrapping_mul(mi), u64::MAX);
+            // 2^(-256) % m
+            let ri = crate::prime::inv_mod(&((Bigi::<4>::new() - &m) % &m), &m);
+            for a_bits in [0, 1, 64, 150, 256] {
+                let a = Bigi::<4>::gen_random(&mut rng, a_bits, false);
+                for b_bits in [0, 1, 64, m_bits] {
+                    let b = Bigi::<4>::gen_random(&mut rng, b_bits, false) % &m;
+                    let expected = crate::prime::mul_mod(
+                        &crate::prime::mul_mod(&(a % &m), &b, &m), &ri, &m
+                    );
+                    assert_eq!(mont_mul_ct(&a, &b, &m, mi), expected);
+                }
             }
         }
-        Some(cmp::Ordering::Equal)
     }
-}
 
+    #[test]
+    fn test_powmod_ct() {
+        assert_eq!(bigi![4; 5].powmod_ct(&bigi![4; 0], &bigi![4; 7]), bigi![4; 1]);
+        assert_eq!(bigi![4; 5].powmod_ct(&bigi![4; 3], &bigi![4; 1]), bigi![4; 0]);
+        assert_eq!(bigi![4; 12].powmod_ct(&bigi![4; 3], &bigi![4; 7]), bigi![4; 6]);
 
-#[cfg(test)]
-mod tests {
-    use crate::bigi;
-    use super::*;
-    use test::Bencher;
+        assert_eq!(bigi![4; 5].powmod_ct(&bigi![4; 3], &Bigi::<4>::MAX), bigi![4; 125]);
 
-    #[test]
-    fn test_add() {
-        assert_eq!(bigi![8; 2] + &bigi![8; 3], bigi![8; 5]);
-        assert_eq!(
-            bigi![8; 3567587328, 232, 0, 29] +
-            &bigi![8; 12312344, 1, 1234098120, 21556, 134236576],
-            bigi![8; 3579899672, 233, 1234098120, 21585, 134236576]
-        );
+        let mut rng = rand::thread_rng();
+        for bits in [1, 64, 200, 256] {
+            let mut m = Bigi::<4>::gen_random(&mut rng, bits, true);
+            m.digits[0] |= 1;
+            let x = Bigi::<4>::gen_random(&mut rng, 256, false);
+            for e in [Bigi::<4>::gen_random(&mut rng, 256, false), Bigi::<4>::MAX, bigi![4; 1]] {
+                assert_eq!(x.powmod_ct(&e, &m), x.powmod(&e, &m));
+            }
+        }
+
+        let mut a = bigi![4; 1, 2];
+        let mut b = bigi![4; 3];
+        cswap(&mut a, &mut b, 0);
+        assert_eq!((a, b), (bigi![4; 1, 2], bigi![4; 3]));
+        cswap(&mut a, &mut b, 1);
+        assert_eq!((a, b), (bigi![4; 3], bigi![4; 1, 2]));
     }
 
     #[test]
-    fn test_sub() {
-        assert_eq!(bigi![8; 5] - &bigi![8; 3], bigi![8; 2]);
-        assert_eq!(
-            bigi![8; 3579899672, 233, 1234098120, 21585, 134236576] -
-            &bigi![8; 12312344, 1, 1234098120, 21556, 134236576],
-            bigi![8; 3567587328, 232, 0, 29]
-        );
+    fn test_powmod_u64() {
+        let m = bigi![4; 1000];
+        assert_eq!(bigi![4; 3].powmod_u64(0, &m), bigi![4; 1]);
+        assert_eq!(bigi![4; 3].powmod_u64(1, &m), bigi![4; 3]);
+        assert_eq!(bigi![4; 1003].powmod_u64(2, &m), bigi![4; 9]);
+        assert_eq!(bigi![4; 3].powmod_u64(65537, &m), bigi![4; 363]);
+        assert_eq!(bigi![4; 3].powmod_u64(0, &bigi![4; 1]), bigi![4; 0]);
+        assert_eq!(bigi![4; 3].powmod_u64(5, &bigi![4; 1]), bigi![4; 0]);
+
+        let mut rng = rand::thread_rng();
+        let m = Bigi::<4>::gen_random(&mut rng, 256, true);
+        let x = Bigi::<4>::gen_random(&mut rng, 256, false) % &m;
+        for e in [2, 3, 17, 65537, u64::MAX] {
+            assert_eq!(x.powmod_u64(e, &m), x.powmod(&bigi![4; e], &m));
+        }
     }
 
     #[test]
-    fn test_mul() {
-        assert_eq!(bigi![8; 5] * &bigi![8; 2], bigi![8; 10]);
-        assert_eq!(
-            bigi![8; 12312344, 1, 1234098120, 21556, 134236576] *
-            &bigi![8; 3567587328, 232, 0, 29],
-            bigi![8; 43925362432376832, 6424051136, 4402752814420623592,
-                     77189580264184, 478900707496709949, 66931731112,
-                     625124, 3892860704]
-        );
+    fn test_exact_div() {
+        assert_eq!(bigi![4; 0].exact_div(&bigi![4; 7]), bigi![4; 0]);
+        assert_eq!(bigi![4; 42].exact_div(&bigi![4; 42]), bigi![4; 1]);
+        assert_eq!(bigi![4; 96].exact_div(&bigi![4; 12]), bigi![4; 8]);
+        assert_eq!(bigi![4; 0, 0, 1].exact_div(&bigi![4; 0, 1]), bigi![4; 0, 1]);
+
+        let mut rng = rand::thread_rng();
+        for (bits1, bits2) in [(64, 64), (100, 30), (128, 120), (200, 50), (64, 180)] {
+            let x = Bigi::<4>::gen_random(&mut rng, bits1, false);
+            let y = Bigi::<4>::gen_random(&mut rng, bits2, true) << 3;
+            assert_eq!((x * &y).exact_div(&y), x);
+            assert_eq!((x * &y).exact_div(&x), y);
+            assert_eq!((x * &y).div_exact(&y), x);
+        }
     }
 
     #[test]
-    fn test_divide() {
-        let mut a = bigi![8; 43925362432376842, 6424051136,
-                             4402752814420623592, 77189580264184,
-                             478900707496709949, 66931731112,
-                             625124, 3892860704];
-        let b = bigi![8; 3567587328, 232, 0, 29];
-        let c = a.divide(&b);
-        assert_eq!(a, bigi![8; 10]);
-        assert_eq!(c, bigi![8; 12312344, 1, 1234098120, 21556, 134236576]);
+    fn test_div_modes() {
+        let d = bigi![4; 4];
+        let cases = [
+            // x, floor, ceil, round, rem floor, rem ceil, rem round
+            (0, 0, 0, 0, 0, 0, 0),
+            (4, 1, 1, 1, 0, 0, 0),
+            (5, 1, 2, 1, 1, 3, 1),
+            (6, 1, 2, 2, 2, 2, 2),
+            (7, 1, 2, 2, 3, 1, 1),
+        ];
+        for (x, fl, cl, rn, rfl, rcl, rrn) in cases {
+            let x = bigi![4; x];
+            assert_eq!(x.div_floor(&d), bigi![4; fl]);
+            assert_eq!(x.div_ceil(&d), bigi![4; cl]);
+            assert_eq!(x.div_round(&d), bigi![4; rn]);
+            assert_eq!(x.rem_floor(&d), bigi![4; rfl]);
+            assert_eq!(x.rem_ceil(&d), bigi![4; rcl]);
+            assert_eq!(x.rem_round(&d), bigi![4; rrn]);
+        }
+
+        let max = bigi![2; u64::MAX, u64::MAX];
+        assert_eq!(max.div_round(&max), bigi![2; 1]);
+        assert_eq!((max - &bigi![2; 1]).div_round(&max), bigi![2; 1]);
+        assert_eq!(max.div_ceil(&bigi![2; 0, 1]), bigi![2; 0, 1]);
     }
 
     #[test]
@@ -700,6 +3223,49 @@ mod tests {
         assert_eq!(overflow, bigi![2; 14967786748685025341, 19131195116908]);
     }
 
+    #[test]
+    fn test_widening_mul() {
+        assert_eq!(bigi![2; 5].widening_mul(&bigi![2; 0]), (bigi![2; 0], bigi![2; 0]));
+        assert_eq!(
+            bigi![2; u64::MAX, u64::MAX].widening_mul(&bigi![2; u64::MAX, u64::MAX]),
+            (bigi![2; 1], bigi![2; u64::MAX - 1, u64::MAX])
+        );
+
+        let mut rng = rand::thread_rng();
+        for (bits1, bits2) in [(0, 100), (64, 64), (200, 100), (255, 256), (256, 256)] {
+            let x = Bigi::<4>::gen_random(&mut rng, bits1, false);
+            let y = Bigi::<4>::gen_random(&mut rng, bits2, false);
+            let (low, high) = x.widening_mul(&y);
+            assert_eq!(
+                Bigi::<8>::from(&low) + &(Bigi::<8>::from(&high) << 256),
+                Bigi::<8>::from(&x) * &Bigi::<8>::from(&y)
+            );
+            assert_eq!((low, high), y.widening_mul(&x));
+        }
+
+        let x = Bigi::<64>::gen_random(&mut rng, 4096, false);
+        let y = Bigi::<64>::gen_random(&mut rng, 4000, false);
+        let (low, high) = x.widening_mul(&y);
+        assert_eq!(low, x * &y);
+        assert_eq!(
+            Bigi::<128>::from(&low) + &(Bigi::<128>::from(&high) << 4096),
+            Bigi::<128>::from(&x) * &Bigi::<128>::from(&y)
+        );
+    }
+
+    #[test]
+    fn test_mul_high() {
+        assert_eq!(bigi![4; 5, 7].mul_high(&bigi![4; 3, 0, 1]), bigi![4; 0]);
+        assert_eq!(bigi![4; 0, 0, 1].mul_high(&bigi![4; 0, 0, 1]), bigi![4; 1]);
+
+        let mut rng = rand::thread_rng();
+        for (bits1, bits2) in [(0, 256), (100, 150), (128, 129), (200, 100), (256, 256)] {
+            let x = Bigi::<4>::gen_random(&mut rng, bits1, false);
+            let y = Bigi::<4>::gen_random(&mut rng, bits2, false);
+            assert_eq!(x.mul_high(&y), x.widening_mul(&y).1);
+        }
+    }
+
     #[test]
     fn test_divide_overflowing() {
         let mut c = bigi![2; 18314275272483195888, 4916496906824170722];
@@ -710,6 +3276,60 @@ mod tests {
 
         assert_eq!(a, bigi![2; 4402752814420623592, 77189580264184]);
         assert_eq!(c, bigi![2; 80]);
+
+        let mut rng = rand::thread_rng();
+        for bits in [64, 65, 128, 200, 255, 256] {
+            let mut d = Bigi::<4>::gen_random(&mut rng, bits, true);
+            d.digits[d.get_order() - 1] = u64::MAX;
+            let x = Bigi::<4>::gen_random(&mut rng, 256, false);
+            let overflow = Bigi::<4>::gen_random(&mut rng, bits - 1, false);
+            let mut r = x;
+            let q = r.divide_overflowing(&d, &overflow);
+            let mut wide = Bigi::<8>::from(&x) + &(Bigi::<8>::from(&overflow) << 256);
+            assert_eq!(Bigi::<8>::from(&q), wide.divide(&Bigi::<8>::from(&d)));
+            assert_eq!(Bigi::<8>::from(&r), wide);
+        }
+    }
+
+    #[test]
+    fn test_cmp_u64() {
+        assert!(bigi![4; 25] == 25);
+        assert!(bigi![4; 25] != 26);
+        assert!(bigi![4; 25, 1] != 25);
+        assert!(bigi![4; 25] < 26);
+        assert!(bigi![4; 25] > 24);
+        assert!(bigi![4; 0, 0, 0, 1] > u64::MAX);
+        assert!(25 == bigi![4; 25]);
+        assert!(24 < bigi![4; 25]);
+        assert!(u64::MAX < bigi![4; 0, 1]);
+        assert!(26 >= bigi![4; 25]);
+    }
+
+    #[test]
+    fn test_bitwise() {
+        let x = bigi![4; 0b1100, u64::MAX, 0, 7];
+        let y = bigi![4; 0b1010, 1 << 63, 5];
+        assert_eq!(x & &y, bigi![4; 0b1000, 1 << 63]);
+        assert_eq!(x | &y, bigi![4; 0b1110, u64::MAX, 5, 7]);
+        assert_eq!(x ^ &y, bigi![4; 0b0110, u64::MAX >> 1, 5, 7]);
+        assert_eq!(!bigi![4; 0b1100, u64::MAX],
+                   bigi![4; !0b1100, 0, u64::MAX, u64::MAX]);
+
+        let mut z = x;
+        z &= &y;
+        assert_eq!(z, x & &y);
+        z |= &x;
+        assert_eq!(z, x);
+        z ^= &x;
+        assert_eq!(z, bigi![4; 0]);
+
+        // Masking the lowest bits is the same as the remainder
+        let mut rng = rand::thread_rng();
+        let x = Bigi::<4>::gen_random(&mut rng, 256, false);
+        let mask = (bigi![4; 1] << 100) - 1;
+        assert_eq!(x & &mask, x.mod_2k(100));
+        assert_eq!(!x, Bigi::<4>::MAX - &x);
+        assert_eq!((x ^ &mask) ^ &mask, x);
     }
 
     #[bench]
@@ -718,6 +3338,116 @@ mod tests {
         bencher.iter(|| x.is_zero());
     }
 
+    #[test]
+    fn test_bit_counts() {
+        assert_eq!(bigi![4; 0].count_ones(), 0);
+        assert_eq!(bigi![4; 0].leading_zeros(), 256);
+        assert_eq!(bigi![4; 0].trailing_zeros(), 256);
+        assert_eq!(Bigi::<4>::MAX.count_ones(), 256);
+        assert_eq!(Bigi::<4>::MAX.leading_zeros(), 0);
+        assert_eq!(Bigi::<4>::MAX.trailing_zeros(), 0);
+        assert_eq!(bigi![4; 0, 0, 0, 1 << 63].trailing_zeros(), 255);
+        assert_eq!(bigi![4; 1].leading_zeros(), 255);
+
+        let mut rng = rand::thread_rng();
+        for bits in [1, 63, 64, 65, 200, 256] {
+            let x = Bigi::<4>::gen_random(&mut rng, bits, true);
+            assert_eq!(x.leading_zeros(), 256 - bits);
+            assert_eq!(x.count_ones(), (0..256).filter(|i| x.get_bit(*i)).count());
+            let s = x.trailing_zeros();
+            assert_eq!((x >> s) << s, x);
+            assert!(x.get_bit(s));
+        }
+    }
+
+    #[test]
+    fn test_bit_mutators() {
+        let mut x = bigi![4; 0];
+        x.set_bit(255);
+        x.set_bit(0);
+        x.set_bit(0);
+        assert_eq!(x, bigi![4; 1, 0, 0, 1 << 63]);
+        x.clear_bit(255);
+        x.clear_bit(100);
+        assert_eq!(x, bigi![4; 1]);
+        x.toggle_bit(64);
+        x.toggle_bit(0);
+        assert_eq!(x, bigi![4; 0, 1]);
+        x.assign_bit(64, false);
+        x.assign_bit(130, true);
+        assert_eq!(x, bigi![4; 0, 0, 4]);
+        for bit in [0, 63, 64, 130, 255] {
+            let mut y = x;
+            y.toggle_bit(bit);
+            assert_eq!(y.get_bit(bit), !x.get_bit(bit));
+            y.toggle_bit(bit);
+            assert_eq!(y, x);
+        }
+    }
+
+    #[test]
+    fn test_bytes() {
+        let mut x = bigi![4; 0];
+        assert_eq!(x.byte_length(), 0);
+        x.set_byte(31, 0x80);
+        assert_eq!(x, bigi![4; 0, 0, 0, 0x80 << 56]);
+        assert_eq!(x.byte_length(), 32);
+        x.set_byte(31, 0);
+        x.set_byte(8, 1);
+        assert_eq!(x.byte_length(), 9);
+        x.set_byte(8, 0xab);
+        assert_eq!(x, bigi![4; 0, 0xab]);
+
+        let mut rng = rand::thread_rng();
+        let x = Bigi::<4>::gen_random(&mut rng, 200, false);
+        let bytes = x.to_bytes_le();
+        assert_eq!(x.byte_length(), x.to_bytes_le_trimmed().len());
+        let mut y = bigi![4; 0];
+        for (i, byte) in bytes.iter().enumerate() {
+            assert_eq!(x.get_byte(i), *byte);
+            y.set_byte(i, *byte);
+        }
+        assert_eq!(y, x);
+    }
+
+    #[test]
+    fn test_shift_overflow() {
+        let x = bigi![4; 1, 2, 3, 4];
+        assert_eq!(x << 256, bigi![4; 0]);
+        assert_eq!(x << 1000, bigi![4; 0]);
+        assert_eq!(x >> 256, bigi![4; 0]);
+        assert_eq!(x >> 1000, bigi![4; 0]);
+        assert_eq!(x << 255, bigi![4; 0, 0, 0, 1 << 63]);
+        assert_eq!(x >> 255, bigi![4; 0]);
+
+        assert_eq!(x.checked_shl(0), Some(x));
+        assert_eq!(x.checked_shl(61), Some(x << 61));
+        assert_eq!(x.checked_shl(62), None);
+        assert_eq!(x.checked_shl(256), None);
+        assert_eq!(bigi![4; 1].checked_shl(255), Some(bigi![4; 0, 0, 0, 1 << 63]));
+        assert_eq!(bigi![4; 0].checked_shl(usize::MAX), Some(bigi![4; 0]));
+        assert_eq!(x.checked_shr(255), Some(bigi![4; 0]));
+        assert_eq!(x.checked_shr(256), None);
+    }
+
+    #[test]
+    fn test_rotate() {
+        let x = bigi![4; 0x0123456789abcdef, 1, 0, 0xf000000000000000];
+        assert_eq!(x.rotate_left(0), x);
+        assert_eq!(x.rotate_left(256), x);
+        assert_eq!(x.rotate_left(4), bigi![4; 0x123456789abcdeff, 0x10, 0, 0]);
+        assert_eq!(x.rotate_right(4), bigi![4; 0x10123456789abcde, 0, 0, 0xff00000000000000]);
+        assert_eq!(x.rotate_right(260), x.rotate_right(4));
+
+        let mut rng = rand::thread_rng();
+        let x = Bigi::<4>::gen_random(&mut rng, 256, false);
+        for k in [1, 63, 64, 65, 100, 255] {
+            assert_eq!(x.rotate_left(k).rotate_right(k), x);
+            assert_eq!(x.rotate_left(k), x.rotate_right(256 - k));
+            assert_eq!(x.rotate_left(k).count_ones(), x.count_ones());
+        }
+    }
+
     #[bench]
     fn bench_get_bit(bencher: &mut Bencher) {
         let x = bigi![8; 3411848022234306463, 14482971280477013830,
@@ -763,6 +3493,138 @@ mod tests {
         bencher.iter(|| x * &y);
     }
 
+    #[bench]
+    fn bench_add_u64_256(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let x = Bigi::<8>::gen_random(&mut rng, 256, false);
+        bencher.iter(|| x + 0x123456789abcdef);
+    }
+
+    #[bench]
+    fn bench_mul_u64_256(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let x = Bigi::<8>::gen_random(&mut rng, 256, false);
+        bencher.iter(|| x * 0x123456789abcdef);
+    }
+
+    #[bench]
+    fn bench_div_u64_256(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let x = Bigi::<8>::gen_random(&mut rng, 256, false);
+        bencher.iter(|| {
+            let mut q = x;
+            let r = q.div_u64(0x123456789abcdef);
+            (q, r)
+        });
+    }
+
+    #[bench]
+    fn bench_mul_high_256(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let x = Bigi::<4>::gen_random(&mut rng, 256, false);
+        let y = Bigi::<4>::gen_random(&mut rng, 256, false);
+        bencher.iter(|| x.mul_high(&y));
+    }
+
+    #[bench]
+    fn bench_isqrt_256(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let x = Bigi::<4>::gen_random(&mut rng, 256, false);
+        bencher.iter(|| x.isqrt());
+    }
+
+    #[bench]
+    fn bench_square_256(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let x = Bigi::<8>::gen_random(&mut rng, 256, false);
+        bencher.iter(|| x.square());
+    }
+
+    #[bench]
+    fn bench_square_overflowing_2048(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let x = Bigi::<32>::gen_random(&mut rng, 2048, false);
+        bencher.iter(|| x.square_overflowing());
+    }
+
+    #[bench]
+    fn bench_multiply_overflowing_2048(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let x = Bigi::<32>::gen_random(&mut rng, 2048, false);
+        bencher.iter(|| x.multiply_overflowing(&x));
+    }
+
+    #[bench]
+    fn bench_mul_assign_scratch_256(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let x = Bigi::<4>::gen_random(&mut rng, 256, false);
+        let y = Bigi::<4>::gen_random(&mut rng, 256, false);
+        let mut scratch = Bigi::<4>::new();
+        bencher.iter(|| {
+            let mut z = x;
+            z.mul_assign_scratch(&y, &mut scratch);
+            z
+        });
+    }
+
+    #[bench]
+    fn bench_mul_by_smaller_2048_128(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let x = Bigi::<64>::gen_random(&mut rng, 2048, false);
+        let y = Bigi::<2>::gen_random(&mut rng, 128, false);
+        bencher.iter(|| x.mul_by_smaller(&y));
+    }
+
+    #[bench]
+    fn bench_mul_into_4096(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let x = Bigi::<64>::gen_random(&mut rng, 2048, false);
+        let y = Bigi::<64>::gen_random(&mut rng, 2048, false);
+        let mut z = Bigi::<64>::new();
+        bencher.iter(|| Bigi::mul_into(&mut z, &x, &y));
+    }
+
+    #[bench]
+    fn bench_mul_ref_4096(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let x = Bigi::<64>::gen_random(&mut rng, 4096, false);
+        let y = Bigi::<64>::gen_random(&mut rng, 4096, false);
+        bencher.iter(|| &x * &y);
+    }
+
+    #[bench]
+    fn bench_div_ref_4096(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let x = Bigi::<64>::gen_random(&mut rng, 4096, false);
+        let y = Bigi::<64>::gen_random(&mut rng, 2048, false);
+        bencher.iter(|| &x / &y);
+    }
+
+    #[bench]
+    fn bench_mul_8192(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let x = Bigi::<128>::gen_random(&mut rng, 4096, false);
+        let y = Bigi::<128>::gen_random(&mut rng, 4096, false);
+        bencher.iter(|| x * &y);
+    }
+
+    #[bench]
+    fn bench_mul_32768(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let x = Bigi::<512>::gen_random(&mut rng, 16384, false);
+        let y = Bigi::<512>::gen_random(&mut rng, 16384, false);
+        bencher.iter(|| x * &y);
+    }
+
+    #[bench]
+    #[cfg(feature = "rayon")]
+    fn bench_mul_parallel_8192(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let x = Bigi::<256>::gen_random(&mut rng, 8192, false);
+        let y = Bigi::<256>::gen_random(&mut rng, 8192, false);
+        bencher.iter(|| x.mul_parallel(&y));
+    }
+
     #[bench]
     fn bench_divide_256_256(bencher: &mut Bencher) {
         let mut rng = rand::thread_rng();
@@ -779,6 +3641,14 @@ mod tests {
         bencher.iter(|| x.divide(&y));
     }
 
+    #[bench]
+    fn bench_divide_16384_8192(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let x = Bigi::<256>::gen_random(&mut rng, 16384, false);
+        let y = Bigi::<256>::gen_random(&mut rng, 8192, false);
+        bencher.iter(|| x.div_rem(&y));
+    }
+
     #[bench]
     fn bench_multiply_overflowing_256(bencher: &mut Bencher) {
         let mut rng = rand::thread_rng();
@@ -812,6 +3682,14 @@ mod tests {
         bencher.iter(|| x / &y);
     }
 
+    #[bench]
+    fn bench_exact_div_256_128(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let y = Bigi::<8>::gen_random(&mut rng, 128, false);
+        let x = Bigi::<8>::gen_random(&mut rng, 128, false) * &y;
+        bencher.iter(|| x.exact_div(&y));
+    }
+
     #[bench]
     fn bench_mod_256_256(bencher: &mut Bencher) {
         let mut rng = rand::thread_rng();
@@ -828,6 +3706,24 @@ mod tests {
         bencher.iter(|| x % &y);
     }
 
+    #[bench]
+    fn bench_powmod_u64_256_65537(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let m = Bigi::<4>::gen_random(&mut rng, 256, false);
+        let x = Bigi::<4>::gen_random(&mut rng, 256, false) % &m;
+        bencher.iter(|| x.powmod_u64(65537, &m));
+    }
+
+    #[bench]
+    fn bench_powmod_ct_256(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let mut m = Bigi::<4>::gen_random(&mut rng, 256, true);
+        m.digits[0] |= 1;
+        let x = Bigi::<4>::gen_random(&mut rng, 256, false) % &m;
+        let p = Bigi::<4>::gen_random(&mut rng, 256, false);
+        bencher.iter(|| x.powmod_ct(&p, &m));
+    }
+
     #[bench]
     fn bench_powmod_256(bencher: &mut Bencher) {
         let mut rng = rand::thread_rng();
@@ -858,6 +3754,14 @@ mod tests {
         bencher.iter(|| x >> 143);
     }
 
+    #[bench]
+    fn bench_xor_256(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let x = Bigi::<8>::gen_random(&mut rng, 256, false);
+        let y = Bigi::<8>::gen_random(&mut rng, 256, false);
+        bencher.iter(|| x ^ &y);
+    }
+
     #[bench]
     fn bench_eq_256(bencher: &mut Bencher) {
         let mut rng = rand::thread_rng();
@@ -873,4 +3777,11 @@ mod tests {
         let y = Bigi::<8>::gen_random(&mut rng, 256, false);
         bencher.iter(|| x < y);
     }
+
+    #[bench]
+    fn bench_cmp_u64_256(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let x = Bigi::<8>::gen_random(&mut rng, 256, false);
+        bencher.iter(|| x < 1000);
+    }
 }
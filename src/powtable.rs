@@ -0,0 +1,148 @@
+//! This module implements modular exponentiation of a fixed base by
+//! the fixed-window (k-ary) method. The powers `base^j` for `j < 2^w` are
+//! precomputed once, then every power takes a squaring per bit of
+//! the exponent and a multiplication per window of `w` bits, so repeated
+//! exponentiations of the same base (Diffie-Hellman, Fermat tests with
+//! a fixed base) only pay the table setup once. The products are reduced
+//! with Barrett reduction.
+//!
+//! ```rust
+//! use bigi::{Bigi, PowTable};
+//!
+//! let table = PowTable::new(&Bigi::<4>::from(5), &Bigi::<4>::from(23));
+//!
+//! assert_eq!(table.pow(&Bigi::<4>::from(6)), Bigi::<4>::from(8));  // 8 = 5**6 % 23
+//! assert_eq!(table.pow(&Bigi::<4>::from(15)), Bigi::<4>::from(19));  // 19 = 5**15 % 23
+//! ```
+
+use crate::base::Bigi;
+use crate::barrett::BarrettAlg;
+
+
+/// Precomputed powers of a base modulo `m`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PowTable<const N: usize> {
+    alg: BarrettAlg<N>,
+    window: usize,
+    powers: Vec<Bigi<N>>,
+}
+
+
+impl<const N: usize> PowTable<N> {
+    /// Creates the table for `base` modulo `m` that must be nonzero.
+    /// The width of the window is chosen by the size of the modulus.
+    pub fn new(base: &Bigi<N>, m: &Bigi<N>) -> Self {
+        let window = match m.bit_length() {
+            0..=128 => 3,
+            129..=512 => 4,
+            513..=2048 => 5,
+            _ => 6,
+        };
+        Self::with_window(base, m, window)
+    }
+
+    /// Creates the table of `2^window` powers of `base` modulo `m`.
+    /// `window` must be from 1 to 16.
+    pub fn with_window(base: &Bigi<N>, m: &Bigi<N>, window: usize) -> Self {
+        assert!((1..=16).contains(&window), "Window must be from 1 to 16");
+        let alg = BarrettAlg::new(m);
+        let x = alg.reduce(base);
+        let mut powers = Vec::with_capacity(1 << window);
+        powers.push(alg.reduce(&Bigi::<N>::from(1)));
+        for j in 1..(1 << window) {
+            powers.push(alg.mul(&powers[j - 1], &x));
+        }
+        Self { alg, window, powers }
+    }
+
+    /// The modulus.
+    pub fn modulus(&self) -> &Bigi<N> {
+        self.alg.modulus()
+    }
+
+    /// The width of the window in bits.
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Calculates `(base^e) % m`.
+    pub fn pow(&self, e: &Bigi<N>) -> Bigi<N> {
+        let mut windows = e.windows(self.window).rev();
+        // The highest window is not zero, so no squarings of one
+        let mut res = match windows.next() {
+            Some(j) => self.powers[j as usize],
+            None => return self.powers[0],
+        };
+        for j in windows {
+            for _ in 0..self.window {
+                res = self.alg.square(&res);
+            }
+            if j > 0 {
+                res = self.alg.mul(&res, &self.powers[j as usize]);
+            }
+        }
+        res
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::bigi;
+    use super::*;
+    use test::Bencher;
+
+    #[test]
+    fn test_new() {
+        let table = PowTable::new(&bigi![4; 28], &bigi![4; 23]);
+        assert_eq!(table.window(), 3);
+        assert_eq!(table.modulus(), &bigi![4; 23]);
+        assert_eq!(table.powers, [1, 5, 2, 10, 4, 20, 8, 17].map(|x| bigi![4; x]));
+        assert_eq!(PowTable::new(&bigi![8; 2], &Bigi::<8>::MAX).window(), 4);
+    }
+
+    #[test]
+    fn test_pow() {
+        let table = PowTable::new(&bigi![4; 5], &bigi![4; 23]);
+        assert_eq!(table.pow(&bigi![4; 0]), bigi![4; 1]);
+        assert_eq!(table.pow(&bigi![4; 1]), bigi![4; 5]);
+        assert_eq!(table.pow(&bigi![4; 22]), bigi![4; 1]);
+        assert_eq!(PowTable::new(&bigi![4; 5], &bigi![4; 1]).pow(&bigi![4; 0]), bigi![4; 0]);
+
+        let mut rng = rand::thread_rng();
+        let m = Bigi::<8>::gen_random(&mut rng, 256, true);
+        let x = Bigi::<8>::gen_random(&mut rng, 300, false);
+        for window in [1, 2, 4, 5, 7, 10] {
+            let table = PowTable::with_window(&x, &m, window);
+            for bits in [1, 5, 64, 65, 256, 512] {
+                let e = Bigi::<8>::gen_random(&mut rng, bits, false);
+                assert_eq!(table.pow(&e), x.powmod(&e, &m));
+            }
+            assert_eq!(table.pow(&Bigi::<8>::MAX), x.powmod(&Bigi::<8>::MAX, &m));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Window must be from 1 to 16")]
+    fn test_zero_window() {
+        PowTable::with_window(&bigi![4; 5], &bigi![4; 23], 0);
+    }
+
+    #[bench]
+    fn bench_new_256(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let m = Bigi::<8>::gen_random(&mut rng, 256, true);
+        let x = Bigi::<8>::gen_random(&mut rng, 256, false) % &m;
+        bencher.iter(|| PowTable::new(&x, &m));
+    }
+
+    #[bench]
+    fn bench_pow_256(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let m = Bigi::<8>::gen_random(&mut rng, 256, true);
+        let x = Bigi::<8>::gen_random(&mut rng, 256, false) % &m;
+        let e = Bigi::<8>::gen_random(&mut rng, 256, false);
+        let table = PowTable::new(&x, &m);
+        bencher.iter(|| table.pow(&e));
+    }
+}
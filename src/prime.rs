@@ -10,26 +10,112 @@
 
 extern crate rand;
 
-use std::mem;
+use std::{mem, cmp};
+use std::borrow::Cow;
+use std::ops::ControlFlow;
+use std::sync::OnceLock;
 use rand::Rng;
 use crate::base::Bigi;
+use crate::modulo::ModuloSmall;
 
 
-const QUICK_PRIMES: &[u64] = &[3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41,
-                            43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89,
-                            97, 101, 103, 107, 109, 113, 127, 131, 137,
-                            139, 149, 151, 157, 163, 167, 173, 179, 181,
-                            191, 193, 197, 199, 211, 223, 227, 229, 233];
+// The largest prime used in quick_prime_check
+const QUICK_PRIMES_BOUND: u64 = 233;
+
+// Primes up to this bound are sieved once and cached
+const SMALL_PRIMES_LIMIT: u64 = 1 << 16;
+
+// Generated primes are composite with the probability at most 2^(-128)
+const PRIME_ERROR_EXP: usize = 128;
+
+// Length of the intervals sieved by nth_prime
+const NTH_PRIME_SEGMENT: usize = 1 << 16;
+
+// Minimum number of digits of the smaller operand for Euclidean algorithm
+// to run Lehmer steps instead of divisions
+const LEHMER_THRESHOLD: usize = 2;
+
+static SMALL_PRIMES: OnceLock<Vec<u64>> = OnceLock::new();
+static QUICK_PRIMORIALS: OnceLock<Vec<u64>> = OnceLock::new();
+
+
+/// Gets all the primes not greater than `bound` using
+/// [sieve of Eratosthenes](https://en.wikipedia.org/wiki/Sieve_of_Eratosthenes)
+/// over odd numbers. The primes up to `2^16` are sieved once and cached,
+/// so small bounds cost nothing after the first call.
+/// ```rust
+/// use bigi::small_primes;
+///
+/// assert_eq!(&small_primes(20)[..], &[2, 3, 5, 7, 11, 13, 17, 19]);
+/// ```
+pub fn small_primes(bound: u64) -> Cow<'static, [u64]> {
+    if bound <= SMALL_PRIMES_LIMIT {
+        let primes = SMALL_PRIMES.get_or_init(|| sieve(SMALL_PRIMES_LIMIT));
+        let size = primes.partition_point(|p| *p <= bound);
+        Cow::Borrowed(&primes[..size])
+    } else {
+        Cow::Owned(sieve(bound))
+    }
+}
+
+
+fn sieve(bound: u64) -> Vec<u64> {
+    if bound < 2 {
+        return Vec::new();
+    }
+
+    // Index i stands for the odd number 2 * i + 3
+    let size = ((bound - 1) / 2) as usize;
+    let mut is_prime = vec![true; size];
+    let mut primes = vec![2];
+
+    for i in 0..size {
+        if is_prime[i] {
+            let p = 2 * i + 3;
+            primes.push(p as u64);
+            let mut j = (p * p - 3) / 2;
+            while j < size {
+                is_prime[j] = false;
+                j += p;
+            }
+        }
+    }
+
+    primes
+}
+
+
+// Products of consecutive odd primes up to QUICK_PRIMES_BOUND, each fits
+// into u64
+fn quick_primorials() -> &'static [u64] {
+    QUICK_PRIMORIALS.get_or_init(|| {
+        let mut primorials = Vec::new();
+        let mut chunk: u64 = 1;
+        for p in small_primes(QUICK_PRIMES_BOUND)[1..].iter() {
+            chunk = match chunk.checked_mul(*p) {
+                Some(value) => value,
+                None => {
+                    primorials.push(chunk);
+                    *p
+                }
+            };
+        }
+        primorials.push(chunk);
+        primorials
+    })
+}
 
 
 /// Checks `x` for prime (except for `2`) that returns true if there is no
-/// divisor among the fixed set of primes `QUICK_PRIMES` (from `3` to `233`).
+/// divisor among the fixed set of primes from `3` to `233`.
+/// Instead of dividing by each prime the function calculates GCD of `x`
+/// and a few precomputed products of the primes that fit into *u64*.
 /// ```rust
 /// use bigi::{Bigi, quick_prime_check};
 ///
-/// assert_eq!(quick_prime_check(&Bigi::<4>::from(11)), true);
-/// assert_eq!(quick_prime_check(&Bigi::<4>::from(121)), false);
-/// assert_eq!(quick_prime_check(&Bigi::<4>::from(541)), true);
+/// assert!(quick_prime_check(&Bigi::<4>::from(11)));
+/// assert!(!quick_prime_check(&Bigi::<4>::from(121)));
+/// assert!(quick_prime_check(&Bigi::<4>::from(541)));
 /// assert_eq!(
 ///     quick_prime_check(&Bigi::<4>::from(282943)), true
 /// );  // Though 282943 = 523 * 541
@@ -38,13 +124,20 @@ pub fn quick_prime_check<const N: usize>(x: &Bigi<N>) -> bool {
     if x.is_even() {
         return false;
     }
-    for p in QUICK_PRIMES.iter() {
-        let b = Bigi::<N>::from(*p);
-        if (*x % &b).is_zero() {
-            return *x == b;
+    for primorial in quick_primorials().iter() {
+        let mut a = *primorial;
+        let mut b = x.rem_u64(a);
+        while b != 0 {
+            a %= b;
+            mem::swap(&mut a, &mut b);
+        }
+        if a != 1 {
+            // x has a small factor, so it is prime only if it is that factor
+            return x.get_order() == 1 &&
+                   small_primes(QUICK_PRIMES_BOUND).contains(&x.digits[0]);
         }
     }
-    return true;
+    true
 }
 
 
@@ -53,10 +146,10 @@ pub fn quick_prime_check<const N: usize>(x: &Bigi<N>) -> bool {
 /// ```rust
 /// use bigi::{Bigi, fermat_test};
 ///
-/// assert_eq!(fermat_test(&Bigi::<4>::from(11), 10), true);
-/// assert_eq!(fermat_test(&Bigi::<4>::from(121), 10), false);
-/// assert_eq!(fermat_test(&Bigi::<4>::from(541), 10), true);
-/// assert_eq!(fermat_test(&Bigi::<4>::from(282943), 10), false);
+/// assert!(fermat_test(&Bigi::<4>::from(11), 10));
+/// assert!(!fermat_test(&Bigi::<4>::from(121), 10));
+/// assert!(fermat_test(&Bigi::<4>::from(541), 10));
+/// assert!(!fermat_test(&Bigi::<4>::from(282943), 10));
 /// ```
 pub fn fermat_test<const N: usize>(x: &Bigi<N>, k: usize) -> bool {
     let one = Bigi::<N>::from(1);
@@ -65,7 +158,7 @@ pub fn fermat_test<const N: usize>(x: &Bigi<N>, k: usize) -> bool {
     let p = *x - &one;
 
     for _i in 0..k {
-        let a = Bigi::<N>::gen_random(&mut rng, bits, false) % &x;
+        let a = Bigi::<N>::gen_random(&mut rng, bits, false) % x;
 
         if a.is_zero() {
             continue;
@@ -85,56 +178,169 @@ pub fn fermat_test<const N: usize>(x: &Bigi<N>, k: usize) -> bool {
 /// ```rust
 /// use bigi::{Bigi, miller_rabin};
 ///
-/// assert_eq!(miller_rabin(&Bigi::<4>::from(11), 10), true);
-/// assert_eq!(miller_rabin(&Bigi::<4>::from(121), 10), false);
-/// assert_eq!(miller_rabin(&Bigi::<4>::from(541), 10), true);
-/// assert_eq!(miller_rabin(&Bigi::<4>::from(282943), 10), false);
+/// assert!(miller_rabin(&Bigi::<4>::from(11), 10));
+/// assert!(!miller_rabin(&Bigi::<4>::from(121), 10));
+/// assert!(miller_rabin(&Bigi::<4>::from(541), 10));
+/// assert!(!miller_rabin(&Bigi::<4>::from(282943), 10));
 /// ```
 pub fn miller_rabin<const N: usize>(x: &Bigi<N>, k: usize) -> bool {
-    let one = Bigi::<N>::from(1);
-    let two = Bigi::<N>::from(2);
-    let bits = x.bit_length();
-    let mut rng = rand::thread_rng();
-    let n = *x - &one;
+    test_prime(x, k).is_probable_prime()
+}
+
+
+/// Performs [Miller-Rabin test](https://en.wikipedia.org/wiki/Miller%E2%80%93Rabin_primality_test)
+/// with the number of rounds enough for the error probability to not exceed
+/// `2^(-target_error_exp)`. The rounds are estimated for a random candidate
+/// of the bit length of `x` by Damgård–Landrock–Pomerance bounds, so large
+/// numbers need just a few rounds.
+/// ```rust
+/// use bigi::{Bigi, miller_rabin_auto};
+///
+/// assert!(miller_rabin_auto(&Bigi::<4>::from(541), 80));
+/// assert!(!miller_rabin_auto(&Bigi::<4>::from(282943), 80));
+/// ```
+pub fn miller_rabin_auto<const N: usize>(x: &Bigi<N>,
+                                         target_error_exp: usize) -> bool {
+    miller_rabin(x, miller_rabin_rounds(x.bit_length(), target_error_exp))
+}
+
+
+// Number of rounds of Miller-Rabin test for a random `bits`-bit candidate
+// to be composite with the probability at most 2^(-target_error_exp)
+fn miller_rabin_rounds(bits: usize, target_error_exp: usize) -> usize {
+    // The worst case bound 4^(-t) holds for any number
+    let worst = target_error_exp.div_ceil(2);
+    let target = -(target_error_exp as f64);
+    let k = bits as f64;
+
+    // p(k, 1) <= k^2 * 4^(2 - sqrt(k))
+    if bits >= 2 && 2.0 * k.log2() + 2.0 * (2.0 - k.sqrt()) <= target {
+        return 1;
+    }
+
+    // p(k, t) <= k^(3/2) * 2^t * t^(-1/2) * 4^(2 - sqrt(t * k))
+    // for t = 2, k >= 88 or 3 <= t <= k / 9, k >= 21
+    for t in 2..worst {
+        let applicable = if t == 2 { bits >= 88 } else { bits >= 21 && 9 * t <= bits };
+        if !applicable {
+            break;
+        }
+        let tf = t as f64;
+        let bound = 1.5 * k.log2() + tf - 0.5 * tf.log2()
+                  + 2.0 * (2.0 - (tf * k).sqrt());
+        if bound <= target {
+            return t;
+        }
+    }
+
+    worst.max(1)
+}
+
+
+/// Result of a primality test.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Primality<const N: usize> {
+    /// No witness of compositeness was found.
+    ProbablePrime,
+    /// The number is composite as proven by `witness`.
+    Composite { witness: Bigi<N> },
+}
 
-    // Calculating d and s such that: x = 2^s * d + 1
-    let mut d = n.clone();
-    let mut s: usize = 0;
-    while d.is_even() {
-        d >>= 1;
-        s += 1;
+
+impl<const N: usize> Primality<N> {
+    /// Checks if the tested number is probably prime.
+    pub fn is_probable_prime(&self) -> bool {
+        matches!(self, Primality::ProbablePrime)
     }
+}
+
+
+/// Performs [Miller-Rabin test](https://en.wikipedia.org/wiki/Miller%E2%80%93Rabin_primality_test)
+/// with `k` rounds and returns the witness `a` if `x` is proven to be
+/// composite.
+/// ```rust
+/// use bigi::{Bigi, test_prime, Primality};
+///
+/// assert_eq!(test_prime(&Bigi::<4>::from(541), 10), Primality::ProbablePrime);
+/// match test_prime(&Bigi::<4>::from(282943), 10) {
+///     Primality::Composite { witness } => assert!(witness < Bigi::<4>::from(282943)),
+///     Primality::ProbablePrime => unreachable!(),
+/// }
+/// ```
+pub fn test_prime<const N: usize>(x: &Bigi<N>, k: usize) -> Primality<N> {
+    let bits = x.bit_length();
+    let mut rng = rand::thread_rng();
+    let (d, s) = odd_decomposition(x);
 
     // Loop
     for _i in 0..k {
-        let a = Bigi::<N>::gen_random(&mut rng, bits, false) % &x;
+        let a = Bigi::<N>::gen_random(&mut rng, bits, false) % x;
 
-        if a.is_zero() {
-            continue;
+        if is_witness(&a, &d, s, x) {
+            return Primality::Composite { witness: a };
         }
+    }
 
-        let mut b = a.powmod(&d, &x);
+    Primality::ProbablePrime
+}
 
-        if b != one {
-            let found = {
-                let mut found = false;
-                for _r in 0..s {
-                    if b == n {
-                        found = true;
-                        break;
-                    }
-                    b = b.powmod(&two, &x);
-                }
-                found
-            };
 
-            if !found {
+/// Performs [Miller-Rabin test](https://en.wikipedia.org/wiki/Miller%E2%80%93Rabin_primality_test)
+/// with `k` rounds distributed across the threads of `rayon` pool.
+/// All the threads stop as soon as any of them finds a witness of
+/// compositeness, so it is useful for large candidates and many rounds.
+/// ```rust
+/// use bigi::{Bigi, miller_rabin_parallel};
+///
+/// assert!(miller_rabin_parallel(&Bigi::<4>::from(541), 64));
+/// assert!(!miller_rabin_parallel(&Bigi::<4>::from(282943), 64));
+/// ```
+#[cfg(feature = "rayon")]
+pub fn miller_rabin_parallel<const N: usize>(x: &Bigi<N>, k: usize) -> bool {
+    use rayon::prelude::*;
+
+    let bits = x.bit_length();
+    let (d, s) = odd_decomposition(x);
+
+    !(0..k).into_par_iter().map_init(
+        rand::thread_rng,
+        |rng, _| Bigi::<N>::gen_random(rng, bits, false) % x
+    ).any(|a| is_witness(&a, &d, s, x))
+}
+
+
+// Calculates d and s such that: x = 2^s * d + 1
+fn odd_decomposition<const N: usize>(x: &Bigi<N>) -> (Bigi<N>, usize) {
+    let d = *x - &Bigi::<N>::from(1);
+    let s = d.trailing_zeros();
+    (d >> s, s)
+}
+
+
+// Checks if `a` proves `x = 2^s * d + 1` to be composite
+fn is_witness<const N: usize>(a: &Bigi<N>, d: &Bigi<N>, s: usize,
+                              x: &Bigi<N>) -> bool {
+    let one = Bigi::<N>::from(1);
+    let n = *x - &one;
+
+    if a.is_zero() {
+        return false;
+    }
+
+    let mut b = a.powmod(d, x);
+
+    if b != one {
+        for _r in 0..s {
+            if b == n {
                 return false;
             }
+            let (lo, hi) = b.square_overflowing();
+            b = reduce_wide(&hi, &lo, x);
         }
+        return true;
     }
 
-    true
+    false
 }
 
 
@@ -147,13 +353,105 @@ pub fn miller_rabin<const N: usize>(x: &Bigi<N>, k: usize) -> bool {
 /// ```
 pub fn gen_prime<R: Rng + ?Sized, const N: usize>(
             rng: &mut R, bits: usize) -> Bigi<N> {
+    gen_prime_with_progress(rng, bits, |_| ControlFlow::Continue(())).unwrap()
+}
+
+
+/// State of the prime search passed to the callback of
+/// `gen_prime_with_progress`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PrimeProgress {
+    /// Size of the candidates in bits.
+    pub bits: usize,
+    /// Number of the candidates tried so far.
+    pub attempts: usize,
+    /// Number of the candidates rejected by the small primes check.
+    pub sieved: usize,
+}
+
+
+/// Generates a prime number sized exactly `bits` bits as `gen_prime` does,
+/// calling `callback` for every candidate. The search stops and the function
+/// returns `None` as soon as the callback returns `ControlFlow::Break`.
+/// ```rust
+/// use std::ops::ControlFlow;
+/// use bigi::{Bigi, gen_prime_with_progress};
+///
+/// let mut rng = rand::thread_rng();
+/// let p = gen_prime_with_progress::<_, _, 4>(&mut rng, 256, |progress| {
+///     if progress.attempts > 1000000 {
+///         ControlFlow::Break(())
+///     } else {
+///         ControlFlow::Continue(())
+///     }
+/// });
+/// assert!(p.is_some());
+/// ```
+pub fn gen_prime_with_progress<R, F, const N: usize>(
+            rng: &mut R, bits: usize, mut callback: F) -> Option<Bigi<N>>
+        where R: Rng + ?Sized, F: FnMut(&PrimeProgress) -> ControlFlow<()> {
+    let mut progress = PrimeProgress { bits, attempts: 0, sieved: 0 };
     loop {
+        progress.attempts += 1;
+        if callback(&progress).is_break() {
+            return None;
+        }
         let x = Bigi::<N>::gen_random(rng, bits, true);
         if !quick_prime_check(&x) {
+            progress.sieved += 1;
             continue;
         }
-        let is_prime = miller_rabin(&x, 100);
+        let is_prime = miller_rabin_auto(&x, PRIME_ERROR_EXP);
         if is_prime {
+            return Some(x);
+        }
+    }
+}
+
+
+/// Generates a pair of RSA primes `p` and `q` sized exactly `bits` bits
+/// each, so their product is exactly `2 * bits` bits. The primes satisfy
+/// the requirements: `|p - q| > 2^(bits / 2 - 100)`,
+/// `gcd(e, p - 1) = gcd(e, q - 1) = 1`, and if `blum = true` then also
+/// `p = q = 3 (mod 4)`. The exponent `e` must be odd and greater than 1,
+/// otherwise no prime satisfies the requirements and it panics.
+/// ```rust
+/// use bigi::{Bigi, gen_prime_pair};
+///
+/// let mut rng = rand::thread_rng();
+/// let e = Bigi::<8>::from(65537);
+/// let (p, q) = gen_prime_pair(&mut rng, 256, &e, false);
+/// assert_eq!((p * &q).bit_length(), 512);
+/// ```
+pub fn gen_prime_pair<R: Rng + ?Sized, const N: usize>(
+            rng: &mut R, bits: usize, e: &Bigi<N>, blum: bool
+        ) -> (Bigi<N>, Bigi<N>) {
+    let distance = Bigi::<N>::from(1) << (bits / 2).saturating_sub(100);
+    let p = gen_rsa_prime(rng, bits, e, blum);
+    loop {
+        let q = gen_rsa_prime(rng, bits, e, blum);
+        let diff = if p > q { p - &q } else { q - &p };
+        if diff > distance {
+            return (p, q);
+        }
+    }
+}
+
+
+fn gen_rsa_prime<R: Rng + ?Sized, const N: usize>(
+            rng: &mut R, bits: usize, e: &Bigi<N>, blum: bool) -> Bigi<N> {
+    let one = Bigi::<N>::from(1);
+    assert!(e.is_odd() && *e > one, "Exponent must be odd and greater than 1");
+    loop {
+        let mut x = Bigi::<N>::gen_random_rsa(rng, bits);
+        x.digits[0] |= if blum { 3 } else { 1 };
+        if !quick_prime_check(&x) {
+            continue;
+        }
+        if !is_coprime(e, &(x - &one)) {
+            continue;
+        }
+        if miller_rabin_auto(&x, PRIME_ERROR_EXP) {
             return x;
         }
     }
@@ -174,6 +472,10 @@ pub fn euclidean<const N: usize>(x: &Bigi<N>, y: &Bigi<N>) -> Bigi<N> {
     let mut a = x.clone();
     let mut b = y.clone();
     while !b.is_zero() {
+        if let Some((m, _)) = lehmer_matrix(&a, &b) {
+            (a, b) = (combine(&a, &b, m[0], m[1]), combine(&a, &b, m[2], m[3]));
+            continue;
+        }
         a.divide(&b);
         mem::swap(&mut a, &mut b);
     }
@@ -181,6 +483,33 @@ pub fn euclidean<const N: usize>(x: &Bigi<N>, y: &Bigi<N>) -> Bigi<N> {
 }
 
 
+/// Checks if `x` and `y` are coprime (their GCD is `1`). It runs Euclidean
+/// algorithm but stops as soon as the answer is known: both numbers are even
+/// or a remainder is `1`.
+/// ```rust
+/// use bigi::{Bigi, is_coprime};
+///
+/// assert!(is_coprime(&Bigi::<4>::from(35), &Bigi::<4>::from(12)));
+/// assert!(!is_coprime(&Bigi::<4>::from(35), &Bigi::<4>::from(14)));
+/// ```
+pub fn is_coprime<const N: usize>(x: &Bigi<N>, y: &Bigi<N>) -> bool {
+    if x.is_even() && y.is_even() {
+        return false;
+    }
+    let one = Bigi::<N>::from(1);
+    let mut a = *x;
+    let mut b = *y;
+    while !b.is_zero() {
+        if b == one {
+            return true;
+        }
+        a.divide(&b);
+        mem::swap(&mut a, &mut b);
+    }
+    a == one
+}
+
+
 /// Applies
 /// [extended Euclidean algorithm](https://en.wikipedia.org/wiki/Extended_Euclidean_algorithm)
 /// over two given numbers.
@@ -206,6 +535,17 @@ pub fn euclidean_extended<const N: usize> (
     let mut inv = false;
 
     while !b.is_zero() {
+        // The coefficients are transformed by the same matrix as a and b,
+        // the arithmetic is wrapping so the negative values are kept as
+        // their complements
+        if let Some((m, odd)) = lehmer_matrix(&a, &b) {
+            (a, b) = (combine(&a, &b, m[0], m[1]), combine(&a, &b, m[2], m[3]));
+            (aa, ba) = (combine(&aa, &ba, m[0], m[1]), combine(&aa, &ba, m[2], m[3]));
+            (ab, bb) = (combine(&ab, &bb, m[0], m[1]), combine(&ab, &bb, m[2], m[3]));
+            inv ^= odd;
+            continue;
+        }
+
         let q = a.divide(&b);
 
         aa -= &(q * &ba);
@@ -229,6 +569,56 @@ pub fn euclidean_extended<const N: usize> (
 }
 
 
+/// Runs the steps of Euclidean algorithm over the highest 64 bits of
+/// `a >= b` while the quotients are the same as for the whole numbers
+/// ([Lehmer's algorithm](https://en.wikipedia.org/wiki/Lehmer%27s_GCD_algorithm),
+/// Knuth, The Art of Computer Programming, Vol. 2, 4.5.2). It returns
+/// the matrix `[A, B, C, D]` so the next pair is `(A a + B b, C a + D b)`
+/// and whether the number of steps is odd, or `None` if `b` is too short,
+/// `a < b` or no quotient is known.
+fn lehmer_matrix<const N: usize>(a: &Bigi<N>, b: &Bigi<N>
+            ) -> Option<([i128; 4], bool)> {
+    if b.get_order() < LEHMER_THRESHOLD || a < b {
+        return None;
+    }
+    let shift = a.bit_length() - 64;
+    let mut ah = (*a >> shift).digits[0] as i128;
+    let mut bh = (*b >> shift).digits[0] as i128;
+    let (mut ma, mut mb, mut mc, mut md) = (1, 0, 0, 1);
+    let mut odd = false;
+    while bh + mc != 0 && bh + md != 0 {
+        let q = (ah + ma) / (bh + mc);
+        if q != (ah + mb) / (bh + md) {
+            break;
+        }
+        (ma, mc) = (mc, ma - q * mc);
+        (mb, md) = (md, mb - q * md);
+        (ah, bh) = (bh, ah - q * bh);
+        odd = !odd;
+    }
+    if mb == 0 {
+        None
+    } else {
+        Some(([ma, mb, mc, md], odd))
+    }
+}
+
+
+/// Calculates `p x + q y` modulo `2^(64N)` for the coefficients that are
+/// not both negative and less than `2^64` by absolute value.
+fn combine<const N: usize>(x: &Bigi<N>, y: &Bigi<N>, p: i128, q: i128) -> Bigi<N> {
+    let px = *x * (p.unsigned_abs() as u64);
+    let qy = *y * (q.unsigned_abs() as u64);
+    if q < 0 {
+        px - &qy
+    } else if p < 0 {
+        qy - &px
+    } else {
+        px + &qy
+    }
+}
+
+
 /// Performs modular addition: `(x + y) % m`.
 pub fn add_mod<const N: usize>(
             x: &Bigi<N>, y: &Bigi<N>, m: &Bigi<N>) -> Bigi<N> {
@@ -255,9 +645,83 @@ pub fn sub_mod<const N: usize>(
 /// Performs modular multiplication `(x * y) % m`.
 pub fn mul_mod<const N: usize>(
             x: &Bigi<N>, y: &Bigi<N>, m: &Bigi<N>) -> Bigi<N> {
-    let mut pair = x.multiply_overflowing(y);
-    pair.0.divide_overflowing(m, &pair.1);
-    pair.0
+    let (lo, hi) = x.widening_mul(y);
+    reduce_wide(&hi, &lo, m)
+}
+
+
+/// Performs modular multiplication by a word `(x * k) % m`. The product
+/// has only one extra digit, so it is reduced by a short division instead
+/// of the full double width one.
+/// ```rust
+/// use bigi::{bigi, Bigi, mul_mod_u64};
+///
+/// assert_eq!(mul_mod_u64(&bigi![2; 0, 1 << 63], 6, &bigi![2; 1000]), bigi![2; 368]);  // 3 * 2^128 % 1000
+/// ```
+pub fn mul_mod_u64<const N: usize>(x: &Bigi<N>, k: u64, m: &Bigi<N>) -> Bigi<N> {
+    let mut lo = *x;
+    let mut fw: u128 = 0;
+    for digit in lo.digits.iter_mut() {
+        fw += (*digit as u128) * (k as u128);
+        *digit = fw as u64;
+        fw >>= 64;
+    }
+    if fw == 0 {
+        lo % m
+    } else {
+        reduce_wide(&Bigi::<N>::from(fw as u64), &lo, m)
+    }
+}
+
+
+/// Calculates `(hi * 2^(64 * N) + lo) % m` for a double width integer
+/// given by its high and low halves, for example, the result of
+/// `widening_mul`.
+/// ```rust
+/// use bigi::{bigi, Bigi, reduce_wide};
+///
+/// let (lo, hi) = bigi![2; 0, 1 << 63].widening_mul(&bigi![2; 0, 6]);
+/// assert_eq!(reduce_wide(&hi, &lo, &bigi![2; 1000]), bigi![2; 688]);  // 3 * 2^192 % 1000
+/// ```
+pub fn reduce_wide<const N: usize>(
+            hi: &Bigi<N>, lo: &Bigi<N>, m: &Bigi<N>) -> Bigi<N> {
+    let mut res = *lo;
+    if hi < m {
+        res.divide_overflowing(m, hi);
+    } else {
+        // The quotient must fit into N digits
+        res.divide_overflowing(m, &(*hi % m));
+    }
+    res
+}
+
+
+/// Calculates `2^k % m` without building the power or the exponent as
+/// an integer: the leading bits of `k` are taken by a shift within
+/// the width, then the rest is processed by squarings and doublings.
+/// ```rust
+/// use bigi::{Bigi, pow2_mod};
+///
+/// assert_eq!(pow2_mod(10, &Bigi::<4>::from(1000)), Bigi::<4>::from(24));
+/// assert_eq!(pow2_mod(1000, &Bigi::<1>::from(1000000007)), Bigi::<1>::from(688423210));
+/// ```
+pub fn pow2_mod<const N: usize>(k: usize, m: &Bigi<N>) -> Bigi<N> {
+    let width = N << 6;
+
+    // The leading bits of k such that the power fits into the width
+    let mut shift = 0;
+    while (k >> shift) >= width {
+        shift += 1;
+    }
+    let mut res = (Bigi::<N>::from(1) << (k >> shift)) % m;
+
+    for i in (0..shift).rev() {
+        res = mul_mod(&res, &res, m);
+        if (k >> i) & 1 == 1 {
+            res = add_mod(&res, &res, m);
+        }
+    }
+    res
 }
 
 
@@ -269,6 +733,38 @@ pub fn inv_mod<const N: usize>(
 }
 
 
+/// Searches for `y` such that `(x * y) % 2^k == 1` for odd `x` using
+/// Newton's (Hensel's) iteration `y = y * (2 - x * y)` that doubles
+/// the number of correct low bits every step.
+/// ```rust
+/// use bigi::{Bigi, inv_mod_2k};
+///
+/// assert_eq!(inv_mod_2k(&Bigi::<4>::from(3), 8), Bigi::<4>::from(171));
+/// ```
+pub fn inv_mod_2k<const N: usize>(x: &Bigi<N>, k: usize) -> Bigi<N> {
+    assert!(x.is_odd(), "x must be odd");
+    assert!(k <= N * 64, "k is too large");
+
+    // The lowest digit in u64 arithmetic, x * x = 1 (mod 8) for odd x
+    let x0 = x.digits[0];
+    let mut y0 = x0;
+    for _ in 0..5 {
+        y0 = y0.wrapping_mul(2u64.wrapping_sub(x0.wrapping_mul(y0)));
+    }
+
+    // The other digits with wrapping multiplication modulo 2^(64 * N)
+    let two = Bigi::<N>::from(2);
+    let mut y = Bigi::<N>::from(y0);
+    let mut bits = 64;
+    while bits < k {
+        y = y * &(two - &(*x * &y));
+        bits <<= 1;
+    }
+
+    y.mod_2k(k)
+}
+
+
 /// Searches for `z` such that `(y * z) % m == x`.
 /// It is called modular division.
 pub fn div_mod<const N: usize>(
@@ -302,11 +798,10 @@ pub fn legendre_symbol<const N: usize>(a: &Bigi<N>, p: &Bigi<N>) -> i32 {
     while !ac.is_zero() {
         let r = u64::from(&pc.mod_2k(3));
         let i = (r == 3) || (r == 5);
-        while ac.is_even() {
-            ac >>= 1;
-            if i {
-                t = -t;
-            }
+        let k = ac.trailing_zeros();
+        ac >>= k;
+        if i && k % 2 == 1 {
+            t = -t;
         }
         mem::swap(&mut ac, &mut pc);
         if (r % 4 == 3) && (u64::from(&pc.mod_2k(2)) == 3) {
@@ -319,125 +814,1310 @@ pub fn legendre_symbol<const N: usize>(a: &Bigi<N>, p: &Bigi<N>) -> i32 {
 }
 
 
-/// Performs [Tonelli–Shanks algorithm](https://en.wikipedia.org/wiki/Tonelli%E2%80%93Shanks_algorithm)
-/// that searches for `x` such that `(x * x) % p == n` where `p` is prime
-/// (modular square root). The functions returns a tuple with two roots or error.
+/// Precomputed data of [Tonelli–Shanks algorithm](https://en.wikipedia.org/wiki/Tonelli%E2%80%93Shanks_algorithm)
+/// for a prime `p`: `q` and `s` such that `p - 1 = q * 2^s` and `z^q` for
+/// a non-quadratic residue `z`. It is useful to calculate many square roots
+/// modulo the same prime.
 /// ```rust
-/// use bigi::{Bigi, sqrt_mod};
+/// use bigi::{Bigi, SqrtContext};
 ///
-/// assert_eq!(
-///     sqrt_mod(&Bigi::<4>::from(8), &Bigi::<4>::from(137)),
-///     Ok((Bigi::<4>::from(62), Bigi::<4>::from(75)))
-/// );
-/// assert_eq!(
-///     sqrt_mod(&Bigi::<4>::from(6), &Bigi::<4>::from(137)),
-///     Err("Non-quadratic residue")
-/// );
+/// let ctx = SqrtContext::new(&Bigi::<4>::from(137));
+/// assert_eq!(ctx.sqrt(&Bigi::<4>::from(8)), Ok((Bigi::<4>::from(62), Bigi::<4>::from(75))));
+/// assert_eq!(ctx.sqrt(&Bigi::<4>::from(6)), Err("Non-quadratic residue"));
 /// ```
-pub fn sqrt_mod<const N: usize>(n: &Bigi<N>, p: &Bigi<N>
-            ) -> Result<(Bigi<N>, Bigi<N>), &'static str> {
-    /*
-    Tonelli–Shanks algorithm: https://en.wikipedia.org/wiki/Tonelli%E2%80%93Shanks_algorithm
-    */
-    // If n is not a quadratic residue
-    if legendre_symbol(&n, &p) != 1 {
-        return Err("Non-quadratic residue");
-    }
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SqrtContext<const N: usize> {
+    p: Bigi<N>,
+    q: Bigi<N>,
+    s: usize,
+    c: Bigi<N>,
+}
 
-    let one = Bigi::<N>::from(1);
-    let mut r;
-    if p.mod_2k(2) == Bigi::<N>::from(3) {
-        // Case p = 3 (mod 4)
-        r = n.powmod(&((*p + &one) >> 2), &p);
 
-    } else {
+impl<const N: usize> SqrtContext<N> {
+    /// Creates the context for prime `p`.
+    pub fn new(p: &Bigi<N>) -> Self {
+        let one = Bigi::<N>::from(1);
+
         // Defining q and s such that p - 1 = q * 2^s
-        let (q, s) = {
-            let mut q = *p - &one;
-            let mut s: usize = 0;
-            while q.is_even() {
-                q >>= 1;
-                s += 1;
-            }
-            (q, s)
-        };
+        let q = *p - &one;
+        let s = q.trailing_zeros();
+        let q = q >> s;
+
+        // Cases p = 3 (mod 4) and p = 5 (mod 8) do not need a non-quadratic
+        // residue
+        if s <= 2 {
+            return Self { p: *p, q, s, c: one };
+        }
 
         // Searching for a non-quadratic residue
         let z = {
             let mut z = Bigi::<N>::from(2);
             loop {
-                if legendre_symbol(&z, &p) != 1 {
+                if legendre_symbol(&z, p) != 1 {
                     break;
                 }
-                z += &one;
+                z += 1;
             }
             z
         };
 
-        let mut c = z.powmod(&q, &p);
-        r = n.powmod(&((q + &one) >> 1), &p);
-        let mut t = n.powmod(&q, &p);
-        let mut m = s;
-
-        // Tonelli–Shanks's loop
-        while t != one {
-            let i = {
-                let mut tp = t.clone();
-                let mut i: usize = 0;
-                while tp != one {
-                    tp = mul_mod(&tp, &tp, &p);
-                    i += 1;
-                }
-                i
-            };
-            let b = c.powmod(&(one << (m - i - 1)), &p);
-            r = mul_mod(&r, &b, &p);
-            c = mul_mod(&b, &b, &p);
-            t = mul_mod(&t, &c, &p);
-            m = i;
-        }
+        Self { p: *p, q, s, c: z.powmod(&q, p) }
     }
 
-    // Second root
-    let mut rc = *p - &r;
-    if rc < r {
-        mem::swap(&mut r, &mut rc);
+    /// Gets the prime of the context.
+    pub fn prime(&self) -> &Bigi<N> {
+        &self.p
     }
 
-    Ok((r, rc))
-}
-
+    /// Searches for `x` such that `(x * x) % p == n`. The functions returns
+    /// a tuple with two roots or error. For `p = 3 (mod 4)` and
+    /// `p = 5 (mod 8)` (Atkin's algorithm) a single exponentiation is used.
+    pub fn sqrt(&self, n: &Bigi<N>) -> Result<(Bigi<N>, Bigi<N>), &'static str> {
+        let p = &self.p;
 
-#[cfg(test)]
-mod tests {
-    use crate::bigi;
-    use super::*;
-    use test::Bencher;
+        if n.is_zero() {
+            return Ok((*n, *n));
+        }
 
-    #[test]
-    fn test_fermat_test() {
-        assert_eq!(fermat_test(&bigi![8; 29], 100), true);
-        assert_eq!(fermat_test(&bigi![8; 1009], 100), true);
-        assert_eq!(fermat_test(&bigi![8; 1001], 100), false);
-    }
+        // If n is not a quadratic residue
+        if legendre_symbol(n, p) != 1 {
+            return Err("Non-quadratic residue");
+        }
 
-    #[test]
-    fn test_miller_rabin() {
-        assert_eq!(miller_rabin(&bigi![8; 29], 100), true);
-        assert_eq!(miller_rabin(&bigi![8; 1009], 100), true);
-        assert_eq!(miller_rabin(&bigi![8; 1001], 100), false);
-    }
+        let one = Bigi::<N>::from(1);
+        let mut r;
+        if self.s == 1 {
+            // Case p = 3 (mod 4)
+            r = n.powmod(&((*p + &one) >> 2), p);
+
+        } else if self.s == 2 {
+            // Case p = 5 (mod 8), Atkin's algorithm:
+            // t = (2n)^((p - 5) / 8), i = 2n * t^2, r = n * t * (i - 1)
+            let n2 = add_mod(n, n, p);
+            let t = n2.powmod(&(*p >> 3), p);
+            let i = mul_mod(&n2, &mul_mod(&t, &t, p), p);
+            r = mul_mod(&mul_mod(n, &t, p), &sub_mod(&i, &one, p), p);
+
+        } else {
+            let mut c = self.c;
+            r = n.powmod(&((self.q + &one) >> 1), p);
+            let mut t = n.powmod(&self.q, p);
+            let mut m = self.s;
+
+            // Tonelli–Shanks's loop
+            while t != one {
+                let i = {
+                    let mut tp = t;
+                    let mut i: usize = 0;
+                    while tp != one {
+                        tp = mul_mod(&tp, &tp, p);
+                        i += 1;
+                    }
+                    i
+                };
+                let b = c.powmod(&(one << (m - i - 1)), p);
+                r = mul_mod(&r, &b, p);
+                c = mul_mod(&b, &b, p);
+                t = mul_mod(&t, &c, p);
+                m = i;
+            }
+        }
 
-    #[test]
-    fn test_gen_prime() {
-        let mut rng = rand::thread_rng();
+        // Second root
+        let mut rc = *p - &r;
+        if rc < r {
+            mem::swap(&mut r, &mut rc);
+        }
 
-        assert_eq!(gen_prime::<_, 4>(&mut rng, 128).bit_length(), 128);
-        assert_eq!(gen_prime::<_, 4>(&mut rng, 65).bit_length(), 65);
-        assert_eq!(gen_prime::<_, 4>(&mut rng, 96).bit_length(), 96);
-        assert_eq!(gen_prime::<_, 4>(&mut rng, 33).bit_length(), 33);
-        assert_eq!(gen_prime::<_, 4>(&mut rng, 15).bit_length(), 15);
-        assert_eq!(gen_prime::<_, 4>(&mut rng, 3).bit_length(), 3);
+        Ok((r, rc))
+    }
+}
+
+
+/// Performs [Tonelli–Shanks algorithm](https://en.wikipedia.org/wiki/Tonelli%E2%80%93Shanks_algorithm)
+/// that searches for `x` such that `(x * x) % p == n` where `p` is prime
+/// (modular square root). The functions returns a tuple with two roots or error.
+/// To calculate many roots modulo the same prime use `SqrtContext`.
+/// ```rust
+/// use bigi::{Bigi, sqrt_mod};
+///
+/// assert_eq!(
+///     sqrt_mod(&Bigi::<4>::from(8), &Bigi::<4>::from(137)),
+///     Ok((Bigi::<4>::from(62), Bigi::<4>::from(75)))
+/// );
+/// assert_eq!(
+///     sqrt_mod(&Bigi::<4>::from(6), &Bigi::<4>::from(137)),
+///     Err("Non-quadratic residue")
+/// );
+/// ```
+pub fn sqrt_mod<const N: usize>(n: &Bigi<N>, p: &Bigi<N>
+            ) -> Result<(Bigi<N>, Bigi<N>), &'static str> {
+    SqrtContext::new(p).sqrt(n)
+}
+
+
+/// Searches for all `x` such that `(x * x) % n == 1` (square roots of unity)
+/// given the factorization of `n` as pairs of a prime and its power. There
+/// are two roots modulo an odd prime power and up to four modulo a power
+/// of `2`, they are combined by the Chinese remainder theorem.
+/// The roots are returned in ascending order.
+/// ```rust
+/// use bigi::{Bigi, sqrts_of_unity};
+///
+/// let n = Bigi::<4>::from(15);
+/// let factors = [(Bigi::<4>::from(3), 1), (Bigi::<4>::from(5), 1)];
+/// assert_eq!(
+///     sqrts_of_unity(&n, &factors),
+///     vec![Bigi::<4>::from(1), Bigi::<4>::from(4), Bigi::<4>::from(11), Bigi::<4>::from(14)]
+/// );
+/// ```
+pub fn sqrts_of_unity<const N: usize>(n: &Bigi<N>, factorization: &[(Bigi<N>, usize)]
+            ) -> Vec<Bigi<N>> {
+    let one = Bigi::<N>::from(1);
+    let two = Bigi::<N>::from(2);
+    let mut modulo = one;
+    let mut roots = vec![Bigi::<N>::new()];
+
+    for (p, e) in factorization.iter() {
+        let mut pe = one;
+        for _ in 0..*e {
+            pe *= p;
+        }
+
+        // Roots modulo p^e
+        let local = if *p != two {
+            vec![one, pe - &one]
+        } else {
+            match *e {
+                1 => vec![one],
+                2 => vec![one, pe - &one],
+                _ => {
+                    let half = pe >> 1;
+                    vec![one, half - &one, half + &one, pe - &one]
+                },
+            }
+        };
+
+        // Combining with the roots modulo the previous factors
+        let inv = inv_mod(&(modulo % &pe), &pe);
+        roots = roots.iter().flat_map(|a| {
+            let ar = *a % &pe;
+            local.iter().map(move |b| {
+                *a + &(modulo * &mul_mod(&sub_mod(b, &ar, &pe), &inv, &pe))
+            })
+        }).collect();
+        modulo *= &pe;
+    }
+
+    assert!(modulo == *n, "Wrong factorization");
+
+    roots.sort();
+    roots
+}
+
+
+/// Calculates the multiplicative order of `x` modulo `n` (the least `k > 0`
+/// such that `x^k = 1 (mod n)`) given the factorization of any multiple of
+/// the order, usually Carmichael function `λ(n)` or Euler's totient `φ(n)`.
+/// `n` is not required to be prime. `x` must be coprime with `n`.
+/// ```rust
+/// use bigi::{Bigi, multiplicative_order};
+///
+/// // λ(91) = lcm(6, 12) = 12 = 2^2 * 3
+/// let factors = [(Bigi::<4>::from(2), 2), (Bigi::<4>::from(3), 1)];
+/// let order = multiplicative_order(&Bigi::<4>::from(3), &Bigi::<4>::from(91), &factors);
+/// assert_eq!(order, Bigi::<4>::from(6));
+/// ```
+pub fn multiplicative_order<const N: usize>(x: &Bigi<N>, n: &Bigi<N>,
+            factorization: &[(Bigi<N>, usize)]) -> Bigi<N> {
+    assert!(is_coprime(x, n), "Non-invertible element");
+    let one = Bigi::<N>::from(1);
+    let mut order = one;
+    for (p, e) in factorization.iter() {
+        for _ in 0..*e {
+            order *= p;
+        }
+    }
+    assert!(x.powmod(&order, n) == one % n, "Wrong factorization");
+
+    // Removing the prime factors that are not needed
+    for (p, e) in factorization.iter() {
+        for _ in 0..*e {
+            let reduced = order / p;
+            if x.powmod(&reduced, n) != one % n {
+                break;
+            }
+            order = reduced;
+        }
+    }
+    order
+}
+
+
+/// Checks whether `x` generates the subgroup of the maximal order of
+/// the multiplicative group modulo `n`, so its order equals the exponent
+/// of the group `λ(n)` (Carmichael function) given by the factorization.
+/// It is enough to check `x^(λ/q) != 1` for every prime `q` of `λ`.
+/// ```rust
+/// use bigi::{Bigi, is_generator};
+///
+/// // λ(91) = 12 = 2^2 * 3
+/// let factors = [(Bigi::<4>::from(2), 2), (Bigi::<4>::from(3), 1)];
+/// assert!(is_generator(&Bigi::<4>::from(2), &Bigi::<4>::from(91), &factors));
+/// assert!(!is_generator(&Bigi::<4>::from(3), &Bigi::<4>::from(91), &factors));
+/// ```
+pub fn is_generator<const N: usize>(x: &Bigi<N>, n: &Bigi<N>,
+            factorization: &[(Bigi<N>, usize)]) -> bool {
+    if !is_coprime(x, n) {
+        return false;
+    }
+    let one = Bigi::<N>::from(1) % n;
+    let mut order = Bigi::<N>::from(1);
+    for (p, e) in factorization.iter() {
+        for _ in 0..*e {
+            order *= p;
+        }
+    }
+    x.powmod(&order, n) == one && factorization.iter()
+        .all(|(p, _)| x.powmod(&(order / p), n) != one)
+}
+
+
+/// Checks whether `x` is a primitive root modulo `n` (generates the whole
+/// multiplicative group) given the factorization of Euler's totient `φ(n)`.
+/// The primitive roots exist only for `n = 1, 2, 4, p^k, 2p^k`
+/// with an odd prime `p`.
+/// ```rust
+/// use bigi::{Bigi, is_primitive_root};
+///
+/// // φ(49) = 42 = 2 * 3 * 7
+/// let factors = [(Bigi::<4>::from(2), 1), (Bigi::<4>::from(3), 1), (Bigi::<4>::from(7), 1)];
+/// assert!(is_primitive_root(&Bigi::<4>::from(3), &Bigi::<4>::from(49), &factors));
+/// assert!(!is_primitive_root(&Bigi::<4>::from(2), &Bigi::<4>::from(49), &factors));
+/// ```
+pub fn is_primitive_root<const N: usize>(x: &Bigi<N>, n: &Bigi<N>,
+            factorization: &[(Bigi<N>, usize)]) -> bool {
+    is_generator(x, n, factorization)
+}
+
+
+/// Solves `x^2 + d * y^2 = p` for prime `p` and `0 < d < p` using
+/// [Cornacchia's algorithm](https://en.wikipedia.org/wiki/Cornacchia%27s_algorithm):
+/// it starts from the square root of `-d` modulo `p` and runs Euclidean
+/// algorithm over `p` and the root until the remainder is less than `sqrt(p)`.
+/// The function returns `None` if there is no solution.
+/// ```rust
+/// use bigi::{Bigi, cornacchia};
+///
+/// assert_eq!(
+///     cornacchia(&Bigi::<4>::from(1), &Bigi::<4>::from(13)),
+///     Some((Bigi::<4>::from(3), Bigi::<4>::from(2)))
+/// );
+/// assert_eq!(cornacchia(&Bigi::<4>::from(1), &Bigi::<4>::from(7)), None);
+/// ```
+pub fn cornacchia<const N: usize>(d: &Bigi<N>, p: &Bigi<N>
+            ) -> Option<(Bigi<N>, Bigi<N>)> {
+    assert!(!d.is_zero() && d < p, "d must be in range 0 < d < p");
+
+    let (_, mut r0) = if *p == 2 {
+        // The only root of -d modulo 2
+        (Bigi::<N>::new(), Bigi::<N>::from(1))
+    } else {
+        sqrt_mod(&(*p - d), p).ok()?
+    };
+
+    // Euclidean algorithm until r^2 < p
+    let mut a = *p;
+    while r0 * &r0 >= *p {
+        let r = a % &r0;
+        a = r0;
+        r0 = r;
+    }
+
+    let (q, rest) = (*p - &(r0 * &r0)).div_rem(d);
+    if !rest.is_zero() {
+        return None;
+    }
+    let y = q.isqrt();
+    if y * &y != q {
+        return None;
+    }
+
+    Some((r0, y))
+}
+
+
+/// Calculates `n! % p` for prime `p`. The factors are multiplied in chunks
+/// that fit into *u64* before a modular reduction, and if `n` is close
+/// to `p` [Wilson's theorem](https://en.wikipedia.org/wiki/Wilson%27s_theorem)
+/// `(p - 1)! = -1 (mod p)` is used to multiply fewer factors.
+/// ```rust
+/// use bigi::{Bigi, factorial_mod};
+///
+/// assert_eq!(
+///     factorial_mod(&Bigi::<4>::from(10), &Bigi::<4>::from(13)),
+///     Bigi::<4>::from(6)
+/// );
+/// ```
+pub fn factorial_mod<const N: usize>(n: &Bigi<N>, p: &Bigi<N>) -> Bigi<N> {
+    if n >= p {
+        return Bigi::<N>::from(0);
+    }
+
+    let one = Bigi::<N>::from(1);
+    let rest = *p - n - &one;
+
+    if rest < *n {
+        // n! = -1 / ((n + 1) * ... * (p - 1)) (mod p)
+        let prod = range_product_mod(&(*n + &one), &rest, p);
+        *p - &inv_mod(&prod, p)
+    } else {
+        range_product_mod(&one, n, p)
+    }
+}
+
+
+fn range_product_mod<const N: usize>(start: &Bigi<N>, count: &Bigi<N>,
+                                     p: &Bigi<N>) -> Bigi<N> {
+    let one = Bigi::<N>::from(1);
+    let mut res = one % p;
+
+    if (*start + count).get_order() <= 1 {
+        // All factors fit into u64, so they are multiplied in chunks
+        let first = start.digits[0];
+        let last = first + count.digits[0];
+        let mut chunk: u64 = 1;
+        for x in first..last {
+            chunk = match chunk.checked_mul(x) {
+                Some(value) => value,
+                None => {
+                    res = mul_mod(&res, &Bigi::<N>::from(chunk), p);
+                    x
+                }
+            };
+        }
+        mul_mod(&res, &Bigi::<N>::from(chunk), p)
+    } else {
+        let mut x = *start;
+        let mut i = Bigi::<N>::from(0);
+        while i < *count {
+            res = mul_mod(&res, &x, p);
+            x += 1;
+            i += 1;
+        }
+        res
+    }
+}
+
+
+/// Factorial table modulo a small prime `p` to calculate binomial
+/// coefficients with [Lucas' theorem](https://en.wikipedia.org/wiki/Lucas%27s_theorem).
+/// Building the table takes `O(p)` time and memory, after that every
+/// coefficient costs `O(log_p n)` operations.
+/// ```rust
+/// use bigi::{Bigi, LucasTable};
+///
+/// let table = LucasTable::new(13);
+/// assert_eq!(
+///     table.binomial(&Bigi::<4>::from(1000), &Bigi::<4>::from(300)),
+///     Bigi::<4>::from(10)
+/// );
+/// ```
+pub struct LucasTable {
+    p: u64,
+    fact: Vec<u64>,
+    inv_fact: Vec<u64>,
+}
+
+
+impl LucasTable {
+    /// Creates a table for prime `p`.
+    pub fn new(p: u64) -> Self {
+        let size = p as usize;
+        let mul = |a: u64, b: u64| ((a as u128) * (b as u128) % (p as u128)) as u64;
+
+        let mut fact = vec![1 % p; size];
+        for i in 1..size {
+            fact[i] = mul(fact[i - 1], i as u64);
+        }
+
+        // (p - 1)! = -1 (mod p), so its inverse is -1 too
+        let mut inv_fact = vec![1 % p; size];
+        inv_fact[size - 1] = p - 1;
+        for i in (1..size).rev() {
+            inv_fact[i - 1] = mul(inv_fact[i], i as u64);
+        }
+
+        Self { p, fact, inv_fact }
+    }
+
+    /// Calculates `C(n, k) % p`.
+    pub fn binomial<const N: usize>(&self, n: &Bigi<N>, k: &Bigi<N>) -> Bigi<N> {
+        let p = Bigi::<N>::from(self.p);
+        let mut nc = *n;
+        let mut kc = *k;
+        let mut res = 1 % self.p;
+
+        while !kc.is_zero() {
+            let nq = nc.divide(&p);
+            let kq = kc.divide(&p);
+            let ni = nc.digits[0];
+            let ki = kc.digits[0];
+            if ki > ni {
+                return Bigi::<N>::from(0);
+            }
+            res = self.small_binomial(res, ni, ki);
+            nc = nq;
+            kc = kq;
+        }
+
+        Bigi::<N>::from(res)
+    }
+
+    fn small_binomial(&self, acc: u64, n: u64, k: u64) -> u64 {
+        let p = self.p as u128;
+        let mut res = (acc as u128) * (self.fact[n as usize] as u128) % p;
+        res = res * (self.inv_fact[k as usize] as u128) % p;
+        res = res * (self.inv_fact[(n - k) as usize] as u128) % p;
+        res as u64
+    }
+}
+
+
+/// Table of the products of the integers not divisible by a prime `p`
+/// modulo `p^q` to calculate binomial coefficients modulo the prime power,
+/// the generalization of Lucas' theorem by Granville (Binomial coefficients
+/// modulo prime powers, 1997). `n!` is `p^v` times the product of such
+/// parts of `floor(n / p^i)!` for all `i`, the power of `p` in `C(n, k)` is
+/// the number of carries when `k` and `n - k` are added in base `p`
+/// (Kummer's theorem). Building the table takes `O(p^q)` time and memory,
+/// after that every coefficient costs `O(log_p n)` operations.
+/// ```rust
+/// use bigi::{Bigi, PrimePowerTable};
+///
+/// let table = PrimePowerTable::new(3, 3);
+/// assert_eq!(
+///     table.binomial(&Bigi::<4>::from(10), &Bigi::<4>::from(3)),
+///     Bigi::<4>::from(12)
+/// );  // C(10, 3) = 120
+/// ```
+pub struct PrimePowerTable {
+    p: u64,
+    q: u32,
+    m: ModuloSmall,
+    prod: Vec<u64>,
+}
+
+
+impl PrimePowerTable {
+    /// Creates a table for prime `p` and the power `q > 0`.
+    pub fn new(p: u64, q: u32) -> Self {
+        assert!(p > 1 && q > 0, "Wrong prime power");
+        let m = ModuloSmall::new(p.checked_pow(q).expect("Prime power is too large"));
+        let size = m.modulo as usize;
+
+        let mut prod = vec![1; size];
+        for i in 1..size {
+            prod[i] = if (i as u64).is_multiple_of(p) {
+                prod[i - 1]
+            } else {
+                m.mul(prod[i - 1], i as u64)
+            };
+        }
+
+        Self { p, q, m, prod }
+    }
+
+    /// Calculates `C(n, k) % p^q`.
+    pub fn binomial<const N: usize>(&self, n: &Bigi<N>, k: &Bigi<N>) -> Bigi<N> {
+        if k > n {
+            return Bigi::<N>::from(0);
+        }
+
+        let p = Bigi::<N>::from(self.p);
+        let mut nc = *n;
+        let mut kc = *k;
+        let mut rc = *n - k;
+        let mut num = 1;
+        let mut den = 1;
+        let mut carries = 0;
+
+        while !nc.is_zero() {
+            num = self.m.mul(num, self.unit_factorial(&nc));
+            den = self.m.mul(den, self.unit_factorial(&kc));
+            den = self.m.mul(den, self.unit_factorial(&rc));
+            nc = nc.divide(&p);
+            kc = kc.divide(&p);
+            rc = rc.divide(&p);
+            carries += (nc - &kc - &rc).digits[0];
+            if carries >= self.q as u64 {
+                return Bigi::<N>::from(0);
+            }
+        }
+
+        let unit = self.m.div(num, den);
+        Bigi::<N>::from(self.m.mul(unit, self.m.pow(self.p, carries)))
+    }
+
+    /// The product of the integers up to `n` not divisible by `p`
+    /// modulo `p^q`. The product over a full period is `1` or `-1`.
+    fn unit_factorial<const N: usize>(&self, n: &Bigi<N>) -> u64 {
+        let (quot, rem) = n.div_rem(&Bigi::<N>::from(self.m.modulo));
+        let res = self.prod[rem.digits[0] as usize];
+        if quot.is_odd() {
+            self.m.mul(res, self.prod[self.prod.len() - 1])
+        } else {
+            res
+        }
+    }
+}
+
+
+const LUCAS_TABLE_LIMIT: u64 = 1 << 16;
+
+
+/// Calculates the binomial coefficient `C(n, k) % p` for prime `p` using
+/// [Lucas' theorem](https://en.wikipedia.org/wiki/Lucas%27s_theorem).
+/// For small `p` it builds a `LucasTable` on every call, so the table
+/// should be created once if many coefficients are needed for the same `p`.
+/// For larger `p` every base `p` digit takes `min(k_i, n_i - k_i)`
+/// modular multiplications.
+/// ```rust
+/// use bigi::{Bigi, binomial_mod};
+///
+/// assert_eq!(
+///     binomial_mod(&Bigi::<4>::from(10), &Bigi::<4>::from(3), &Bigi::<4>::from(7)),
+///     Bigi::<4>::from(1)
+/// );  // C(10, 3) = 120
+/// ```
+pub fn binomial_mod<const N: usize>(n: &Bigi<N>, k: &Bigi<N>,
+                                    p: &Bigi<N>) -> Bigi<N> {
+    if k > n {
+        return Bigi::<N>::from(0);
+    }
+
+    if p.get_order() <= 1 && p.digits[0] < LUCAS_TABLE_LIMIT {
+        return LucasTable::new(p.digits[0]).binomial(n, k);
+    }
+
+    let mut nc = *n;
+    let mut kc = *k;
+    let mut res = Bigi::<N>::from(1) % p;
+
+    while !kc.is_zero() {
+        let (nq, ni) = nc.div_rem(p);
+        let (kq, ki) = kc.div_rem(p);
+        if ki > ni {
+            return Bigi::<N>::from(0);
+        }
+        res = mul_mod(&res, &digit_binomial_mod(&ni, &ki, p), p);
+        nc = nq;
+        kc = kq;
+    }
+
+    res
+}
+
+
+/// Calculates `C(n, k) % p` for `k <= n < p` as the product of the last
+/// `min(k, n - k)` factors of `n!` divided by the factorial of their count.
+fn digit_binomial_mod<const N: usize>(n: &Bigi<N>, k: &Bigi<N>,
+                                      p: &Bigi<N>) -> Bigi<N> {
+    let k = cmp::min(*k, *n - k);
+    let num = range_product_mod(&(*n - &k + 1), &k, p);
+    div_mod(&num, &factorial_mod(&k, p), p)
+}
+
+
+/// Calculates the binomial coefficient `C(n, k) % p^q` for prime `p`.
+/// It builds a `PrimePowerTable` on every call, so the table should be
+/// created once if many coefficients are needed for the same `p^q`.
+/// ```rust
+/// use bigi::{Bigi, binomial_mod_prime_power};
+///
+/// assert_eq!(
+///     binomial_mod_prime_power(&Bigi::<4>::from(10), &Bigi::<4>::from(3), 2, 4),
+///     Bigi::<4>::from(8)
+/// );  // C(10, 3) = 120
+/// ```
+pub fn binomial_mod_prime_power<const N: usize>(n: &Bigi<N>, k: &Bigi<N>,
+                                                p: u64, q: u32) -> Bigi<N> {
+    PrimePowerTable::new(p, q).binomial(n, k)
+}
+
+
+/// Divides `x` by the primes from `factor_base` as many times as possible.
+/// The function returns a pair of the smooth part (the product of the found
+/// factors) and the remaining cofactor, so `x = smooth * cofactor`.
+/// ```rust
+/// use bigi::{Bigi, smooth_part};
+///
+/// let (smooth, cofactor) = smooth_part(&Bigi::<4>::from(2 * 2 * 3 * 5 * 101), &[2, 3, 5, 7]);
+/// assert_eq!(smooth, Bigi::<4>::from(60));
+/// assert_eq!(cofactor, Bigi::<4>::from(101));
+/// ```
+pub fn smooth_part<const N: usize>(x: &Bigi<N>, factor_base: &[u64]
+            ) -> (Bigi<N>, Bigi<N>) {
+    let mut smooth = Bigi::<N>::from(1);
+    let mut cofactor = *x;
+
+    if cofactor.is_zero() {
+        return (smooth, cofactor);
+    }
+
+    for p in factor_base.iter() {
+        loop {
+            let mut quot = cofactor;
+            if quot.div_u64(*p) != 0 {
+                break;
+            }
+            cofactor = quot;
+            smooth *= *p;
+        }
+    }
+
+    (smooth, cofactor)
+}
+
+
+/// Checks if all the prime factors of `x` are not greater than `bound`.
+/// ```rust
+/// use bigi::{Bigi, is_smooth};
+///
+/// assert!(is_smooth(&Bigi::<4>::from(2 * 3 * 3 * 7 * 13), 13));
+/// assert!(!is_smooth(&Bigi::<4>::from(2 * 3 * 3 * 7 * 13), 11));
+/// ```
+pub fn is_smooth<const N: usize>(x: &Bigi<N>, bound: u64) -> bool {
+    let (_, cofactor) = smooth_part(x, &small_primes(bound));
+    cofactor == Bigi::<N>::from(1)
+}
+
+
+/// Sieves the interval `[start, start + length)` by the primes not greater
+/// than `prime_bound`. The function returns a vector of flags, where
+/// the flag at index `i` is `false` if `start + i` has a prime factor not
+/// greater than `prime_bound` (but is not this prime itself) or it is less
+/// than `2`. So the flags set to `true` mark the candidates for primes.
+/// ```rust
+/// use bigi::{Bigi, sieve_interval};
+///
+/// let flags = sieve_interval(&Bigi::<4>::from(20), 10, 5);
+/// assert_eq!(
+///     flags,
+///     vec![false, false, false, true, false, false, false, false, false, true]
+/// );  // 23 and 29
+/// ```
+pub fn sieve_interval<const N: usize>(start: &Bigi<N>, length: usize,
+                                      prime_bound: u64) -> Vec<bool> {
+    let mut flags = vec![true; length];
+
+    // 0 and 1 are not primes
+    if *start < 2 {
+        let size = cmp::min(length, (2 - start.digits[0]) as usize);
+        flags[..size].fill(false);
+    }
+
+    for p in small_primes(prime_bound).iter() {
+        let mut idx = if *start <= *p {
+            // The prime itself is kept
+            (2 * *p - start.digits[0]) as usize
+        } else {
+            ((*p - start.rem_u64(*p)) % *p) as usize
+        };
+        while idx < length {
+            flags[idx] = false;
+            idx += *p as usize;
+        }
+    }
+
+    flags
+}
+
+
+/// Counts the primes not greater than `limit` by
+/// [Lucy Hedgehog's method](https://en.wikipedia.org/wiki/Prime-counting_function#Algorithms_for_evaluating_%CF%80(x))
+/// (the combinatorial sieve of Legendre over the values `limit / k`)
+/// in `O(limit^(3/4))` operations and `O(sqrt(limit))` memory.
+/// ```rust
+/// use bigi::prime_count;
+///
+/// assert_eq!(prime_count(100), 25);
+/// assert_eq!(prime_count(1000000), 78498);
+/// ```
+pub fn prime_count(limit: u64) -> u64 {
+    if limit < 2 {
+        return 0;
+    }
+    let root = sqrt_u64(limit) as usize;
+
+    // small[v] is the count for v <= root, large[i] is the count for
+    // limit / i, initially the numbers from 2 are counted
+    let mut small: Vec<u64> = (0..=root as u64).map(|v| v.saturating_sub(1)).collect();
+    let mut large = vec![0];
+    large.extend((1..=root as u64).map(|i| limit / i - 1));
+
+    for p in 2..=root {
+        if small[p] == small[p - 1] {
+            // p is not prime
+            continue;
+        }
+        let count = small[p - 1];
+        let square = (p * p) as u64;
+
+        // Removing the numbers with the least prime factor p
+        for i in 1..=root {
+            if limit / (i as u64) < square {
+                break;
+            }
+            let below = if i * p <= root {
+                large[i * p]
+            } else {
+                small[(limit / ((i * p) as u64)) as usize]
+            };
+            large[i] -= below - count;
+        }
+        for v in (p * p..=root).rev() {
+            small[v] -= small[v / p] - count;
+        }
+    }
+
+    large[1]
+}
+
+
+/// Gets the `n`-th prime (`nth_prime(1) = 2`). It counts the primes up to
+/// Dusart's lower bound `n * (ln n + ln ln n - 1)` by `prime_count` and
+/// sieves the rest with `sieve_interval`.
+/// ```rust
+/// use bigi::nth_prime;
+///
+/// assert_eq!(nth_prime(1), 2);
+/// assert_eq!(nth_prime(1000), 7919);
+/// ```
+pub fn nth_prime(n: u64) -> u64 {
+    assert!(n > 0, "Primes are counted from 1");
+    if n < 6 {
+        return [2, 3, 5, 7, 11][n as usize - 1];
+    }
+
+    let ln = (n as f64).ln();
+    let mut start = (n as f64 * (ln + ln.ln() - 1.0)) as u64 - 1;
+    let mut count = prime_count(start);
+    loop {
+        let flags = sieve_interval(
+            &Bigi::<1>::from(start + 1), NTH_PRIME_SEGMENT,
+            sqrt_u64(start + NTH_PRIME_SEGMENT as u64)
+        );
+        for (i, flag) in flags.iter().enumerate() {
+            if *flag {
+                count += 1;
+                if count == n {
+                    return start + 1 + i as u64;
+                }
+            }
+        }
+        start += NTH_PRIME_SEGMENT as u64;
+    }
+}
+
+
+/// Integer square root of *u64*.
+fn sqrt_u64(x: u64) -> u64 {
+    let mut r = (x as f64).sqrt() as u128;
+    while r * r > x as u128 {
+        r -= 1;
+    }
+    while (r + 1) * (r + 1) <= x as u128 {
+        r += 1;
+    }
+    r as u64
+}
+
+
+/// Performs [Proth test](https://en.wikipedia.org/wiki/Proth%27s_theorem)
+/// that proves `k * 2^n + 1` prime, where `k` is odd and `k < 2^n`.
+/// Unlike the probabilistic tests the result is exact. The base `a` is
+/// the first small prime such that `(a / x) = -1`, then `x` is prime if and
+/// only if `a^((x - 1) / 2) = -1 (mod x)`.
+/// ```rust
+/// use bigi::{Bigi, proth_test};
+///
+/// assert!(proth_test(&Bigi::<4>::from(5), 3));  // 41
+/// assert!(!proth_test(&Bigi::<4>::from(3), 3));  // 25
+/// assert!(proth_test(&Bigi::<4>::from(3), 189));
+/// ```
+pub fn proth_test<const N: usize>(k: &Bigi<N>, n: usize) -> bool {
+    assert!(k.is_odd(), "k must be odd");
+    assert!(k.bit_length() <= n, "k must be less than 2^n");
+    assert!(k.bit_length() + n < N * 64, "k * 2^n + 1 does not fit");
+
+    let one = Bigi::<N>::from(1);
+    let x = (*k << n) + &one;
+    let minus_one = x - &one;
+    let p = minus_one >> 1;
+
+    for a in small_primes(SMALL_PRIMES_LIMIT).iter().skip(1) {
+        let a = Bigi::<N>::from(*a);
+        if a == x {
+            return true;
+        }
+        // By Proth's theorem any base satisfying the condition proves x prime
+        if legendre_symbol(&a, &x) == -1 {
+            return a.powmod(&p, &x) == minus_one;
+        }
+    }
+
+    // No non-residue among small primes, so x is a perfect square
+    false
+}
+
+
+/// Performs [Pépin's test](https://en.wikipedia.org/wiki/P%C3%A9pin%27s_test)
+/// that proves the Fermat number `F(n) = 2^(2^n) + 1` prime: for `n > 0`
+/// it is prime if and only if `3^((F(n) - 1) / 2) = -1 (mod F(n))`.
+/// ```rust
+/// use bigi::{Bigi, pepin_test};
+///
+/// assert!(pepin_test::<4>(4));  // 65537
+/// assert!(!pepin_test::<4>(5));  // 641 * 6700417
+/// ```
+pub fn pepin_test<const N: usize>(n: usize) -> bool {
+    assert!(n < usize::BITS as usize && (1usize << n) < N * 64,
+            "F(n) does not fit");
+
+    if n == 0 {
+        // F(0) = 3
+        return true;
+    }
+
+    let one = Bigi::<N>::from(1);
+    let x = (one << (1 << n)) + &one;
+    let minus_one = x - &one;
+    Bigi::<N>::from(3).powmod(&(minus_one >> 1), &x) == minus_one
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::bigi;
+    use super::*;
+    use test::Bencher;
+
+    #[test]
+    fn test_quick_prime_check() {
+        assert!(quick_prime_check(&bigi![8; 1]));
+        assert!(quick_prime_check(&bigi![8; 3]));
+        assert!(quick_prime_check(&bigi![8; 233]));
+        assert!(quick_prime_check(&bigi![8; 239]));
+        assert!(!quick_prime_check(&bigi![8; 9]));
+        assert!(!quick_prime_check(&bigi![8; 3 * 233]));
+        assert!(!quick_prime_check(&bigi![8; 233, 1]));  // 2^64 + 233
+        assert!(quick_prime_check(&bigi![8; 239 * 241]));
+        for p in small_primes(QUICK_PRIMES_BOUND)[1..].iter() {
+            assert!(quick_prime_check(&bigi![8; *p]));
+            assert!(!quick_prime_check(&bigi![8; *p * 1009]));
+        }
+        assert_eq!(
+            quick_primorials(),
+            &[16294579238595022365, 7145393598349078859, 6408001374760705163,
+              690862709424854779, 4312024209383942993]
+        );
+    }
+
+    #[test]
+    fn test_small_primes() {
+        assert_eq!(small_primes(0).len(), 0);
+        assert_eq!(small_primes(1).len(), 0);
+        assert_eq!(&small_primes(2)[..], &[2]);
+        assert_eq!(&small_primes(3)[..], &[2, 3]);
+        assert_eq!(&small_primes(10)[..], &[2, 3, 5, 7]);
+        assert_eq!(&small_primes(11)[..], &[2, 3, 5, 7, 11]);
+        assert_eq!(small_primes(1000).len(), 168);
+        assert_eq!(small_primes(65536).len(), 6542);
+        assert_eq!(small_primes(100000).len(), 9592);
+        assert_eq!(&small_primes(100000)[..6542], &small_primes(65536)[..]);
+    }
+
+    #[test]
+    fn test_fermat_test() {
+        assert!(fermat_test(&bigi![8; 29], 100));
+        assert!(fermat_test(&bigi![8; 1009], 100));
+        assert!(!fermat_test(&bigi![8; 1001], 100));
+    }
+
+    #[test]
+    fn test_miller_rabin() {
+        assert!(miller_rabin(&bigi![8; 29], 100));
+        assert!(miller_rabin(&bigi![8; 1009], 100));
+        assert!(!miller_rabin(&bigi![8; 1001], 100));
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn test_miller_rabin_parallel() {
+        assert!(miller_rabin_parallel(&bigi![8; 29], 100));
+        assert!(miller_rabin_parallel(&bigi![8; 1009], 100));
+        assert!(!miller_rabin_parallel(&bigi![8; 1001], 100));
+        assert!(miller_rabin_parallel(&bigi![8; 1009], 0));
+
+        let mut rng = rand::thread_rng();
+        let p = gen_prime::<_, 8>(&mut rng, 256);
+        assert!(miller_rabin_parallel(&p, 64));
+        assert!(!miller_rabin_parallel(&(p * &p), 64));
+    }
+
+    #[test]
+    fn test_miller_rabin_auto() {
+        assert!(miller_rabin_auto(&bigi![8; 29], 128));
+        assert!(!miller_rabin_auto(&bigi![8; 1001], 128));
+
+        let mut rng = rand::thread_rng();
+        let p = gen_prime::<_, 8>(&mut rng, 256);
+        assert!(miller_rabin_auto(&p, 128));
+        assert!(!miller_rabin_auto(&(p * &p), 128));
+    }
+
+    #[test]
+    fn test_miller_rabin_rounds() {
+        assert_eq!(miller_rabin_rounds(16, 80), 40);
+        assert_eq!(miller_rabin_rounds(16, 0), 1);
+        assert_eq!(miller_rabin_rounds(256, 80), 11);
+        assert_eq!(miller_rabin_rounds(512, 80), 6);
+        assert_eq!(miller_rabin_rounds(1024, 80), 3);
+        assert_eq!(miller_rabin_rounds(2048, 80), 2);
+        assert_eq!(miller_rabin_rounds(2048, 128), 3);
+        for bits in [32, 100, 300, 700, 1500, 3000] {
+            assert!(miller_rabin_rounds(bits, 128) >= miller_rabin_rounds(2 * bits, 128));
+        }
+    }
+
+    #[test]
+    fn test_test_prime() {
+        assert_eq!(test_prime(&bigi![8; 1009], 100), Primality::ProbablePrime);
+        match test_prime(&bigi![8; 1001], 100) {
+            Primality::Composite { witness } => {
+                let one = bigi![8; 1];
+                let n = bigi![8; 1000];
+                // 1001 - 1 = 2^3 * 125
+                let mut b = witness.powmod(&bigi![8; 125], &bigi![8; 1001]);
+                assert_ne!(b, one);
+                for _ in 0..3 {
+                    assert_ne!(b, n);
+                    b = b.powmod(&bigi![8; 2], &bigi![8; 1001]);
+                }
+            },
+            Primality::ProbablePrime => panic!("1001 is composite"),
+        }
+    }
+
+    #[test]
+    fn test_gen_prime() {
+        let mut rng = rand::thread_rng();
+
+        assert_eq!(gen_prime::<_, 4>(&mut rng, 128).bit_length(), 128);
+        assert_eq!(gen_prime::<_, 4>(&mut rng, 65).bit_length(), 65);
+        assert_eq!(gen_prime::<_, 4>(&mut rng, 96).bit_length(), 96);
+        assert_eq!(gen_prime::<_, 4>(&mut rng, 33).bit_length(), 33);
+        assert_eq!(gen_prime::<_, 4>(&mut rng, 15).bit_length(), 15);
+        assert_eq!(gen_prime::<_, 4>(&mut rng, 3).bit_length(), 3);
+    }
+
+    #[test]
+    fn test_gen_prime_with_progress() {
+        let mut rng = rand::thread_rng();
+
+        let mut last = None;
+        let p = gen_prime_with_progress::<_, _, 4>(&mut rng, 128, |progress| {
+            last = Some(*progress);
+            ControlFlow::Continue(())
+        });
+        assert_eq!(p.unwrap().bit_length(), 128);
+        let last = last.unwrap();
+        assert_eq!(last.bits, 128);
+        assert!(last.attempts > last.sieved);
+
+        let mut calls = 0;
+        let p = gen_prime_with_progress::<_, _, 4>(&mut rng, 128, |_| {
+            calls += 1;
+            ControlFlow::Break(())
+        });
+        assert_eq!(p, None);
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn test_gen_prime_pair() {
+        let mut rng = rand::thread_rng();
+        let one = bigi![8; 1];
+        let e = bigi![8; 3];
+        for blum in [false, true] {
+            let (p, q) = gen_prime_pair(&mut rng, 128, &e, blum);
+            assert_eq!((p * &q).bit_length(), 256);
+            assert_ne!(p, q);
+            assert_eq!(euclidean(&e, &(p - &one)), one);
+            assert_eq!(euclidean(&e, &(q - &one)), one);
+            if blum {
+                assert_eq!(p.mod_2k(2), bigi![8; 3]);
+                assert_eq!(q.mod_2k(2), bigi![8; 3]);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Exponent must be odd and greater than 1")]
+    fn test_gen_prime_pair_even_exponent() {
+        let mut rng = rand::thread_rng();
+        gen_prime_pair(&mut rng, 128, &bigi![8; 4], false);
+    }
+
+    #[test]
+    #[should_panic(expected = "Exponent must be odd and greater than 1")]
+    fn test_gen_prime_pair_unit_exponent() {
+        let mut rng = rand::thread_rng();
+        gen_prime_pair(&mut rng, 128, &bigi![8; 1], false);
+    }
+
+    #[test]
+    fn test_sqrt_context() {
+        let mut rng = rand::thread_rng();
+        for bits in [64, 128, 200] {
+            for _ in 0..2 {
+                let p = gen_prime::<_, 8>(&mut rng, bits);
+                let ctx = SqrtContext::new(&p);
+                assert_eq!(ctx.prime(), &p);
+                for _ in 0..10 {
+                    let x = Bigi::<8>::gen_random(&mut rng, bits - 1, false);
+                    let n = mul_mod(&x, &x, &p);
+                    let (r1, r2) = ctx.sqrt(&n).unwrap();
+                    assert_eq!(mul_mod(&r1, &r1, &p), n);
+                    assert_eq!(r1 + &r2, p);
+                    assert!(r1 == x || r2 == x);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_sqrts_of_unity() {
+        let cases: Vec<(u64, Vec<(u64, usize)>)> = vec![
+            (2, vec![(2, 1)]),
+            (4, vec![(2, 2)]),
+            (7, vec![(7, 1)]),
+            (8, vec![(2, 3)]),
+            (15, vec![(3, 1), (5, 1)]),
+            (360, vec![(2, 3), (3, 2), (5, 1)]),
+            (1001, vec![(7, 1), (11, 1), (13, 1)]),
+            (3136, vec![(2, 6), (7, 2)]),
+        ];
+        for (n, factors) in cases {
+            let factors: Vec<(Bigi<4>, usize)> = factors.iter()
+                .map(|(p, e)| (bigi![4; *p], *e)).collect();
+            let expected: Vec<Bigi<4>> = (1..n).filter(|x| x * x % n == 1)
+                .map(|x| bigi![4; x]).collect();
+            assert_eq!(sqrts_of_unity(&bigi![4; n], &factors), expected);
+        }
+
+        let mut rng = rand::thread_rng();
+        let p = gen_prime::<_, 4>(&mut rng, 64);
+        let q = gen_prime::<_, 4>(&mut rng, 64);
+        let n = p * &q;
+        let roots = sqrts_of_unity(&n, &[(p, 1), (q, 1)]);
+        assert_eq!(roots.len(), 4);
+        for x in roots.iter() {
+            assert_eq!(mul_mod(x, x, &n), bigi![4; 1]);
+        }
+    }
+
+    #[test]
+    fn test_multiplicative_order() {
+        // (n, factorization of λ(n))
+        let cases: Vec<(u64, Vec<(u64, usize)>)> = vec![
+            (2, vec![]),
+            (15, vec![(2, 2)]),
+            (49, vec![(2, 1), (3, 1), (7, 1)]),
+            (91, vec![(2, 2), (3, 1)]),
+            (360, vec![(2, 2), (3, 1)]),
+            (1001, vec![(2, 2), (3, 1), (5, 1)]),
+        ];
+        for (n, factors) in cases {
+            let factors: Vec<(Bigi<4>, usize)> = factors.iter()
+                .map(|(p, e)| (bigi![4; *p], *e)).collect();
+            let lambda = factors.iter().fold(1, |acc, (p, e)| acc * p.digits[0].pow(*e as u32));
+            for x in (1..n).filter(|x| euclidean(&bigi![4; *x], &bigi![4; n]) == bigi![4; 1]) {
+                let mut k = 1;
+                let mut y = x % n;
+                while y != 1 % n {
+                    y = y * x % n;
+                    k += 1;
+                }
+                assert_eq!(multiplicative_order(&bigi![4; x], &bigi![4; n], &factors),
+                           bigi![4; k]);
+                assert_eq!(is_generator(&bigi![4; x], &bigi![4; n], &factors), k == lambda);
+            }
+            assert!(!is_generator(&bigi![4; 0], &bigi![4; n], &factors));
+        }
+
+        // Primitive roots modulo a prime
+        let p = bigi![4; 1000003];
+        let factors = [(bigi![4; 2], 1), (bigi![4; 3], 1), (bigi![4; 166667], 1)];
+        let roots = (2..50).filter(|x| is_primitive_root(&bigi![4; *x], &p, &factors)).count();
+        assert_eq!(roots, 17);
+    }
+
+    #[test]
+    #[should_panic(expected = "Wrong factorization")]
+    fn test_multiplicative_order_wrong_factorization() {
+        multiplicative_order(&bigi![4; 3], &bigi![4; 91], &[(bigi![4; 2], 2)]);
+    }
+
+    #[test]
+    fn test_cornacchia() {
+        for p in [2u64, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 97, 101, 1009] {
+            for d in 1..p {
+                let brute = (1..p).take_while(|y| d * y * y <= p)
+                    .find(|y| (0..p).any(|x| x * x + d * y * y == p));
+                let res = cornacchia(&bigi![8; d], &bigi![8; p]);
+                match brute {
+                    Some(_) => {
+                        let (x, y) = res.unwrap();
+                        assert_eq!(x * &x + &(bigi![8; d] * &y * &y), bigi![8; p]);
+                    },
+                    None => assert_eq!(res, None),
+                }
+            }
+        }
+
+        // p = x^2 + y^2 for p = 1 (mod 4)
+        let mut rng = rand::thread_rng();
+        let mut p = gen_prime::<_, 8>(&mut rng, 128);
+        while p.mod_2k(2) != bigi![8; 1] {
+            p = gen_prime::<_, 8>(&mut rng, 128);
+        }
+        let (x, y) = cornacchia(&bigi![8; 1], &p).unwrap();
+        assert_eq!(x * &x + &(y * &y), p);
+    }
+
+    #[test]
+    fn test_reduce_wide() {
+        let m = bigi![4; 1000];
+        assert_eq!(reduce_wide(&bigi![4; 0], &bigi![4; 123456], &m), bigi![4; 456]);
+        // 2^256 % 1000 = 936
+        assert_eq!(reduce_wide(&bigi![4; 1], &bigi![4; 0], &m), bigi![4; 936]);
+        // Overflow is greater than the modulus
+        assert_eq!(reduce_wide(&bigi![4; 1001], &bigi![4; 0], &m), bigi![4; 936]);
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..10 {
+            let hi = Bigi::<4>::gen_random(&mut rng, 256, false);
+            let lo = Bigi::<4>::gen_random(&mut rng, 256, false);
+            let m = Bigi::<4>::gen_random(&mut rng, 100, false);
+            let wide = Bigi::<8>::from(&lo) + &(Bigi::<8>::from(&hi) << 256);
+            assert_eq!(Bigi::<8>::from(&reduce_wide(&hi, &lo, &m)),
+                       wide % &Bigi::<8>::from(&m));
+        }
+    }
+
+    #[test]
+    fn test_mul_mod_u64() {
+        let m = bigi![4; 1000];
+        assert_eq!(mul_mod_u64(&bigi![4; 123], 0, &m), bigi![4; 0]);
+        assert_eq!(mul_mod_u64(&bigi![4; 123], 5, &m), bigi![4; 615]);
+        // 2^256 % 1000 = 936
+        assert_eq!(mul_mod_u64(&bigi![4; 0, 0, 0, 1 << 63], 2, &m), bigi![4; 936]);
+        assert_eq!(mul_mod_u64(&Bigi::<4>::MAX, u64::MAX, &bigi![4; 1]), bigi![4; 0]);
+
+        let mut rng = rand::thread_rng();
+        for bits in [1, 64, 100, 255, 256] {
+            let m = Bigi::<4>::gen_random(&mut rng, bits, false) + 1;
+            let x = Bigi::<4>::gen_random(&mut rng, 256, false);
+            for k in [1, 2, 12345, rand::Rng::gen(&mut rng), u64::MAX] {
+                assert_eq!(mul_mod_u64(&x, k, &m), mul_mod(&x, &bigi![4; k], &m));
+            }
+        }
+    }
+
+    #[test]
+    fn test_euclidean() {
+        let mut rng = rand::thread_rng();
+        let mut pairs = vec![];
+        for (bits1, bits2) in [(512, 512), (512, 500), (512, 130), (300, 512), (128, 64),
+                               (512, 0), (0, 512), (65, 64)] {
+            let x = Bigi::<8>::gen_random(&mut rng, bits1, false);
+            let y = Bigi::<8>::gen_random(&mut rng, bits2, false);
+            let z = Bigi::<8>::gen_random(&mut rng, 256, false);
+            pairs.push((x, y));
+            pairs.push((x * &z, y * &z));
+        }
+        // Consecutive Fibonacci numbers make all quotients one
+        let (mut f1, mut f2) = (bigi![8; 1], bigi![8; 1]);
+        while f2.bit_length() < 510 {
+            (f1, f2) = (f2, f1 + &f2);
+        }
+        pairs.push((f2, f1));
+        pairs.push((f1, f2));
+        pairs.push((Bigi::<8>::MAX, Bigi::<8>::MAX - 1));
+        pairs.push((Bigi::<8>::MAX, Bigi::<8>::MAX));
+
+        for (x, y) in pairs {
+            let mut a = x;
+            let mut b = y;
+            while !b.is_zero() {
+                a.divide(&b);
+                mem::swap(&mut a, &mut b);
+            }
+            assert_eq!(euclidean(&x, &y), a);
+            let (g, ra, rb) = euclidean_extended(&x, &y);
+            assert_eq!(g, a);
+            assert_eq!(x * &ra - &(y * &rb), g);
+        }
+    }
+
+    #[test]
+    fn test_is_coprime() {
+        assert!(is_coprime(&bigi![4; 1], &bigi![4; 0]));
+        assert!(is_coprime(&bigi![4; 0], &bigi![4; 1]));
+        assert!(!is_coprime(&bigi![4; 0], &bigi![4; 0]));
+        assert!(!is_coprime(&bigi![4; 7], &bigi![4; 0]));
+        assert!(!is_coprime(&bigi![4; 4], &bigi![4; 6]));
+        assert!(!is_coprime(&bigi![4; 9], &bigi![4; 6]));
+        assert!(is_coprime(&bigi![4; 9], &bigi![4; 8]));
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..20 {
+            let x = Bigi::<4>::gen_random(&mut rng, 30, false);
+            let y = Bigi::<4>::gen_random(&mut rng, 30, false);
+            assert_eq!(is_coprime(&x, &y), euclidean(&x, &y) == bigi![4; 1]);
+        }
+    }
+
+    #[test]
+    fn test_pow2_mod() {
+        let mut rng = rand::thread_rng();
+        let two = bigi![4; 2];
+        for bits in [2, 64, 100, 255, 256] {
+            let m = Bigi::<4>::gen_random(&mut rng, bits, true);
+            for k in [0, 1, 63, 64, 200, 255, 256, 257, 1000, 123456] {
+                assert_eq!(pow2_mod(k, &m), two.powmod(&bigi![4; k as u64], &m));
+            }
+        }
+        assert_eq!(pow2_mod(100, &bigi![4; 1]), bigi![4; 0]);
+    }
+
+    #[test]
+    fn test_inv_mod_2k() {
+        assert_eq!(inv_mod_2k(&bigi![4; 1], 0), bigi![4; 0]);
+        assert_eq!(inv_mod_2k(&bigi![4; 7], 1), bigi![4; 1]);
+        assert_eq!(inv_mod_2k(&bigi![4; 3], 8), bigi![4; 171]);
+        let mut rng = rand::thread_rng();
+        for k in [1, 5, 63, 64, 65, 128, 200, 256] {
+            let mut x = Bigi::<4>::gen_random(&mut rng, 256, false);
+            x.digits[0] |= 1;
+            let y = inv_mod_2k(&x, k);
+            assert!(y.bit_length() <= k);
+            assert_eq!((x * &y).mod_2k(k), bigi![4; 1].mod_2k(k));
+        }
     }
 
     #[test]
@@ -446,6 +2126,187 @@ mod tests {
         assert_eq!(sqrt_mod(&bigi![8; 5], &bigi![8; 29]), Ok((bigi![8; 11], bigi![8; 18])));
         assert_eq!(sqrt_mod(&bigi![8; 8], &bigi![8; 29]), Err("Non-quadratic residue"));
         assert_eq!(sqrt_mod(&bigi![8; 75], &bigi![8; 97]), Ok((bigi![8; 47], bigi![8; 50])));
+        assert_eq!(sqrt_mod(&bigi![8; 0], &bigi![8; 97]), Ok((bigi![8; 0], bigi![8; 0])));
+        // p = 5 (mod 8)
+        for n in 1..101 {
+            let squares: Vec<u64> = (1..101).filter(|x| x * x % 101 == n).collect();
+            match sqrt_mod(&bigi![8; n], &bigi![8; 101]) {
+                Ok((r1, r2)) => assert_eq!(vec![r1.digits[0], r2.digits[0]], squares),
+                Err(_) => assert_eq!(squares.len(), 0),
+            }
+        }
+    }
+
+    #[test]
+    fn test_factorial_mod() {
+        assert_eq!(factorial_mod(&bigi![8; 0], &bigi![8; 13]), bigi![8; 1]);
+        assert_eq!(factorial_mod(&bigi![8; 10], &bigi![8; 13]), bigi![8; 6]);
+        assert_eq!(factorial_mod(&bigi![8; 11], &bigi![8; 13]), bigi![8; 1]);
+        assert_eq!(factorial_mod(&bigi![8; 12], &bigi![8; 13]), bigi![8; 12]);
+        assert_eq!(factorial_mod(&bigi![8; 13], &bigi![8; 13]), bigi![8; 0]);
+        assert_eq!(factorial_mod(&bigi![8; 100], &bigi![8; 1009]), bigi![8; 228]);
+        assert_eq!(factorial_mod(&bigi![8; 1000], &bigi![8; 1009]), bigi![8; 782]);
+    }
+
+    #[test]
+    fn test_binomial_mod() {
+        assert_eq!(binomial_mod(&bigi![8; 10], &bigi![8; 3], &bigi![8; 7]), bigi![8; 1]);
+        assert_eq!(binomial_mod(&bigi![8; 10], &bigi![8; 11], &bigi![8; 7]), bigi![8; 0]);
+        assert_eq!(binomial_mod(&bigi![8; 10], &bigi![8; 0], &bigi![8; 7]), bigi![8; 1]);
+        assert_eq!(binomial_mod(&bigi![8; 1000], &bigi![8; 300], &bigi![8; 13]), bigi![8; 10]);
+        assert_eq!(binomial_mod(&bigi![8; 1000], &bigi![8; 300], &bigi![8; 1009]), bigi![8; 283]);
+
+        // Large prime that does not use a table
+        let p = bigi![8; 1000003];
+        assert_eq!(binomial_mod(&bigi![8; 2000000], &bigi![8; 3], &p), bigi![8; 999947]);
+        assert_eq!(binomial_mod(&bigi![8; 3000009], &bigi![8; 1000003], &p), bigi![8; 3]);
+
+        // Large digits with small k
+        let p = bigi![8; (1 << 61) - 1];
+        let n = bigi![8; 1 << 60];
+        let expected = (n * &(n - 1) * &(n - 2) / &bigi![8; 6]) % &p;
+        assert_eq!(binomial_mod(&n, &bigi![8; 3], &p), expected);
+        assert_eq!(binomial_mod(&n, &(n - 3), &p), expected);
+    }
+
+    #[test]
+    fn test_binomial_mod_prime_power() {
+        // Exact coefficients C(n, k) for n < 200
+        let mut rows = vec![vec![bigi![8; 1]]];
+        for n in 1..200 {
+            let prev: &Vec<Bigi<8>> = &rows[n - 1];
+            let mut row = vec![bigi![8; 1]; n + 1];
+            for k in 1..n {
+                row[k] = prev[k - 1] + &prev[k];
+            }
+            rows.push(row);
+        }
+
+        for (p, q) in [(2, 1), (2, 3), (2, 7), (3, 1), (3, 4), (5, 2), (7, 3)] {
+            let table = PrimePowerTable::new(p, q);
+            let m = bigi![8; p.pow(q)];
+            for n in [0, 1, 5, 17, 64, 100, 127, 198, 199] {
+                for (k, c) in rows[n].iter().enumerate() {
+                    assert_eq!(table.binomial(&bigi![8; n as u64], &bigi![8; k as u64]),
+                               *c % &m);
+                }
+            }
+            assert_eq!(table.binomial(&bigi![8; 5], &bigi![8; 6]), bigi![8; 0]);
+        }
+
+        // Agrees with Lucas' theorem for q = 1
+        let mut rng = rand::thread_rng();
+        let table = PrimePowerTable::new(1009, 1);
+        let n = Bigi::<4>::gen_random(&mut rng, 256, false);
+        let k = Bigi::<4>::gen_random(&mut rng, 255, false);
+        assert_eq!(table.binomial(&n, &k), LucasTable::new(1009).binomial(&n, &k));
+        assert_eq!(binomial_mod_prime_power(&bigi![4; 1000], &bigi![4; 300], 5, 1),
+                   binomial_mod(&bigi![4; 1000], &bigi![4; 300], &bigi![4; 5]));
+    }
+
+    #[test]
+    fn test_lucas_table() {
+        let table = LucasTable::new(2);
+        assert_eq!(table.binomial(&bigi![4; 5], &bigi![4; 1]), bigi![4; 1]);
+        assert_eq!(table.binomial(&bigi![4; 5], &bigi![4; 2]), bigi![4; 0]);
+        let table = LucasTable::new(1009);
+        assert_eq!(table.binomial(&bigi![4; 0, 1], &bigi![4; 1 << 40]), bigi![4; 788]);
+    }
+
+    #[test]
+    fn test_smooth_part() {
+        assert_eq!(smooth_part(&bigi![8; 1], &[2, 3]), (bigi![8; 1], bigi![8; 1]));
+        assert_eq!(smooth_part(&bigi![8; 0], &[2, 3]), (bigi![8; 1], bigi![8; 0]));
+        assert_eq!(smooth_part(&bigi![8; 1024], &[2, 3]), (bigi![8; 1024], bigi![8; 1]));
+        assert_eq!(smooth_part(&bigi![8; 1001], &[2, 3, 5]), (bigi![8; 1], bigi![8; 1001]));
+        assert_eq!(smooth_part(&bigi![8; 0, 3], &[2, 3]), (bigi![8; 0, 3], bigi![8; 1]));
+    }
+
+    #[test]
+    fn test_is_smooth() {
+        assert!(is_smooth(&bigi![8; 1], 2));
+        assert!(is_smooth(&bigi![8; 1000], 5));
+        assert!(!is_smooth(&bigi![8; 1000], 3));
+        assert!(!is_smooth(&bigi![8; 1009], 1000));
+        assert!(is_smooth(&bigi![8; 1009], 1009));
+    }
+
+    #[test]
+    fn test_sieve_interval() {
+        assert_eq!(sieve_interval(&bigi![8; 0], 8, 5),
+                   vec![false, false, true, true, false, true, false, true]);
+        assert_eq!(sieve_interval(&bigi![8; 7], 0, 5), vec![]);
+
+        let flags = sieve_interval(&bigi![8; 1000], 200, 31);
+        for (i, flag) in flags.iter().enumerate() {
+            let x = bigi![8; 1000 + i as u64];
+            assert_eq!(*flag, smooth_part(&x, &small_primes(31)).0 == bigi![8; 1]);
+        }
+
+        // 2^64 + 1 = 274177 * 67280421310721 has no factors up to 1000
+        let flags = sieve_interval(&bigi![8; 1, 1], 20, 1000);
+        let candidates: Vec<usize> = flags.iter().enumerate()
+            .filter(|(_, flag)| **flag).map(|(i, _)| i).collect();
+        assert_eq!(candidates, vec![0, 2, 6, 12]);
+    }
+
+    #[test]
+    fn test_prime_count() {
+        for limit in [0, 1, 2, 3, 4, 10, 97, 100, 1000, 65536, 100000] {
+            assert_eq!(prime_count(limit), small_primes(limit).len() as u64);
+        }
+        assert_eq!(prime_count(1000000000), 50847534);
+        assert_eq!(prime_count(10000000000), 455052511);
+    }
+
+    #[test]
+    fn test_nth_prime() {
+        let primes = small_primes(100000);
+        for n in (1..primes.len()).step_by(97).chain(1..20) {
+            assert_eq!(nth_prime(n as u64), primes[n - 1]);
+        }
+        assert_eq!(nth_prime(1000000), 15485863);
+        assert_eq!(nth_prime(50847534), 999999937);
+    }
+
+    #[test]
+    fn test_sqrt_u64() {
+        for x in [0, 1, 2, 3, 4, 15, 16, 17, u32::MAX as u64, u64::MAX] {
+            let r = sqrt_u64(x) as u128;
+            assert!(r * r <= x as u128 && (r + 1) * (r + 1) > x as u128);
+        }
+    }
+
+    #[test]
+    fn test_proth_test() {
+        assert!(proth_test(&bigi![8; 1], 1));  // 3
+        assert!(proth_test(&bigi![8; 1], 2));  // 5
+        assert!(proth_test(&bigi![8; 5], 3));  // 41
+        assert!(proth_test(&bigi![8; 7], 4));  // 113
+        assert!(!proth_test(&bigi![8; 3], 3));  // 25
+        assert!(!proth_test(&bigi![8; 9], 4));  // 145
+        assert!(!proth_test(&bigi![8; 13], 4));  // 209
+        for n in 2..300 {
+            let expected = [1, 2, 5, 6, 8, 12, 18, 30, 36, 41, 66, 189, 201, 209,
+                            276].contains(&n);
+            assert_eq!(proth_test(&bigi![8; 3], n), expected);
+        }
+    }
+
+    #[test]
+    fn test_pepin_test() {
+        for n in 0..5 {
+            assert!(pepin_test::<8>(n));
+        }
+        for n in 5..9 {
+            assert!(!pepin_test::<8>(n));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "F(n) does not fit")]
+    fn test_pepin_test_too_large() {
+        pepin_test::<4>(64);
     }
 
     #[bench]
@@ -457,6 +2318,11 @@ mod tests {
         });
     }
 
+    #[bench]
+    fn bench_small_primes_1000000(bencher: &mut Bencher) {
+        bencher.iter(|| small_primes(1000000));
+    }
+
     #[bench]
     fn bench_fermat_test_256(bencher: &mut Bencher) {
         let mut rng = rand::thread_rng();
@@ -475,6 +2341,14 @@ mod tests {
         });
     }
 
+    #[bench]
+    #[cfg(feature = "rayon")]
+    fn bench_miller_rabin_parallel_1024(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let p = gen_prime::<_, 32>(&mut rng, 1024);
+        bencher.iter(|| miller_rabin_parallel(&p, 64));
+    }
+
     #[bench]
     fn bench_gen_prime_32(bencher: &mut Bencher) {
         let mut rng = rand::thread_rng();
@@ -499,6 +2373,13 @@ mod tests {
         bencher.iter(|| gen_prime::<_, 4>(&mut rng, 256));
     }
 
+    #[bench]
+    fn bench_gen_prime_pair_256(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let e = Bigi::<8>::from(65537);
+        bencher.iter(|| gen_prime_pair(&mut rng, 256, &e, false));
+    }
+
     #[bench]
     fn bench_euclidean_256(bencher: &mut Bencher) {
         let mut rng = rand::thread_rng();
@@ -507,6 +2388,14 @@ mod tests {
         bencher.iter(|| euclidean(&x, &y));
     }
 
+    #[bench]
+    fn bench_euclidean_4096(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let x = Bigi::<64>::gen_random(&mut rng, 4096, false);
+        let y = Bigi::<64>::gen_random(&mut rng, 4096, false);
+        bencher.iter(|| euclidean(&x, &y));
+    }
+
     #[bench]
     fn bench_euclidean_extended_256(bencher: &mut Bencher) {
         let mut rng = rand::thread_rng();
@@ -542,6 +2431,14 @@ mod tests {
         bencher.iter(|| mul_mod(&x, &y, &m));
     }
 
+    #[bench]
+    fn bench_mul_mod_u64_256(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let m = gen_prime::<_, 4>(&mut rng, 256);
+        let x = gen_prime::<_, 4>(&mut rng, 256) % &m;
+        bencher.iter(|| mul_mod_u64(&x, 0x123456789abcdef, &m));
+    }
+
     #[bench]
     fn bench_inv_mod_256(bencher: &mut Bencher) {
         let mut rng = rand::thread_rng();
@@ -578,4 +2475,53 @@ mod tests {
             let _ = sqrt_mod(&x, &p);
         });
     }
+
+    #[bench]
+    fn bench_factorial_mod_10000(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let p = gen_prime::<_, 4>(&mut rng, 256);
+        bencher.iter(|| factorial_mod(&Bigi::<4>::from(10000), &p));
+    }
+
+    #[bench]
+    fn bench_binomial_mod_256(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let n = Bigi::<4>::gen_random(&mut rng, 256, false);
+        let k = Bigi::<4>::gen_random(&mut rng, 255, false);
+        let table = LucasTable::new(65521);
+        bencher.iter(|| table.binomial(&n, &k));
+    }
+
+    #[bench]
+    fn bench_binomial_mod_prime_power_256(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let n = Bigi::<4>::gen_random(&mut rng, 256, false);
+        let k = Bigi::<4>::gen_random(&mut rng, 255, false);
+        let table = PrimePowerTable::new(3, 10);
+        bencher.iter(|| table.binomial(&n, &k));
+    }
+
+    #[bench]
+    fn bench_pow2_mod_256(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let m = Bigi::<4>::gen_random(&mut rng, 256, true);
+        bencher.iter(|| pow2_mod(512, &m));
+    }
+
+    #[bench]
+    fn bench_prime_count_1e9(bencher: &mut Bencher) {
+        bencher.iter(|| prime_count(1000000000));
+    }
+
+    #[bench]
+    fn bench_sieve_interval_256(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let x = Bigi::<4>::gen_random(&mut rng, 256, true);
+        bencher.iter(|| sieve_interval(&x, 4096, 10000));
+    }
+
+    #[bench]
+    fn bench_proth_test_256(bencher: &mut Bencher) {
+        bencher.iter(|| proth_test(&bigi![4; 3], 209));
+    }
 }
@@ -49,6 +49,25 @@ impl<const N: usize> Bigi<N> {
 
         res
     }
+
+    /// Generates a random integer of exactly `bits` bits with the two most
+    /// significant bits set, so it is from `3 << (bits - 2)` to
+    /// `(1 << bits) - 1`. The product of two such integers is exactly
+    /// `2 * bits` bits long, that is required for RSA primes.
+    /// ```rust
+    /// use bigi::Bigi;
+    ///
+    /// let mut rng = rand::thread_rng();
+    /// let z = Bigi::<8>::gen_random_rsa(&mut rng, 256);
+    /// assert_eq!(z.bit_length(), 256);
+    /// assert!(z.get_bit(254));
+    /// ```
+    pub fn gen_random_rsa<R: Rng + ?Sized>(rng: &mut R, bits: usize) -> Self {
+        assert!(bits >= 2);
+        let mut res = Self::gen_random(rng, bits, true);
+        res.set_bit(bits - 2);
+        res
+    }
 }
 
 
@@ -65,6 +84,22 @@ mod tests {
         assert!(x.digits[3] > 0);
     }
 
+    #[test]
+    fn test_gen_random_rsa() {
+        let mut rng = rand::thread_rng();
+        for bits in [2, 3, 64, 65, 128, 200] {
+            let x = Bigi::<4>::gen_random_rsa(&mut rng, bits);
+            assert_eq!(x.bit_length(), bits);
+            assert!(x.get_bit(bits - 2));
+            let y = Bigi::<4>::gen_random_rsa(&mut rng, bits);
+            let (lo, hi) = x.multiply_overflowing(&y);
+            let product = Bigi::<8>::from_limbs_iter(
+                lo.digits.iter().chain(hi.digits.iter()).copied()
+            );
+            assert_eq!(product.bit_length(), 2 * bits);
+        }
+    }
+
     #[bench]
     fn bench_gen_random_256(bencher: &mut Bencher) {
         let mut rng = rand::thread_rng();
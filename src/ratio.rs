@@ -0,0 +1,301 @@
+//! This module implements rational numbers over `Bigi` as the type
+//! **BigiRatio**. The fraction is always kept normalized: the numerator and
+//! the denominator are coprime and the denominator is non-zero.
+//!
+//! ```rust
+//! use bigi::{Bigi, BigiRatio};
+//!
+//! let a = BigiRatio::new(&Bigi::<4>::from(1), &Bigi::<4>::from(6));
+//! let b = BigiRatio::new(&Bigi::<4>::from(1), &Bigi::<4>::from(3));
+//! assert_eq!(a + &b, BigiRatio::new(&Bigi::<4>::from(1), &Bigi::<4>::from(2)));
+//! ```
+
+use std::{ops, cmp};
+use crate::base::Bigi;
+use crate::prime::euclidean;
+
+
+/// Type for non-negative rational numbers.
+#[derive(Debug, Clone, Copy)]
+pub struct BigiRatio<const N: usize> {
+    numer: Bigi<N>,
+    denom: Bigi<N>,
+}
+
+
+impl<const N: usize> BigiRatio<N> {
+    /// Creates a normalized fraction `numer / denom`.
+    /// ```rust
+    /// use bigi::{Bigi, BigiRatio};
+    ///
+    /// let r = BigiRatio::new(&Bigi::<4>::from(10), &Bigi::<4>::from(4));
+    /// assert_eq!(r.numer(), &Bigi::<4>::from(5));
+    /// assert_eq!(r.denom(), &Bigi::<4>::from(2));
+    /// ```
+    pub fn new(numer: &Bigi<N>, denom: &Bigi<N>) -> Self {
+        assert!(!denom.is_zero(), "Zero denominator");
+        let mut res = Self { numer: *numer, denom: *denom };
+        res.reduce();
+        res
+    }
+
+    /// Gets the numerator.
+    pub fn numer(&self) -> &Bigi<N> {
+        &self.numer
+    }
+
+    /// Gets the denominator.
+    pub fn denom(&self) -> &Bigi<N> {
+        &self.denom
+    }
+
+    /// Checks if the fraction is zero.
+    pub fn is_zero(&self) -> bool {
+        self.numer.is_zero()
+    }
+
+    /// Checks if the fraction is an integer.
+    pub fn is_integer(&self) -> bool {
+        self.denom == Bigi::<N>::from(1)
+    }
+
+    /// Gets the reciprocal fraction `denom / numer`.
+    /// ```rust
+    /// use bigi::{Bigi, BigiRatio};
+    ///
+    /// let r = BigiRatio::new(&Bigi::<4>::from(2), &Bigi::<4>::from(7));
+    /// assert_eq!(r.recip(), BigiRatio::new(&Bigi::<4>::from(7), &Bigi::<4>::from(2)));
+    /// ```
+    pub fn recip(&self) -> Self {
+        assert!(!self.numer.is_zero(), "Zero denominator");
+        Self { numer: self.denom, denom: self.numer }
+    }
+
+    /// Gets the integer part of the fraction (rounded down).
+    /// ```rust
+    /// use bigi::{Bigi, BigiRatio};
+    ///
+    /// let r = BigiRatio::new(&Bigi::<4>::from(22), &Bigi::<4>::from(7));
+    /// assert_eq!(r.floor(), Bigi::<4>::from(3));
+    /// ```
+    pub fn floor(&self) -> Bigi<N> {
+        self.numer / &self.denom
+    }
+
+    /// Gets the integer part of the fraction rounded up.
+    pub fn ceil(&self) -> Bigi<N> {
+        self.numer.div_ceil(&self.denom)
+    }
+
+    fn reduce(&mut self) {
+        let gcd = euclidean(&self.numer, &self.denom);
+        if gcd != Bigi::<N>::from(1) {
+            self.numer = self.numer.exact_div(&gcd);
+            self.denom = self.denom.exact_div(&gcd);
+        }
+    }
+}
+
+
+impl<const N: usize> From<&Bigi<N>> for BigiRatio<N> {
+    fn from(a: &Bigi<N>) -> Self {
+        Self { numer: *a, denom: Bigi::<N>::from(1) }
+    }
+}
+
+
+impl<const N: usize> ops::Add<&BigiRatio<N>> for BigiRatio<N> {
+    type Output = BigiRatio<N>;
+
+    fn add(self, other: &BigiRatio<N>) -> BigiRatio<N> {
+        let mut res = self;
+        res += other;
+        res
+    }
+}
+
+
+impl<const N: usize> ops::AddAssign<&BigiRatio<N>> for BigiRatio<N> {
+    fn add_assign(&mut self, other: &BigiRatio<N>) {
+        let gcd = euclidean(&self.denom, &other.denom);
+        let k1 = other.denom.exact_div(&gcd);
+        let k2 = self.denom.exact_div(&gcd);
+        self.numer = self.numer * &k1 + &(other.numer * &k2);
+        self.denom *= &k1;
+        self.reduce();
+    }
+}
+
+
+impl<const N: usize> ops::Sub<&BigiRatio<N>> for BigiRatio<N> {
+    type Output = BigiRatio<N>;
+
+    fn sub(self, other: &BigiRatio<N>) -> BigiRatio<N> {
+        let mut res = self;
+        res -= other;
+        res
+    }
+}
+
+
+impl<const N: usize> ops::SubAssign<&BigiRatio<N>> for BigiRatio<N> {
+    fn sub_assign(&mut self, other: &BigiRatio<N>) {
+        let gcd = euclidean(&self.denom, &other.denom);
+        let k1 = other.denom.exact_div(&gcd);
+        let k2 = self.denom.exact_div(&gcd);
+        let subtrahend = other.numer * &k2;
+        self.numer *= &k1;
+        assert!(self.numer >= subtrahend, "Negative difference");
+        self.numer -= &subtrahend;
+        self.denom *= &k1;
+        self.reduce();
+    }
+}
+
+
+impl<const N: usize> ops::Mul<&BigiRatio<N>> for BigiRatio<N> {
+    type Output = BigiRatio<N>;
+
+    fn mul(self, other: &BigiRatio<N>) -> BigiRatio<N> {
+        let mut res = self;
+        res *= other;
+        res
+    }
+}
+
+
+impl<const N: usize> ops::MulAssign<&BigiRatio<N>> for BigiRatio<N> {
+    fn mul_assign(&mut self, other: &BigiRatio<N>) {
+        if self.is_zero() || other.is_zero() {
+            self.numer = Bigi::<N>::from(0);
+            self.denom = Bigi::<N>::from(1);
+            return;
+        }
+        // Cross reduction keeps the intermediate products small
+        let g1 = euclidean(&self.numer, &other.denom);
+        let g2 = euclidean(&other.numer, &self.denom);
+        self.numer = self.numer.exact_div(&g1) * &other.numer.exact_div(&g2);
+        self.denom = self.denom.exact_div(&g2) * &other.denom.exact_div(&g1);
+    }
+}
+
+
+impl<const N: usize> ops::Div<&BigiRatio<N>> for BigiRatio<N> {
+    type Output = BigiRatio<N>;
+
+    fn div(self, other: &BigiRatio<N>) -> BigiRatio<N> {
+        let mut res = self;
+        res /= other;
+        res
+    }
+}
+
+
+impl<const N: usize> ops::DivAssign<&BigiRatio<N>> for BigiRatio<N> {
+    fn div_assign(&mut self, other: &BigiRatio<N>) {
+        ops::MulAssign::mul_assign(self, &other.recip());
+    }
+}
+
+
+impl<const N: usize> cmp::PartialEq for BigiRatio<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.numer == other.numer && self.denom == other.denom
+    }
+}
+
+
+impl<const N: usize> cmp::PartialOrd for BigiRatio<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        // Comparing a * d and c * b as double width integers
+        let (lo1, hi1) = self.numer.multiply_overflowing(&other.denom);
+        let (lo2, hi2) = other.numer.multiply_overflowing(&self.denom);
+        match hi1.partial_cmp(&hi2) {
+            Some(cmp::Ordering::Equal) => lo1.partial_cmp(&lo2),
+            ord => ord,
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::bigi;
+    use super::*;
+    use test::Bencher;
+
+    #[test]
+    fn test_new() {
+        let r = BigiRatio::new(&bigi![4; 12], &bigi![4; 18]);
+        assert_eq!(r.numer(), &bigi![4; 2]);
+        assert_eq!(r.denom(), &bigi![4; 3]);
+
+        let r = BigiRatio::new(&bigi![4; 0], &bigi![4; 18]);
+        assert_eq!(r.numer(), &bigi![4; 0]);
+        assert_eq!(r.denom(), &bigi![4; 1]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_new_zero_denom() {
+        BigiRatio::new(&bigi![4; 1], &bigi![4; 0]);
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let a = BigiRatio::new(&bigi![4; 3], &bigi![4; 4]);
+        let b = BigiRatio::new(&bigi![4; 5], &bigi![4; 6]);
+        assert_eq!(a + &b, BigiRatio::new(&bigi![4; 19], &bigi![4; 12]));
+        assert_eq!(b - &a, BigiRatio::new(&bigi![4; 1], &bigi![4; 12]));
+        assert_eq!(a * &b, BigiRatio::new(&bigi![4; 5], &bigi![4; 8]));
+        assert_eq!(a / &b, BigiRatio::new(&bigi![4; 9], &bigi![4; 10]));
+        assert_eq!(a - &a, BigiRatio::from(&bigi![4; 0]));
+        assert!((a * &a.recip()).is_integer());
+    }
+
+    #[test]
+    #[should_panic(expected = "Negative difference")]
+    fn test_sub_negative() {
+        let a = BigiRatio::new(&bigi![4; 3], &bigi![4; 4]);
+        let b = BigiRatio::new(&bigi![4; 5], &bigi![4; 6]);
+        let _ = a - &b;
+    }
+
+    #[test]
+    fn test_floor_ceil() {
+        let r = BigiRatio::new(&bigi![4; 22], &bigi![4; 7]);
+        assert_eq!(r.floor(), bigi![4; 3]);
+        assert_eq!(r.ceil(), bigi![4; 4]);
+        let r = BigiRatio::from(&bigi![4; 5]);
+        assert_eq!(r.floor(), bigi![4; 5]);
+        assert_eq!(r.ceil(), bigi![4; 5]);
+    }
+
+    #[test]
+    fn test_cmp() {
+        let a = BigiRatio::new(&bigi![2; 1], &bigi![2; 2]);
+        let b = BigiRatio::new(&bigi![2; 2], &bigi![2; 5]);
+        assert!(a > b);
+        assert!(b < a);
+        assert!(a <= a);
+
+        // Cross products overflow the width
+        let c = BigiRatio::new(&bigi![2; 1, 1 << 63], &bigi![2; 3, 1 << 62]);
+        let d = BigiRatio::new(&bigi![2; 1, 1 << 62], &bigi![2; 3, 1 << 63]);
+        assert!(c > d);
+    }
+
+    #[bench]
+    fn bench_add_256(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let a = BigiRatio::new(
+            &Bigi::<8>::gen_random(&mut rng, 128, false),
+            &Bigi::<8>::gen_random(&mut rng, 128, true)
+        );
+        let b = BigiRatio::new(
+            &Bigi::<8>::gen_random(&mut rng, 128, false),
+            &Bigi::<8>::gen_random(&mut rng, 128, true)
+        );
+        bencher.iter(|| a + &b);
+    }
+}
@@ -0,0 +1,104 @@
+//! This module implements power-on known-answer self tests (enabled by
+//! the feature `selftest`). They run the basic algorithms over fixed 256-bit
+//! vectors and compare the results with the embedded answers, so a broken
+//! build or platform is detected before any real data is processed.
+//!
+//! ```rust
+//! assert_eq!(bigi::selftest(), Ok(()));
+//! ```
+
+use crate::base::Bigi;
+use crate::prime::sqrt_mod;
+use crate::montgomery::MontgomeryAlg;
+
+
+const A: &str = "0x6A09E667F3BCC908BB67AE8584CAA73B3C6EF372FE94F82BA54FF53A5F1D36F1";
+const B: &str = "0x510E527FADE682D19B05688C2B3E6C1F1F83D9ABFB41BD6B5BE0CD19137E2179";
+/// The prime `2^255 - 19`.
+const P: &str = "0x7FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFED";
+
+const SUM: &str = "0xBB1838E7A1A34BDA566D1711B009135A5BF2CD1EF9D6B5970130C253729B586A";
+const PRODUCT: &str = "0x2193109D8C8CD55C3F3A455BDDE48B4FE28D1DFF1BDDE61AAF5453FC40A7B86B\
+                       6220346F0C4BF9478B7CCB88D1485E8E4280E091FB2F6A2EE5EAC4D7A68208E9";
+/// `A / (B >> 100)` and `A % (B >> 100)`.
+const QUOTIENT: &str = "0x14EE73B86A17CC5C975B1724F0";
+const REMAINDER: &str = "0x45FC242D0B0342F76060AEA477CB05756E3CE91";
+/// `A^B % P`.
+const POWER: &str = "0x29B603AB21E59F9FE880B98DD7C55F892B5071609E8122064E4EE0140968D9E4";
+/// Square roots of `A^2 % P`.
+const SQUARE: &str = "0x5271DE5721DE5E64B89E30FA627A883963244BDD6CBE14A81444EE5C791F1E78";
+const ROOT: &str = "0x15F619980C4336F74498517A7B3558C4C3910C8D016B07D45AB00AC5A0E2C8FC";
+/// `A * B * 2^(-256) % P` (Montgomery multiplication).
+const MONTGOMERY_PRODUCT: &str = "0x13508B4227D2337FD05F3D3DC873EC1DBBE159CD0E443300F8BF8F0F66B89DB7";
+/// `A^65537 % P`.
+const MONTGOMERY_POWER: &str = "0x6F73E36421845F31B88F4ABBEDD0B9ECF0EC775A3C1948A41DD1139C4508EF07";
+
+
+/// Runs all the known-answer tests. It returns the name of the first
+/// failed test as an error.
+pub fn selftest() -> Result<(), &'static str> {
+    let a = Bigi::<4>::from_hex(A);
+    let b = Bigi::<4>::from_hex(B);
+    let p = Bigi::<4>::from_hex(P);
+
+    // Addition and multiplication
+    check(a + &b == Bigi::<4>::from_hex(SUM), "add")?;
+    let (lo, hi) = a.multiply_overflowing(&b);
+    let product = Bigi::<8>::from(&lo) + &(Bigi::<8>::from(&hi) << 256);
+    check(product == Bigi::<8>::from_hex(PRODUCT), "mul")?;
+
+    // Division
+    let (quot, rem) = a.div_rem(&(b >> 100));
+    check(quot == Bigi::<4>::from_hex(QUOTIENT), "divide")?;
+    check(rem == Bigi::<4>::from_hex(REMAINDER), "divide")?;
+
+    // Modular exponentiation and square root
+    check(a.powmod(&b, &p) == Bigi::<4>::from_hex(POWER), "powmod")?;
+    let root = Bigi::<4>::from_hex(ROOT);
+    check(
+        sqrt_mod(&Bigi::<4>::from_hex(SQUARE), &p) == Ok((root, p - &root)),
+        "sqrt_mod"
+    )?;
+    check(p - &root == a, "sqrt_mod")?;
+
+    // Montgomery arithmetics needs double width for the products
+    let (a, b, p) = (Bigi::<8>::from(&a), Bigi::<8>::from(&b), Bigi::<8>::from(&p));
+    let mgr = MontgomeryAlg::new(256, &p);
+    check(mgr.mul(&a, &b) == Bigi::<8>::from_hex(MONTGOMERY_PRODUCT), "montgomery")?;
+    let power = mgr.from_repr(&mgr.powmod(&mgr.to_repr(&a), &Bigi::<8>::from(65537)));
+    check(power == Bigi::<8>::from_hex(MONTGOMERY_POWER), "montgomery")?;
+
+    Ok(())
+}
+
+
+fn check(passed: bool, name: &'static str) -> Result<(), &'static str> {
+    if passed {
+        Ok(())
+    } else {
+        Err(name)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test::Bencher;
+
+    #[test]
+    fn test_selftest() {
+        assert_eq!(selftest(), Ok(()));
+    }
+
+    #[test]
+    fn test_check() {
+        assert_eq!(check(true, "add"), Ok(()));
+        assert_eq!(check(false, "add"), Err("add"));
+    }
+
+    #[bench]
+    fn bench_selftest(bencher: &mut Bencher) {
+        bencher.iter(selftest);
+    }
+}
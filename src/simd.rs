@@ -0,0 +1,261 @@
+//! This module implements vectorized addition, subtraction, comparison and
+//! multiplication (enabled by the feature `simd`) for the integers of 8 and 16 digits that
+//! are common in cryptography. Every function returns `None` for other
+//! sizes, so the portable loops are used instead. The vector code is faster
+//! only if the target has wide vector registers, so the crate should be
+//! built with `-C target-cpu=native` or at least `+avx2`.
+//!
+//! The carries of addition and subtraction are resolved for all the lanes
+//! at once: the lanes that generate a carry and the lanes that propagate
+//! it form two bit masks `g` and `p`, then the lanes that receive a carry
+//! are `((g << 1) + p) ^ p`, the same as in the ordinary addition of bits.
+//!
+//! There is no widening multiplication of 64-bit lanes, so the schoolbook
+//! multiplication works on the 32-bit halves of the digits. It needs four
+//! times more multiplications than the scalar loops and is faster only with
+//! AVX-512 (about 95 ns against 110 ns for `Bigi<8>` and 360 ns against
+//! 520 ns for `Bigi<16>`), so `widening_mul` uses it only if the target has
+//! `avx512f`.
+
+use std::cmp;
+use std::simd::prelude::*;
+
+
+/// Adds `b` to `a` and returns the carry from the highest digit.
+pub(crate) fn add_assign<const N: usize>(a: &mut [u64; N], b: &[u64; N]
+            ) -> Option<bool> {
+    match N {
+        8 => Some(add_lanes::<8>(as_lanes_mut(a), as_lanes(b))),
+        16 => Some(add_lanes::<16>(as_lanes_mut(a), as_lanes(b))),
+        _ => None,
+    }
+}
+
+
+/// Subtracts `b` from `a` and returns the borrow from the highest digit.
+pub(crate) fn sub_assign<const N: usize>(a: &mut [u64; N], b: &[u64; N]
+            ) -> Option<bool> {
+    match N {
+        8 => Some(sub_lanes::<8>(as_lanes_mut(a), as_lanes(b))),
+        16 => Some(sub_lanes::<16>(as_lanes_mut(a), as_lanes(b))),
+        _ => None,
+    }
+}
+
+
+/// Full product of `a` and `b` as the low and the high halves.
+#[cfg_attr(not(target_feature = "avx512f"), allow(dead_code))]
+pub(crate) fn widening_mul<const N: usize>(a: &[u64; N], b: &[u64; N]
+            ) -> Option<[[u64; N]; 2]> {
+    let mut product = [[0; N]; 2];
+    let res = product.as_flattened_mut();
+    match N {
+        8 => mul_lanes::<16>(a, b, res),
+        16 => mul_lanes::<32>(a, b, res),
+        _ => return None,
+    }
+    Some(product)
+}
+
+
+/// Compares the integers given by their digits.
+pub(crate) fn cmp<const N: usize>(a: &[u64; N], b: &[u64; N]
+            ) -> Option<cmp::Ordering> {
+    match N {
+        8 => Some(cmp_lanes::<8>(as_lanes(a), as_lanes(b))),
+        16 => Some(cmp_lanes::<16>(as_lanes(a), as_lanes(b))),
+        _ => None,
+    }
+}
+
+
+fn as_lanes<const N: usize, const L: usize>(a: &[u64; N]) -> &[u64; L] {
+    a.as_slice().try_into().unwrap()
+}
+
+
+fn as_lanes_mut<const N: usize, const L: usize>(a: &mut [u64; N]) -> &mut [u64; L] {
+    a.as_mut_slice().try_into().unwrap()
+}
+
+
+fn add_lanes<const L: usize>(a: &mut [u64; L], b: &[u64; L]) -> bool {
+    let x = Simd::from_array(*a);
+    let sum = x + Simd::from_array(*b);
+    let g = sum.simd_lt(x).to_bitmask() as u128;
+    let p = sum.simd_eq(Simd::splat(u64::MAX)).to_bitmask() as u128;
+    let carries = ((g << 1) + p) ^ p;
+    let carry_in = Mask::<i64, L>::from_bitmask(carries as u64);
+    *a = (sum + carry_in.select(Simd::splat(1), Simd::splat(0))).to_array();
+    (carries >> L) & 1 == 1
+}
+
+
+fn sub_lanes<const L: usize>(a: &mut [u64; L], b: &[u64; L]) -> bool {
+    let x = Simd::from_array(*a);
+    let y = Simd::from_array(*b);
+    let diff = x - y;
+    let g = x.simd_lt(y).to_bitmask() as u128;
+    let p = diff.simd_eq(Simd::splat(0)).to_bitmask() as u128;
+    let borrows = ((g << 1) + p) ^ p;
+    let borrow_in = Mask::<i64, L>::from_bitmask(borrows as u64);
+    *a = (diff - borrow_in.select(Simd::splat(1), Simd::splat(0))).to_array();
+    (borrows >> L) & 1 == 1
+}
+
+
+/// Schoolbook multiplication over `L` lanes of 32-bit halves of the digits.
+/// Every row `a * b[j]` is added to the accumulators of the columns
+/// `j..(j + L)`, the low and the high halves of the lane products are
+/// accumulated separately, so the columns do not overflow. Then the lowest
+/// column is complete, it leaves the accumulators and its carry is
+/// propagated by scalar code.
+#[cfg_attr(not(target_feature = "avx512f"), allow(dead_code))]
+fn mul_lanes<const L: usize>(a: &[u64], b: &[u64], res: &mut [u64]) {
+    let mask = Simd::splat(u32::MAX as u64);
+    let half = |x: &[u64], i: usize| (x[i >> 1] >> ((i & 1) << 5)) & (u32::MAX as u64);
+    let x = Simd::<u64, L>::from_array(std::array::from_fn(|i| half(a, i)));
+
+    // The lanes of hi are the columns shifted by one
+    let mut lo = Simd::<u64, L>::splat(0);
+    let mut hi = Simd::<u64, L>::splat(0);
+    // Enough columns for the product of `Bigi<16>`
+    let mut columns = [0; 64];
+    let mut hi0 = 0;
+    for (j, value) in columns[..L].iter_mut().enumerate() {
+        let p = x * Simd::splat(half(b, j));
+        lo += p & mask;
+        hi += p >> 32;
+        *value = lo[0] + hi0;
+        hi0 = hi[0];
+        lo = lo.shift_elements_left::<1>(0);
+        hi = hi.shift_elements_left::<1>(0);
+    }
+    let rest = lo + hi.shift_elements_right::<1>(0);
+    rest.copy_to_slice(&mut columns[L..(2 * L)]);
+    columns[L] += hi0;
+
+    let mut carry = 0;
+    for (digit, pair) in res.iter_mut().zip(columns.chunks_exact(2)) {
+        let t0 = pair[0] + carry;
+        let t1 = pair[1] + (t0 >> 32);
+        *digit = (t0 & (u32::MAX as u64)) | (t1 << 32);
+        carry = t1 >> 32;
+    }
+}
+
+
+fn cmp_lanes<const L: usize>(a: &[u64; L], b: &[u64; L]) -> cmp::Ordering {
+    let x = Simd::from_array(*a);
+    let y = Simd::from_array(*b);
+    let ne = x.simd_ne(y).to_bitmask();
+    if ne == 0 {
+        return cmp::Ordering::Equal;
+    }
+    // The highest different digit decides
+    let i = 63 - ne.leading_zeros() as usize;
+    a[i].cmp(&b[i])
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::{bigi, Bigi};
+    use super::*;
+    use test::Bencher;
+
+    #[test]
+    fn test_add_sub() {
+        let mut x = [u64::MAX; 8];
+        assert_eq!(add_assign(&mut x, &bigi![8; 1].digits), Some(true));
+        assert_eq!(x, [0; 8]);
+        assert_eq!(sub_assign(&mut x, &bigi![8; 1].digits), Some(true));
+        assert_eq!(x, [u64::MAX; 8]);
+        let mut x = bigi![8; u64::MAX, 5, u64::MAX, u64::MAX, 7].digits;
+        assert_eq!(add_assign(&mut x, &bigi![8; 1, 0, 1].digits), Some(false));
+        assert_eq!(x, bigi![8; 0, 6, 0, 0, 8].digits);
+        assert_eq!(sub_assign(&mut x, &bigi![8; 1, 0, 1].digits), Some(false));
+        assert_eq!(x, bigi![8; u64::MAX, 5, u64::MAX, u64::MAX, 7].digits);
+        assert_eq!(add_assign(&mut [0; 4], &[0; 4]), None);
+        assert_eq!(sub_assign(&mut [0; 4], &[0; 4]), None);
+
+        let mut rng = rand::thread_rng();
+        for bits in [0, 1, 64, 500, 512, 1000, 1024] {
+            let x = Bigi::<16>::gen_random(&mut rng, bits, false);
+            let mut y = Bigi::<16>::gen_random(&mut rng, bits, false);
+            // Long runs of carries and borrows
+            y.digits[3] = u64::MAX;
+            y.digits[4] = u64::MAX;
+            for (a, b) in [(x, y), (y, x), (x, x)] {
+                let mut z = a.digits;
+                add_assign(&mut z, &b.digits);
+                assert_eq!(Bigi { digits: z }, a.add_smaller(&b));
+                let mut z = a.digits;
+                sub_assign(&mut z, &b.digits);
+                assert_eq!(Bigi { digits: z }, a.sub_smaller(&b));
+            }
+        }
+    }
+
+    #[test]
+    fn test_cmp() {
+        let x = bigi![8; 1, 2, 3];
+        assert_eq!(cmp(&x.digits, &x.digits), Some(cmp::Ordering::Equal));
+        assert_eq!(cmp(&x.digits, &bigi![8; 2, 2, 3].digits), Some(cmp::Ordering::Less));
+        assert_eq!(cmp(&x.digits, &bigi![8; 5, 1, 3].digits), Some(cmp::Ordering::Greater));
+        let mut y = [0; 16];
+        y[15] = 1;
+        assert_eq!(cmp(&[0; 16], &y), Some(cmp::Ordering::Less));
+        assert_eq!(cmp(&y, &[0; 16]), Some(cmp::Ordering::Greater));
+        assert_eq!(cmp(&[0; 4], &[0; 4]), None);
+    }
+
+    #[test]
+    fn test_widening_mul() {
+        let x = [u64::MAX; 8];
+        let mut high = [u64::MAX; 8];
+        high[0] = u64::MAX - 1;
+        assert_eq!(widening_mul(&x, &x), Some([bigi![8; 1].digits, high]));
+        assert_eq!(widening_mul(&[0; 4], &[0; 4]), None);
+
+        let mut rng = rand::thread_rng();
+        for bits in [0, 1, 64, 500, 512] {
+            let x = Bigi::<8>::gen_random(&mut rng, bits, false);
+            let y = Bigi::<8>::gen_random(&mut rng, 512, false);
+            let (low, high) = x.widening_mul(&y);
+            assert_eq!(widening_mul(&x.digits, &y.digits), Some([low.digits, high.digits]));
+        }
+        for bits in [0, 1, 64, 1000, 1024] {
+            let x = Bigi::<16>::gen_random(&mut rng, bits, false);
+            let y = Bigi::<16>::gen_random(&mut rng, 1024, false);
+            let product = Bigi::<32>::from(&x) * &Bigi::<32>::from(&y);
+            let low = Bigi::<16>::from(&product);
+            let high = Bigi::<16>::from(&(product >> 1024));
+            assert_eq!(widening_mul(&x.digits, &y.digits), Some([low.digits, high.digits]));
+        }
+    }
+
+    #[bench]
+    fn bench_add_512(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let x = Bigi::<8>::gen_random(&mut rng, 512, false);
+        let y = Bigi::<8>::gen_random(&mut rng, 512, false);
+        bencher.iter(|| x + &y);
+    }
+
+    #[bench]
+    fn bench_widening_mul_512(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let x = Bigi::<8>::gen_random(&mut rng, 512, false);
+        let y = Bigi::<8>::gen_random(&mut rng, 512, false);
+        bencher.iter(|| widening_mul(&x.digits, &y.digits));
+    }
+
+    #[bench]
+    fn bench_widening_mul_1024(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let x = Bigi::<16>::gen_random(&mut rng, 1024, false);
+        let y = Bigi::<16>::gen_random(&mut rng, 1024, false);
+        bencher.iter(|| widening_mul(&x.digits, &y.digits));
+    }
+}
@@ -0,0 +1,138 @@
+//! This module implements **StreamingReducer** that reads a big-endian
+//! integer by chunks of bytes and calculates its remainder modulo `m`
+//! on the fly (Horner's rule with base `2^64`), so a long message can be
+//! mapped into Z_m without keeping the whole integer in memory.
+//!
+//! ```rust
+//! use bigi::{Bigi, StreamingReducer};
+//!
+//! let mut reducer = StreamingReducer::new(&Bigi::<4>::from(1000));
+//! reducer.update(&[0x01, 0x02]);
+//! reducer.update(&[0x03]);
+//! assert_eq!(reducer.finish(), Bigi::<4>::from(51));  // 0x010203 % 1000
+//! ```
+
+use std::io;
+use crate::base::Bigi;
+use crate::prime::reduce_wide;
+
+
+/// Remainder of a big-endian byte stream modulo `m`.
+#[derive(Debug, Clone, Copy)]
+pub struct StreamingReducer<const N: usize> {
+    m: Bigi<N>,
+    acc: Bigi<N>,
+    buffer: u64,
+    buffered: usize,
+}
+
+
+impl<const N: usize> StreamingReducer<N> {
+    /// Creates a reducer modulo `m` for an empty stream.
+    pub fn new(m: &Bigi<N>) -> Self {
+        assert!(!m.is_zero(), "Zero modulo");
+        Self { m: *m, acc: Bigi::<N>::new(), buffer: 0, buffered: 0 }
+    }
+
+    /// Appends the bytes to the stream. The bytes are collected into *u64*
+    /// words, so the reduction happens once per 8 bytes.
+    pub fn update(&mut self, bytes: &[u8]) {
+        for byte in bytes.iter() {
+            self.buffer = (self.buffer << 8) | (*byte as u64);
+            self.buffered += 1;
+            if self.buffered == 8 {
+                self.acc = self.push(self.buffer, 64);
+                self.buffer = 0;
+                self.buffered = 0;
+            }
+        }
+    }
+
+    /// Gets the remainder of the stream read so far. The stream can be
+    /// continued after that.
+    pub fn finish(&self) -> Bigi<N> {
+        if self.buffered > 0 {
+            self.push(self.buffer, self.buffered << 3)
+        } else {
+            self.acc
+        }
+    }
+
+    /// Calculates `(acc * 2^bits + value) % m` for `value < 2^bits`.
+    fn push(&self, value: u64, bits: usize) -> Bigi<N> {
+        let mut lo = self.acc << bits;
+        lo.digits[0] |= value;
+        let hi = self.acc >> (N * 64 - bits);
+        reduce_wide(&hi, &lo, &self.m)
+    }
+}
+
+
+impl<const N: usize> io::Write for StreamingReducer<N> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::bigi;
+    use super::*;
+    use test::Bencher;
+
+    #[test]
+    fn test_update() {
+        let m = bigi![4; 1000];
+        let reducer = StreamingReducer::new(&m);
+        assert_eq!(reducer.finish(), bigi![4; 0]);
+
+        let mut rng = rand::thread_rng();
+        for bits in [8, 64, 72, 256, 1000, 4096] {
+            let x = Bigi::<64>::gen_random(&mut rng, bits, false);
+            let m = Bigi::<4>::gen_random(&mut rng, 200, true);
+            let bytes: Vec<u8> = x.to_bytes()[..bits >> 3].iter().rev()
+                .copied().collect();
+            let expected = Bigi::<4>::from(&(x % &Bigi::<64>::from(&m)));
+
+            let mut reducer = StreamingReducer::new(&m);
+            for chunk in bytes.chunks(13) {
+                reducer.update(chunk);
+            }
+            assert_eq!(reducer.finish(), expected);
+
+            let mut reducer = StreamingReducer::new(&m);
+            io::Write::write_all(&mut reducer, &bytes).unwrap();
+            assert_eq!(reducer.finish(), expected);
+        }
+    }
+
+    #[test]
+    fn test_update_single_digit() {
+        let m = bigi![1; 1000003];
+        let mut reducer = StreamingReducer::new(&m);
+        reducer.update(&[0xff; 100]);
+        let mut expected = 0u128;
+        for _ in 0..100 {
+            expected = (expected * 256 + 255) % 1000003;
+        }
+        assert_eq!(reducer.finish(), bigi![1; expected as u64]);
+    }
+
+    #[bench]
+    fn bench_update_256_x1k(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let m = Bigi::<4>::gen_random(&mut rng, 256, true);
+        let bytes = [0xa5u8; 1024];
+        bencher.iter(|| {
+            let mut reducer = StreamingReducer::new(&m);
+            reducer.update(&bytes);
+            reducer.finish()
+        });
+    }
+}